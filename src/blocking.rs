@@ -0,0 +1,55 @@
+use std::future::Future;
+use std::pin::pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+/// Wakes the thread blocked in [`block_on_timeout`]
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+/// Drives `future` to completion on the current thread, parking the thread
+/// between polls
+///
+/// Returns `None` if `timeout` elapses before the future completes.
+///
+/// # Panics
+///
+/// Panics if called within an asynchronous execution context, like Tokio's blocking APIs
+pub(crate) fn block_on_timeout<F: Future>(
+    future: F,
+    timeout: Option<Duration>,
+) -> Option<F::Output> {
+    if tokio::runtime::Handle::try_current().is_ok() {
+        panic!("Cannot block the current thread from within a runtime. This happens because a function attempted to block the current thread while the thread is being used to drive asynchronous tasks.");
+    }
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut context = Context::from_waker(&waker);
+    let mut future = pin!(future);
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return Some(output);
+        }
+        match deadline {
+            Some(deadline) => {
+                let now = Instant::now();
+                if now >= deadline {
+                    return None;
+                }
+                thread::park_timeout(deadline - now);
+            }
+            None => thread::park(),
+        }
+    }
+}
@@ -1,169 +1,2830 @@
-use crate::error::{ReceiveError, RequestError, RespondError, SendError};
+use crate::blocking::block_on_timeout;
+use crate::clock::ClockSleep;
+use crate::dead_letter::{DeadLetter, DeadLetterReason, DeadLetterSender};
+#[cfg(feature = "tracing-error")]
+use crate::error::Traced;
+use crate::error::{
+    ReceiveError, RecvTimeoutError, RequestError, RespondError, RetainedError, SendError,
+    SendTimeoutError, TryRecvError, TrySendError,
+};
+use crate::pause::PauseState;
+use crate::retry::{retry, retry_if, RetryPolicy};
+use crate::serve::{Outcome, ServeReport, ServeReporter};
+use crate::sink::{RequestSenderSink, ResponseReceiverStream};
+use crate::state::{
+    CancelReason, ChannelState, Hook, RequestContext, RequestId, RequestState, SenderId,
+};
+use crate::{PauseHandle, Request};
 
-use tokio::sync::{mpsc, oneshot};
-use tokio::time::{timeout, Duration};
+use tokio::sync::{mpsc, oneshot, watch, Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::task::{self, JoinError, JoinHandle, JoinSet};
+use tokio::time::{Duration, Instant};
+#[cfg(feature = "tokio-util")]
+use tokio_util::sync::CancellationToken;
 
 use futures_core::Stream;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use futures_util::future::{select, Either, Shared};
+use futures_util::stream::StreamExt;
+use futures_util::FutureExt;
+use std::collections::VecDeque;
+use std::fmt;
+use std::future::{poll_fn, Future, IntoFuture};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::thread;
 
 /// The internal data sent in the MPSC request channel, a tuple that contains the request and the oneshot response channel responder
 pub type Payload<Req, Res> = (Req, Responder<Res>);
 
+/// Access the metadata of a [`Payload`] without taking it apart
+pub trait PayloadExt {
+    /// Returns the id the request was assigned when it was sent, see
+    /// [`Responder::request_id()`]
+    fn id(&self) -> Option<RequestId>;
+
+    /// Returns the context the request was sent with, see [`Responder::context()`]
+    fn context(&self) -> Option<&RequestContext>;
+
+    /// Returns the sender the request came from, see [`Responder::sender_id()`]
+    fn sender_id(&self) -> Option<&SenderId>;
+}
+
+impl<Req, Res> PayloadExt for Payload<Req, Res> {
+    fn id(&self) -> Option<RequestId> {
+        self.1.request_id()
+    }
+
+    fn context(&self) -> Option<&RequestContext> {
+        self.1.context()
+    }
+
+    fn sender_id(&self) -> Option<&SenderId> {
+        self.1.sender_id()
+    }
+}
+
 /// Send values to the associated [`RequestReceiver`].
 #[derive(Debug)]
 pub struct RequestSender<Req, Res> {
     request_sender: mpsc::Sender<Payload<Req, Res>>,
-    timeout_duration: Option<Duration>,
+    channel: Arc<ChannelState>,
+    pub(crate) rejected: Option<RejectedHandler<Req>>,
+    /// Limits the number of requests of this sender and its clones waiting for a
+    /// response, see [`with_quota()`](Self::with_quota())
+    quota: Option<Arc<Semaphore>>,
+    id: Arc<SenderId>,
+}
+
+/// A sender that does not keep the channel open
+///
+/// The [`RequestReceiver`] sees the channel closed once all the [`RequestSender`] instances are
+/// dropped, even if some `WeakRequestSender` instances are still alive.
+///
+/// Instances are created by calling [`RequestSender::downgrade()`]
+#[derive(Debug)]
+pub struct WeakRequestSender<Req, Res> {
+    request_sender: mpsc::WeakSender<Payload<Req, Res>>,
+    channel: Arc<ChannelState>,
+    rejected: Option<RejectedHandler<Req>>,
+    quota: Option<Arc<Semaphore>>,
+    id: Arc<SenderId>,
+}
+
+/// Receive requests values from the associated [`RequestSender`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct RequestReceiver<Req, Res> {
+    request_receiver: mpsc::Receiver<Payload<Req, Res>>,
+    request_sender: mpsc::WeakSender<Payload<Req, Res>>,
+    requeued_front: VecDeque<Payload<Req, Res>>,
+    requeued_back: VecDeque<Payload<Req, Res>>,
+    peeked: Option<Payload<Req, Res>>,
+    pub(crate) hooks: ReceiverHooks<Req, Res>,
+    pause: Arc<PauseState>,
+    channel: Arc<ChannelState>,
+}
+
+/// Called with the responses that arrive after the response timeout, see
+/// [`ChannelBuilder::on_late_response()`](crate::ChannelBuilder::on_late_response())
+pub(crate) type LateResponseHandler<Res> = Hook<dyn Fn(RequestId, &Res) + Send + Sync>;
+
+/// Called with the id, the send instant and the drop instant of a request whose
+/// responder is dropped without responding
+pub(crate) type UnansweredHandler = Hook<dyn Fn(RequestId, Instant, Instant) + Send + Sync>;
+
+/// Called with the requests rejected by the admission controller and the instant
+/// they are rejected at
+pub(crate) type RejectedHandler<Req> = Hook<dyn Fn(&Req, Instant) + Send + Sync>;
+
+/// The handlers a receiver applies to the requests it takes out of the queue
+#[derive(Debug)]
+pub(crate) struct ReceiverHooks<Req, Res> {
+    pub(crate) late_response: Option<LateResponseHandler<Res>>,
+    pub(crate) dead_letters: Option<DeadLetterSender<Req>>,
+    pub(crate) unanswered: Option<UnansweredHandler>,
+}
+
+impl<Req, Res> Default for ReceiverHooks<Req, Res> {
+    fn default() -> Self {
+        ReceiverHooks {
+            late_response: None,
+            dead_letters: None,
+            unanswered: None,
+        }
+    }
+}
+
+/// Send values back to the [`RequestSender`] or [`RequestReceiver`]
+///
+/// Instances are created by calling [`RequestSender::send_receive()`] or [`RequestSender::send()`].
+/// The unbounded channel uses the same type, see [`UnboundedResponder`](crate::unbounded::UnboundedResponder).
+#[derive(Debug)]
+pub struct Responder<Res> {
+    response_sender: Option<oneshot::Sender<Res>>,
+    state: Option<Arc<RequestState>>,
+    pub(crate) sender: Option<Arc<SenderId>>,
+    pub(crate) attempt: usize,
+    late_response: Option<LateResponseHandler<Res>>,
+    unanswered: Option<UnansweredHandler>,
+    /// The outstanding requests permits of a request sent with
+    /// [`RequestSender::send_forget()`], released once the responder is dropped
+    outstanding: Option<Box<[OwnedSemaphorePermit]>>,
+}
+
+/// Receive responses from a [`Responder`]
+///
+/// Instances are created by calling [`RequestSender::send_receive()`] or [`RequestSender::send()`]
+#[derive(Debug)]
+pub struct ResponseReceiver<Res> {
+    pub(crate) response_receiver: Option<oneshot::Receiver<Res>>,
+    pub(crate) state: Arc<RequestState>,
+}
+
+/// Permit to send one request over the channel, without waiting for capacity
+///
+/// Instances are created by calling [`RequestSender::reserve()`]
+#[derive(Debug)]
+pub struct Permit<'a, Req, Res> {
+    permit: mpsc::Permit<'a, Payload<Req, Res>>,
+    channel: Arc<ChannelState>,
+    outstanding: Vec<OwnedSemaphorePermit>,
+    sender: Arc<SenderId>,
+}
+
+/// Owned permit to send one request over the channel, without waiting for capacity
+///
+/// Instances are created by calling [`RequestSender::reserve_owned()`]
+#[derive(Debug)]
+pub struct OwnedPermit<Req, Res> {
+    permit: mpsc::OwnedPermit<Payload<Req, Res>>,
+    channel: Arc<ChannelState>,
+    outstanding: Vec<OwnedSemaphorePermit>,
+    sender: Arc<SenderId>,
+}
+
+impl<Req, Res> RequestSender<Req, Res> {
+    fn new(request_sender: mpsc::Sender<Payload<Req, Res>>, channel: Arc<ChannelState>) -> Self {
+        RequestSender {
+            request_sender,
+            id: channel.next_sender_id(None),
+            channel,
+            rejected: None,
+            quota: None,
+        }
+    }
+
+    /// Marks the payload as sent by this sender
+    fn stamp(&self, payload: &mut Payload<Req, Res>) {
+        payload.1.sender = Some(self.id.clone());
+    }
+
+    /// Send a request over the MPSC channel, open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    ///
+    /// This call waits if the request channel is full. It does not wait for a response
+    ///
+    /// If the channel was built with a [send timeout](crate::ChannelBuilder::send_timeout()),
+    /// the request is also handed back when the channel stays full for that long
+    ///
+    /// If the channel has an [admission controller](crate::ChannelBuilder::admission()),
+    /// the request is handed back right away while the channel is overloaded. Use
+    /// [`send_or_reject()`](Self::send_or_reject()) to tell these failures apart.
+    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_admitted(request, |request| new_payload(request, &self.channel))
+            .await
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Send a request over the MPSC channel, open the response channel, telling
+    /// why the request is handed back if it cannot be sent
+    ///
+    /// It waits like [`send()`](Self::send()), but fails with [`RequestError::Rejected`]
+    /// while the [admission controller](crate::ChannelBuilder::admission()) sheds
+    /// requests, with [`RequestError::SendTimeoutError`] once the
+    /// [send timeout](crate::ChannelBuilder::send_timeout()) elapses, and with
+    /// [`RequestError::SendError`] if the channel is closed.
+    pub async fn send_or_reject(
+        &self,
+        request: Req,
+    ) -> Result<ResponseReceiver<Res>, RequestError<Req>> {
+        self.send_admitted(request, |request| new_payload(request, &self.channel))
+            .await
+            .map_err(RequestError::from)
+    }
+
+    /// Sends the payload built from `request` by `payload`, unless the admission
+    /// controller sheds the request
+    async fn send_admitted(
+        &self,
+        request: Req,
+        payload: impl FnOnce(Req) -> (Payload<Req, Res>, ResponseReceiver<Res>),
+    ) -> Result<ResponseReceiver<Res>, SendTimeoutError<Req>> {
+        if !self.admits() {
+            return Err(SendTimeoutError::Rejected(self.reject(request)));
+        }
+        self.send_payload(payload(request)).await
+    }
+
+    /// Waits until the quota of this sender and the outstanding requests limit of the
+    /// channel let a new request through, and returns the permits to hold until the
+    /// request is finished
+    async fn acquire_outstanding(&self) -> Vec<OwnedSemaphorePermit> {
+        let quota = match self.quota.clone() {
+            Some(quota) => quota.acquire_owned().await.ok(),
+            None => None,
+        };
+        let channel = self.channel.acquire_outstanding().await;
+        quota.into_iter().chain(channel).collect()
+    }
+
+    /// Returns the permits to hold until a new request is finished without waiting,
+    /// or `Err` if the quota of this sender or the limit of the channel is reached
+    fn try_acquire_outstanding(&self) -> Result<Vec<OwnedSemaphorePermit>, ()> {
+        let quota = match &self.quota {
+            Some(quota) => Some(quota.clone().try_acquire_owned().map_err(drop)?),
+            None => None,
+        };
+        let channel = self.channel.try_acquire_outstanding()?;
+        Ok(quota.into_iter().chain(channel).collect())
+    }
+
+    /// Checks the queue against the thresholds of the admission controller
+    fn admits(&self) -> bool {
+        let depth = self.request_sender.max_capacity() - self.request_sender.capacity();
+        self.channel.admits(depth)
+    }
+
+    /// Hands a request shed by the admission controller to the rejected handler of
+    /// the channel, like its dead-letter queue, and returns it
+    fn reject(&self, request: Req) -> Req {
+        if let Some(rejected) = &self.rejected {
+            (rejected.0)(&request, self.channel.now());
+        }
+        request
+    }
+
+    /// Send a request over the MPSC channel with its own time-to-live, open the response channel
+    ///
+    /// If the request is still queued once `ttl` elapses, the receiver skips it and
+    /// the response receiver fails with [`ReceiveError::Expired`]. This overrides the
+    /// [time-to-live](crate::ChannelBuilder::ttl()) of the channel. It fails like
+    /// [`send()`](Self::send()) otherwise.
+    pub async fn send_with_ttl(
+        &self,
+        request: Req,
+        ttl: Duration,
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_admitted(request, |request| {
+            new_payload_with_ttl(request, &self.channel, Some(ttl))
+        })
+        .await
+        .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Send a request over the MPSC channel with a user-provided context, open the
+    /// response channel
+    ///
+    /// The context is available to the receiving side from
+    /// [`Responder::context()`], for example to correlate logs across the channel.
+    /// It fails like [`send()`](Self::send()).
+    pub async fn send_with_context(
+        &self,
+        request: Req,
+        context: RequestContext,
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_admitted(request, |request| {
+            new_payload_with_context(request, &self.channel, context)
+        })
+        .await
+        .map_err(|err| SendError(err.into_inner()))
+    }
+
+    async fn send_payload(
+        &self,
+        (payload, receiver): (Payload<Req, Res>, ResponseReceiver<Res>),
+    ) -> Result<ResponseReceiver<Res>, SendTimeoutError<Req>> {
+        self.send_holding(payload, |_, outstanding| receiver.state.hold(outstanding))
+            .await?;
+        Ok(receiver)
+    }
+
+    /// Sends a payload once the rate limit, the quota of this sender and the outstanding
+    /// requests limit of the channel let it through, handing the permits of the quota
+    /// and of the limit to `hold`, to keep until the request is finished
+    async fn send_holding(
+        &self,
+        mut payload: Payload<Req, Res>,
+        hold: impl FnOnce(&mut Payload<Req, Res>, Vec<OwnedSemaphorePermit>),
+    ) -> Result<(), SendTimeoutError<Req>> {
+        if self.channel.is_closing() {
+            return Err(SendTimeoutError::Closed(payload.0));
+        }
+        self.stamp(&mut payload);
+        let deadline = self
+            .channel
+            .send_timeout
+            .map(|duration| self.channel.now() + duration);
+        if !self.channel.pace(deadline).await {
+            return Err(SendTimeoutError::Timeout(payload.0));
+        }
+        let outstanding = match deadline {
+            Some(deadline) => {
+                match self
+                    .channel
+                    .timeout_at(deadline, self.acquire_outstanding())
+                    .await
+                {
+                    Some(outstanding) => outstanding,
+                    None => return Err(SendTimeoutError::Timeout(payload.0)),
+                }
+            }
+            None => self.acquire_outstanding().await,
+        };
+        hold(&mut payload, outstanding);
+        match reserve_until(&self.channel, deadline, self.request_sender.reserve()).await {
+            Ok(permit) => permit.send(payload),
+            Err(SendTimeoutError::Timeout(())) => return Err(SendTimeoutError::Timeout(payload.0)),
+            Err(..) => return Err(SendTimeoutError::Closed(payload.0)),
+        }
+        self.channel.add_depth(1);
+        Ok(())
+    }
+
+    /// Waits for the rate limit, the quota of this sender and the outstanding requests
+    /// limit of the channel to let a reserved slot through, returning the deadline
+    /// set by the send timeout and the permits to hold until the request is finished
+    async fn pace_reserve(
+        &self,
+    ) -> Result<(Option<Instant>, Vec<OwnedSemaphorePermit>), SendError<()>> {
+        if self.channel.is_closing() || !self.admits() {
+            return Err(SendError(()));
+        }
+        let deadline = self
+            .channel
+            .send_timeout
+            .map(|duration| self.channel.now() + duration);
+        if !self.channel.pace(deadline).await {
+            return Err(SendError(()));
+        }
+        let outstanding = match deadline {
+            Some(deadline) => self
+                .channel
+                .timeout_at(deadline, self.acquire_outstanding())
+                .await
+                .ok_or(SendError(()))?,
+            None => self.acquire_outstanding().await,
+        };
+        Ok((deadline, outstanding))
+    }
+
+    /// Send a request over the MPSC channel without opening a response channel
+    ///
+    /// Use this for requests that never need a response. The [`Responder`] of the
+    /// request discards the response, see [`Responder::expects_response()`].
+    /// This call waits if the request channel is full, and is subject to the
+    /// admission controller, the quota of this sender and the outstanding requests
+    /// limit of the channel, and fails like [`send()`](Self::send()). The request
+    /// stays outstanding until its responder is dropped.
+    pub async fn send_forget(&self, request: Req) -> Result<(), SendError<Req>> {
+        if !self.admits() {
+            return Err(SendError(self.reject(request)));
+        }
+        let payload = (request, Responder::forgotten());
+        self.send_holding(payload, |payload, outstanding| {
+            payload.1.outstanding = Some(outstanding.into())
+        })
+        .await
+        .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Attempts to immediately send a request over the MPSC channel, open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    ///
+    /// This call does not wait. It fails with [`TrySendError::Full`] if the request
+    /// channel is full, [`TrySendError::Rejected`] if the admission controller sheds
+    /// the request, or [`TrySendError::Closed`] if the receiver has been dropped
+    pub fn try_send(&self, request: Req) -> Result<ResponseReceiver<Res>, TrySendError<Req>> {
+        if self.channel.is_closing() {
+            return Err(TrySendError::Closed(request));
+        }
+        if !self.admits() {
+            return Err(TrySendError::Rejected(self.reject(request)));
+        }
+        let Ok(outstanding) = self.try_acquire_outstanding() else {
+            return Err(TrySendError::Full(request));
+        };
+        // The slot is reserved before a token is taken, so a full or closed queue
+        // does not use up the rate limit
+        let permit = match self.request_sender.try_reserve() {
+            Ok(permit) => permit,
+            Err(mpsc::error::TrySendError::Full(())) => return Err(TrySendError::Full(request)),
+            Err(mpsc::error::TrySendError::Closed(())) => {
+                return Err(TrySendError::Closed(request))
+            }
+        };
+        if !self.channel.try_pace() {
+            return Err(TrySendError::Full(request));
+        }
+        let (mut payload, receiver) = new_payload(request, &self.channel);
+        self.stamp(&mut payload);
+        receiver.state.hold(outstanding);
+        permit.send(payload);
+        self.channel.add_depth(1);
+        Ok(receiver)
+    }
+
+    /// Puts a payload taken from the receiver back at the end of the queue, with its
+    /// original responder
+    ///
+    /// Use this from handlers that cannot process a request yet but have no access
+    /// to the receiver, like tasks spawned for every request or handlers consuming a
+    /// [`RequestReceiverStream`]. The attempt counter of the responder is incremented.
+    /// The payload is handed back if the queue is full or closed.
+    pub fn requeue(
+        &self,
+        mut payload: Payload<Req, Res>,
+    ) -> Result<(), TrySendError<Payload<Req, Res>>> {
+        if self.channel.is_closing() {
+            return Err(TrySendError::Closed(payload));
+        }
+        payload.1.attempt += 1;
+        self.request_sender.try_send(payload).map_err(|err| {
+            let mut err = TrySendError::from(err);
+            let (TrySendError::Full(payload)
+            | TrySendError::Closed(payload)
+            | TrySendError::Rejected(payload)) = &mut err;
+            payload.1.attempt -= 1;
+            err
+        })?;
+        self.channel.add_depth(1);
+        Ok(())
+    }
+
+    /// Send a request over the MPSC channel, waiting at most `duration` for capacity
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    ///
+    /// This only bounds the time spent waiting for the request channel. The response
+    /// is still subject to the timeout of the channel, if any. The request is handed
+    /// back in a [`SendTimeoutError::Rejected`] if the admission controller sheds it.
+    pub async fn send_timeout(
+        &self,
+        request: Req,
+        duration: Duration,
+    ) -> Result<ResponseReceiver<Res>, SendTimeoutError<Req>> {
+        if self.channel.is_closing() {
+            return Err(SendTimeoutError::Closed(request));
+        }
+        if !self.admits() {
+            return Err(SendTimeoutError::Rejected(self.reject(request)));
+        }
+        let deadline = self.channel.now() + duration;
+        if !self.channel.pace(Some(deadline)).await {
+            return Err(SendTimeoutError::Timeout(request));
+        }
+        let Some(outstanding) = self
+            .channel
+            .timeout_at(deadline, self.acquire_outstanding())
+            .await
+        else {
+            return Err(SendTimeoutError::Timeout(request));
+        };
+        let (mut payload, receiver) = new_payload(request, &self.channel);
+        self.stamp(&mut payload);
+        receiver.state.hold(outstanding);
+        match reserve_until(&self.channel, Some(deadline), self.request_sender.reserve()).await {
+            Ok(permit) => permit.send(payload),
+            Err(SendTimeoutError::Timeout(())) => return Err(SendTimeoutError::Timeout(payload.0)),
+            Err(..) => return Err(SendTimeoutError::Closed(payload.0)),
+        }
+        self.channel.add_depth(1);
+        Ok(receiver)
+    }
+
+    /// Waits for capacity in the request channel and reserves a slot for one request
+    ///
+    /// This applies backpressure before the request is constructed. The slot is
+    /// released if the [`Permit`] is dropped without sending. It fails right away
+    /// while the admission controller sheds requests, and once the send timeout of
+    /// the channel elapses if it has one, like [`send()`](Self::send()).
+    pub async fn reserve(&self) -> Result<Permit<'_, Req, Res>, SendError<()>> {
+        let (deadline, outstanding) = self.pace_reserve().await?;
+        let permit = reserve_until(&self.channel, deadline, self.request_sender.reserve())
+            .await
+            .map_err(|_| SendError(()))?;
+        Ok(Permit {
+            permit,
+            channel: self.channel.clone(),
+            outstanding,
+            sender: self.id.clone(),
+        })
+    }
+
+    /// Waits for capacity in the request channel and reserves a slot for one request,
+    /// consuming the sender
+    ///
+    /// Unlike [`reserve()`](Self::reserve()), the returned [`OwnedPermit`] does not
+    /// borrow the sender, so it can be moved into another task.
+    pub async fn reserve_owned(self) -> Result<OwnedPermit<Req, Res>, SendError<()>> {
+        let (deadline, outstanding) = self.pace_reserve().await?;
+        let channel = self.channel.clone();
+        let sender = self.id.clone();
+        let permit = reserve_until(&channel, deadline, self.request_sender.reserve_owned())
+            .await
+            .map_err(|_| SendError(()))?;
+        Ok(OwnedPermit {
+            permit,
+            channel,
+            outstanding,
+            sender,
+        })
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it
+    ///
+    /// This call waits if the request channel is full, and while waiting for the response.
+    /// It fails to send like [`send_or_reject()`](Self::send_or_reject())
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send_or_reject(request).await?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// capturing a span trace with the error if it fails
+    ///
+    /// The span trace of a failed response is captured in the span of the request.
+    #[cfg(feature = "tracing-error")]
+    pub async fn send_receive_traced(
+        &self,
+        request: Req,
+    ) -> Result<Res, Traced<RequestError<Req>>> {
+        let mut receiver = self.send_or_reject(request).await.map_err(Traced::new)?;
+        receiver
+            .recv_traced()
+            .await
+            .map_err(|err| err.map(RequestError::from))
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// handing the part of the request picked by `retain` back with
+    /// [`RequestError::RecvTimeoutError`] if the response timeout elapses
+    ///
+    /// `retain` is called before the request is sent, and what it returns is kept
+    /// until the response comes, so a timed out request can be rebuilt and retried.
+    /// Pass `Clone::clone` to retain the whole request. The other errors come with
+    /// [`RetainedError::retained`] set to `None`, a request that fails to send is
+    /// already handed back in the error.
+    pub async fn send_receive_retained<R, F>(
+        &self,
+        request: Req,
+        retain: F,
+    ) -> Result<Res, RetainedError<Req, R>>
+    where
+        F: FnOnce(&Req) -> R,
+    {
+        let retained = retain(&request);
+        self.send_receive(request)
+            .await
+            .map_err(|error| match error {
+                RequestError::RecvTimeoutError(..) => RetainedError {
+                    error,
+                    retained: Some(retained),
+                },
+                error => RetainedError {
+                    error,
+                    retained: None,
+                },
+            })
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// sending a second copy of the request if no response comes within `hedge_delay`
+    ///
+    /// Whichever copy is answered first wins, and the other one is cancelled, see
+    /// [`ResponseReceiver::cancel()`]. If the first copy fails before `hedge_delay`,
+    /// the second one is sent right away. An error is only returned once both copies
+    /// failed, or if the first copy cannot be sent.
+    pub async fn send_receive_hedged(
+        &self,
+        request: Req,
+        hedge_delay: Duration,
+    ) -> Result<Res, RequestError<Req>>
+    where
+        Req: Clone,
+    {
+        let mut first = self.send_or_reject(request.clone()).await?;
+        let hedge_at = self.channel.now() + hedge_delay;
+        let early = match select(pin!(first.recv()), self.channel.sleep_until(hedge_at)).await {
+            Either::Left((result, _)) => Some(result),
+            Either::Right(..) => None,
+        };
+        match early {
+            Some(Ok(response)) => return Ok(response),
+            Some(Err(..)) => {
+                let mut second = self.send_or_reject(request).await?;
+                return second.recv().await.map_err(|err| err.into());
+            }
+            None => {}
+        }
+        let mut second = match self.send(request).await {
+            Ok(second) => second,
+            Err(..) => return first.recv().await.map_err(|err| err.into()),
+        };
+        let result = match select(pin!(first.recv()), pin!(second.recv())).await {
+            Either::Left((Ok(response), _)) | Either::Right((Ok(response), _)) => Ok(response),
+            Either::Left((Err(..), other)) | Either::Right((Err(..), other)) => other.await,
+        };
+        first.cancel();
+        second.cancel();
+        result.map_err(|err| err.into())
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// sending the request again as the [`RetryPolicy`] allows when no response comes
+    ///
+    /// Every attempt sends a clone of `request`.
+    pub async fn send_receive_with_retry(
+        &self,
+        request: Req,
+        policy: &RetryPolicy,
+    ) -> Result<Res, RequestError<Req>>
+    where
+        Req: Clone,
+    {
+        retry(request, policy, |request| self.send_receive(request)).await
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// using `duration` as the response timeout instead of the one of the channel
+    pub async fn send_receive_timeout(
+        &self,
+        request: Req,
+        duration: Duration,
+    ) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send_or_reject(request).await?;
+        receiver.set_timeout(Some(duration));
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// giving up as soon as `token` is cancelled
+    ///
+    /// If the token fires while the request is still queued, the receiver never sees
+    /// it. If the handler is already processing it, its [`Responder`] is closed, just like
+    /// with [`cancel()`](Self::cancel()). Either way it fails with [`RequestError::Cancelled`].
+    #[cfg(feature = "tokio-util")]
+    pub async fn send_receive_with_token(
+        &self,
+        request: Req,
+        token: &CancellationToken,
+    ) -> Result<Res, RequestError<Req>> {
+        let mut receiver =
+            match select(pin!(self.send_or_reject(request)), pin!(token.cancelled())).await {
+                Either::Left((receiver, _)) => receiver?,
+                Either::Right(..) => return Err(RequestError::Cancelled),
+            };
+        receiver
+            .recv_with_token(token)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Blocking send to call outside of asynchronous contexts.
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    ///
+    /// Like [`send()`](Self::send()), it hands the request back once the
+    /// [send timeout](crate::ChannelBuilder::send_timeout()) of the channel elapses.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn blocking_send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.blocking_enqueue(request)
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Sends a request from a blocking thread, telling why it is handed back if it
+    /// cannot be sent
+    fn blocking_enqueue(
+        &self,
+        request: Req,
+    ) -> Result<ResponseReceiver<Res>, SendTimeoutError<Req>> {
+        if self.channel.is_closing() {
+            return Err(SendTimeoutError::Closed(request));
+        }
+        if !self.admits() {
+            return Err(SendTimeoutError::Rejected(self.reject(request)));
+        }
+        let deadline = self
+            .channel
+            .send_timeout
+            .map(|duration| self.channel.now() + duration);
+        let remaining =
+            || deadline.map(|deadline| deadline.saturating_duration_since(self.channel.now()));
+        if !self.channel.blocking_pace(deadline) {
+            return Err(SendTimeoutError::Timeout(request));
+        }
+        let outstanding = match (&self.channel.max_outstanding, &self.quota) {
+            (None, None) => Vec::new(),
+            _ => match block_on_timeout(self.acquire_outstanding(), remaining()) {
+                Some(outstanding) => outstanding,
+                None => return Err(SendTimeoutError::Timeout(request)),
+            },
+        };
+        let (mut payload, receiver) = new_payload(request, &self.channel);
+        self.stamp(&mut payload);
+        receiver.state.hold(outstanding);
+        match block_on_timeout(self.request_sender.reserve(), remaining()) {
+            Some(Ok(permit)) => permit.send(payload),
+            Some(Err(..)) => return Err(SendTimeoutError::Closed(payload.0)),
+            None => return Err(SendTimeoutError::Timeout(payload.0)),
+        }
+        self.channel.add_depth(1);
+        Ok(receiver)
+    }
+
+    /// Blocking version of [`send_receive()`](Self::send_receive()) to call outside of
+    /// asynchronous contexts.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn blocking_send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.blocking_enqueue(request)?;
+        receiver.blocking_recv().map_err(|err| err.into())
+    }
+
+    /// Sends every request of the `requests` stream and returns a stream of their
+    /// responses, in the same order as the requests
+    ///
+    /// Up to [`max_capacity()`](Self::max_capacity()) requests are in flight at
+    /// once, so the requests are pipelined while the channel still applies backpressure.
+    pub fn send_all<'a, S>(
+        &'a self,
+        requests: S,
+    ) -> impl Stream<Item = Result<Res, RequestError<Req>>> + 'a
+    where
+        S: Stream<Item = Req> + 'a,
+        Req: 'a,
+        Res: 'a,
+    {
+        requests
+            .map(move |request| self.send_receive(request))
+            .buffered(self.max_capacity())
+    }
+
+    /// Sends the requests of the `requests` stream, keeping up to `concurrency` of
+    /// them in flight, and returns a stream of their responses as they complete
+    ///
+    /// Every response is paired with the index of its request in `requests`, since
+    /// the responses are not yielded in request order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `concurrency` is 0
+    pub fn send_receive_unordered<'a, S>(
+        &'a self,
+        requests: S,
+        concurrency: usize,
+    ) -> impl Stream<Item = (usize, Result<Res, RequestError<Req>>)> + 'a
+    where
+        S: Stream<Item = Req> + 'a,
+        Req: 'a,
+        Res: 'a,
+    {
+        assert!(concurrency > 0, "the concurrency must be greater than 0");
+        requests
+            .enumerate()
+            .map(move |(index, request)| async move { (index, self.send_receive(request).await) })
+            .buffer_unordered(concurrency)
+    }
+
+    /// Returns the name given to the channel with [`ChannelBuilder::name()`](crate::ChannelBuilder::name())
+    pub fn name(&self) -> Option<&str> {
+        self.channel.name.as_deref()
+    }
+
+    /// Checks if the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.channel.is_closing() || self.request_sender.is_closed()
+    }
+
+    /// Cancels the request with the given id, sent by this sender or its clones
+    ///
+    /// A request that is still queued is skipped by the receiver. The handler of a
+    /// request that is being processed sees its [`Responder`] closed, and its response
+    /// is discarded. Either way the [`ResponseReceiver`] fails with
+    /// [`ReceiveError::Cancelled`]. Returns `false` if the request is unknown or
+    /// already finished, or if the channel was not built with
+    /// [`ChannelBuilder::cancellable()`](crate::ChannelBuilder::cancellable()).
+    pub fn cancel(&self, id: RequestId) -> bool {
+        self.channel.cancel(id)
+    }
+
+    /// Closes the channel for this sender and all its clones, without dropping them
+    ///
+    /// The following sends fail as if the receiver was dropped. The receiver still
+    /// gets the requests that were queued before, then sees the channel closed.
+    pub fn close_channel(&self) {
+        self.channel.close();
+    }
+
+    /// Waits until the receiver is dropped or closed
+    ///
+    /// After [`close_channel()`](Self::close_channel()), this resolves once the
+    /// receiver sees the channel closed.
+    pub async fn closed(&self) {
+        self.request_sender.closed().await
+    }
+
+    /// Creates a clone of the sender that may only have `quota` requests waiting for
+    /// a response at once, independently of the capacity of the channel
+    ///
+    /// Give every tenant of a shared channel a sender with a quota, so none of them
+    /// can monopolize the queue. Beyond the quota, [`send()`](Self::send()) waits
+    /// until one of the requests of the sender is finished, and [`try_send()`](Self::try_send())
+    /// fails with [`TrySendError::Full`]. A request stops counting once it is
+    /// responded to, or once the requesting side gives up on it. The clones of the
+    /// returned sender share its quota, which replaces the quota of this sender.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (tx, _rx) = bmrng::channel::<i32, i32>(16);
+    ///     let tenant = tx.with_quota(1);
+    ///     let _queued = tenant.send(1).await.unwrap();
+    ///     assert!(tenant.try_send(2).is_err());
+    ///     assert!(tx.try_send(3).is_ok());
+    /// }
+    /// ```
+    pub fn with_quota(&self, quota: usize) -> Self {
+        RequestSender {
+            quota: Some(Arc::new(Semaphore::new(quota))),
+            ..self.clone()
+        }
+    }
+
+    /// Creates a clone of the sender whose requests carry the given label in their
+    /// [`SenderId`], see [`Responder::sender_id()`]
+    ///
+    /// The clones of the returned sender keep the label.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    ///     let tenant = tx.labeled("tenant-a");
+    ///     let _response = tenant.send(1).await.unwrap();
+    ///     let (_, responder) = rx.recv().await.unwrap();
+    ///     let sender = responder.sender_id().unwrap();
+    ///     assert_eq!(sender.label(), Some("tenant-a"));
+    ///     assert_eq!(sender, tenant.id());
+    /// }
+    /// ```
+    pub fn labeled(&self, label: impl Into<String>) -> Self {
+        let mut sender = self.clone();
+        sender.id = sender.id.with_label(label.into());
+        sender
+    }
+
+    /// Returns the id of this sender, carried by the requests it sends
+    pub fn id(&self) -> &SenderId {
+        &self.id
+    }
+
+    /// Returns the number of requests this sender and its clones can still send
+    /// before their quota is reached, or `None` if the sender has no quota
+    pub fn available_quota(&self) -> Option<usize> {
+        self.quota.as_ref().map(|quota| quota.available_permits())
+    }
+
+    /// Returns the number of requests sent by this sender or its clones that are
+    /// still waiting for a response
+    ///
+    /// A request stops being counted once it is responded to, or once the
+    /// requesting side times out, cancels or drops its [`ResponseReceiver`].
+    pub fn pending_responses(&self) -> usize {
+        self.channel.in_flight()
+    }
+
+    /// Waits until every request sent by this sender or its clones has been
+    /// responded to, timed out or dropped
+    pub async fn await_idle(&self) {
+        self.channel.idle().await
+    }
+
+    /// Returns the number of requests that are queued or have a reserved slot in the channel
+    pub fn len(&self) -> usize {
+        self.max_capacity() - self.capacity()
+    }
+
+    /// Returns `true` if no requests are queued and no slots are reserved in the channel
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a watch receiver that sees the number of queued requests change
+    ///
+    /// Use it to shed load or scale the workers as the queue grows, without polling
+    /// [`len()`](Self::len()). The depth counts the requests sent to the channel
+    /// until the receiver takes them out of the queue, but not the reserved slots.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    ///     let mut depth = tx.watch_depth();
+    ///     let _response = tx.send(1).await.unwrap();
+    ///     depth.changed().await.unwrap();
+    ///     assert_eq!(*depth.borrow_and_update(), 1);
+    ///
+    ///     let _request = rx.recv().await.unwrap();
+    ///     assert_eq!(*depth.borrow_and_update(), 0);
+    /// }
+    /// ```
+    pub fn watch_depth(&self) -> watch::Receiver<usize> {
+        self.channel.watch_depth()
+    }
+
+    /// Returns the number of requests that can be sent before the channel is full
+    pub fn capacity(&self) -> usize {
+        self.request_sender.capacity()
+    }
+
+    /// Returns the buffer capacity the channel was created with
+    pub fn max_capacity(&self) -> usize {
+        self.request_sender.max_capacity()
+    }
+
+    /// Converts the sender into a [`RequestSenderSink`], so a [`Stream`] of requests
+    /// can be forwarded into the channel
+    ///
+    /// The [`ResponseReceiver`] of every request is yielded by the returned
+    /// [`ResponseReceiverStream`].
+    pub fn into_sink(self) -> (RequestSenderSink<Req, Res>, ResponseReceiverStream<Res>)
+    where
+        Req: Send + 'static,
+        Res: Send + 'static,
+    {
+        RequestSenderSink::new(self)
+    }
+
+    /// Converts the sender into a [`WeakRequestSender`] that does not keep the channel open
+    pub fn downgrade(&self) -> WeakRequestSender<Req, Res> {
+        WeakRequestSender {
+            request_sender: self.request_sender.downgrade(),
+            channel: self.channel.clone(),
+            rejected: self.rejected.clone(),
+            quota: self.quota.clone(),
+            id: self.id.clone(),
+        }
+    }
+}
+
+impl<Req, Res> Permit<'_, Req, Res> {
+    /// Sends a request using the reserved capacity, open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    pub fn send(self, request: Req) -> ResponseReceiver<Res> {
+        let (mut payload, receiver) = new_payload(request, &self.channel);
+        payload.1.sender = Some(self.sender);
+        receiver.state.hold(self.outstanding);
+        self.permit.send(payload);
+        self.channel.add_depth(1);
+        receiver
+    }
+}
+
+impl<Req, Res> OwnedPermit<Req, Res> {
+    /// Sends a request using the reserved capacity, open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    pub fn send(self, request: Req) -> ResponseReceiver<Res> {
+        let (mut payload, receiver) = new_payload(request, &self.channel);
+        payload.1.sender = Some(self.sender);
+        receiver.state.hold(self.outstanding);
+        self.permit.send(payload);
+        self.channel.add_depth(1);
+        receiver
+    }
+}
+
+/// Reserves a slot in the request channel, giving up once the clock of the `channel`
+/// reaches the `deadline` if there is one
+async fn reserve_until<P>(
+    channel: &ChannelState,
+    deadline: Option<Instant>,
+    reserve: impl Future<Output = Result<P, mpsc::error::SendError<()>>>,
+) -> Result<P, SendTimeoutError<()>> {
+    let reserved = match deadline {
+        Some(deadline) => channel
+            .timeout_at(deadline, reserve)
+            .await
+            .ok_or(SendTimeoutError::Timeout(()))?,
+        None => reserve.await,
+    };
+    reserved.map_err(|_| SendTimeoutError::Closed(()))
+}
+
+impl<Req, Res> Clone for RequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        RequestSender {
+            request_sender: self.request_sender.clone(),
+            channel: self.channel.clone(),
+            rejected: self.rejected.clone(),
+            quota: self.quota.clone(),
+            id: self.channel.next_sender_id(self.id.label.clone()),
+        }
+    }
+}
+
+impl<Req, Res> WeakRequestSender<Req, Res> {
+    /// Tries to convert back into a [`RequestSender`]
+    ///
+    /// Returns `None` if all the [`RequestSender`] instances have been dropped.
+    pub fn upgrade(&self) -> Option<RequestSender<Req, Res>> {
+        self.request_sender
+            .upgrade()
+            .map(|request_sender| RequestSender {
+                request_sender,
+                channel: self.channel.clone(),
+                rejected: self.rejected.clone(),
+                quota: self.quota.clone(),
+                id: self.id.clone(),
+            })
+    }
+}
+
+impl<Req, Res> Clone for WeakRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        WeakRequestSender {
+            request_sender: self.request_sender.clone(),
+            channel: self.channel.clone(),
+            rejected: self.rejected.clone(),
+            quota: self.quota.clone(),
+            id: self.id.clone(),
+        }
+    }
+}
+
+// The requeued payloads are never pinned, so the receiver can move freely
+impl<Req, Res> Unpin for RequestReceiver<Req, Res> {}
+
+impl<Req, Res> RequestReceiver<Req, Res> {
+    fn new(
+        receiver: mpsc::Receiver<Payload<Req, Res>>,
+        sender: mpsc::WeakSender<Payload<Req, Res>>,
+        channel: Arc<ChannelState>,
+    ) -> Self {
+        RequestReceiver {
+            request_receiver: receiver,
+            request_sender: sender,
+            requeued_front: VecDeque::new(),
+            requeued_back: VecDeque::new(),
+            peeked: None,
+            hooks: ReceiverHooks::<Req, Res>::default(),
+            pause: Arc::default(),
+            channel,
+        }
+    }
+
+    /// Receives the next value for this receiver.
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            self.pause.resumed().await;
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => match poll_fn(|cx| self.poll_recv_queued(cx)).await {
+                    Some(payload) => payload,
+                    None => return Err(RequestError::recv_error()),
+                },
+            };
+            if self.pause.is_paused() {
+                self.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives up to `limit` requests into `buffer`, waiting until at least one is available
+    ///
+    /// Returns the number of requests appended to `buffer`. It returns `0` only if
+    /// `limit` is `0`, or if the channel is closed and no requests are left.
+    pub async fn recv_many(&mut self, buffer: &mut Vec<Payload<Req, Res>>, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        let start = buffer.len();
+        loop {
+            self.pause.resumed().await;
+            let mut received = 0;
+            while received < limit {
+                match self.next_requeued() {
+                    Some(payload) => {
+                        buffer.push(payload);
+                        received += 1;
+                    }
+                    None => break,
+                }
+            }
+            if received == 0
+                && poll_fn(|cx| self.poll_recv_many_queued(cx, buffer, limit)).await == 0
+            {
+                return 0;
+            }
+            let payloads = buffer.split_off(start);
+            if self.pause.is_paused() {
+                for payload in payloads.into_iter().rev() {
+                    self.requeued_front.push_front(payload);
+                }
+                continue;
+            }
+            buffer.extend(
+                payloads
+                    .into_iter()
+                    .filter_map(|payload| unexpired(payload, &self.hooks)),
+            );
+            if buffer.len() > start {
+                return buffer.len() - start;
+            }
+        }
+    }
+
+    /// Tries to receive the next value for this receiver without waiting.
+    ///
+    /// Fails with [`TryRecvError::Empty`] if no request is queued, or
+    /// [`TryRecvError::Disconnected`] if the channel is closed and drained.
+    pub fn try_recv(&mut self) -> Result<Payload<Req, Res>, TryRecvError> {
+        if self.pause.is_paused() {
+            return Err(TryRecvError::Empty);
+        }
+        self.close_if_closing();
+        loop {
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => {
+                    let payload = self.request_receiver.try_recv()?;
+                    self.channel.add_depth(-1);
+                    payload
+                }
+            };
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives the next value for this receiver, waiting at most `duration` for it
+    ///
+    /// Fails with [`RecvTimeoutError::Timeout`] if no request arrives in time, or
+    /// [`RecvTimeoutError::Disconnected`] if the channel is closed and drained.
+    pub async fn recv_timeout(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Payload<Req, Res>, RecvTimeoutError> {
+        let channel = self.channel.clone();
+        match channel
+            .timeout_at(channel.now() + duration, self.recv())
+            .await
+        {
+            Some(Ok(payload)) => Ok(payload),
+            Some(Err(..)) => Err(RecvTimeoutError::Disconnected),
+            None => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Waits for the next request and returns a reference to it, without taking it
+    /// out of the queue
+    ///
+    /// The next call to [`recv()`](Self::recv()) or [`try_recv()`](Self::try_recv())
+    /// returns the same request, unless it expires in the meantime.
+    ///
+    /// Peeking does not count as receiving: the request stays queued, so its sender
+    /// is not told that it was accepted until it is received.
+    pub async fn peek(&mut self) -> Result<&Req, RequestError<Req>> {
+        while !self.discard_stale() {
+            self.pause.resumed().await;
+            if !poll_fn(|cx| self.poll_peek_queued(cx)).await {
+                return Err(RequestError::recv_error());
+            }
+        }
+        Ok(self.peeked_request())
+    }
+
+    /// Returns a reference to the next request without waiting and without taking
+    /// it out of the queue
+    ///
+    /// Fails just like [`try_recv()`](Self::try_recv()).
+    pub fn try_peek(&mut self) -> Result<&Req, TryRecvError> {
+        while !self.discard_stale() {
+            if self.pause.is_paused() {
+                return Err(TryRecvError::Empty);
+            }
+            self.close_if_closing();
+            if !self.peek_requeued_back() {
+                self.peeked = Some(self.request_receiver.try_recv()?);
+            }
+        }
+        Ok(self.peeked_request())
+    }
+
+    /// Blocking receive to call outside of asynchronous contexts.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn blocking_recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            if self.pause.is_paused() {
+                block_on_timeout(self.pause.resumed(), None);
+            }
+            self.close_if_closing();
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => match self.request_receiver.blocking_recv() {
+                    Some(payload) => {
+                        self.channel.add_depth(-1);
+                        payload
+                    }
+                    None => return Err(RequestError::recv_error()),
+                },
+            };
+            if self.pause.is_paused() {
+                self.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives the next request, processes it in place with `handler` and
+    /// responds with the value it returns
+    ///
+    /// The handler borrows the request instead of taking ownership of it, and the
+    /// request is returned once the response is sent, so large buffers can be reused.
+    pub async fn recv_with<F>(&mut self, handler: F) -> Result<Req, RequestError<Res>>
+    where
+        F: FnOnce(&mut Req) -> Res,
+    {
+        match self.recv().await {
+            Ok((mut request, responder)) => {
+                let response = handler(&mut request);
+                responder.respond(response)?;
+                Ok(request)
+            }
+            Err(..) => Err(RequestError::recv_error()),
+        }
+    }
+
+    /// Answers every request with the response of the async `handler`, until the channel closes
+    ///
+    /// The requests are handled one at a time. The returned future resolves to a
+    /// [`ServeReport`] once all the senders have been dropped and the queue is drained.
+    pub async fn serve<F, Fut>(mut self, mut handler: F) -> ServeReport
+    where
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = in_request_span(&responder, handler(request)).await;
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve()`](Self::serve()), but the handler also receives a clone of
+    /// `state` with every request
+    ///
+    /// Use an `Arc` or another cheaply cloneable handle to share a database pool or
+    /// a configuration between the handler invocations.
+    pub async fn serve_with_state<S, F, Fut>(self, state: S, mut handler: F) -> ServeReport
+    where
+        S: Clone,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        self.serve(|request| handler(state.clone(), request)).await
+    }
+
+    /// Like [`serve()`](Self::serve()), but a panic of the handler is caught and
+    /// the loop keeps serving the next requests
+    ///
+    /// The requesting side of the request that made the handler panic receives
+    /// [`RequestError::HandlerPanicked`]. The request is counted as panicked in
+    /// the [`ServeReport`].
+    pub async fn serve_catch_unwind<F, Fut>(mut self, mut handler: F) -> ServeReport
+    where
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = match catch_unwind(AssertUnwindSafe(|| handler(request))) {
+                Ok(response) => {
+                    AssertUnwindSafe(in_request_span(&responder, response))
+                        .catch_unwind()
+                        .await
+                }
+                Err(panic) => Err(panic),
+            };
+            match response {
+                Ok(response) => reporter.record(responder.respond_outcome(response)),
+                Err(..) => {
+                    responder.drop_with(ReceiveError::HandlerPanicked);
+                    reporter.record(Outcome::Panicked);
+                }
+            }
+        }
+        reporter.finish()
+    }
+
+    /// Answers the requests with the async `handler`, running up to `limit` handlers
+    /// concurrently, until the channel closes
+    ///
+    /// Every handler is spawned as a task on the current Tokio runtime. No new
+    /// request is received while `limit` handlers are in flight. The returned future
+    /// resolves to a [`ServeReport`] once the channel is closed and every handler
+    /// has finished. A handler that panics is counted as panicked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent<F, Fut>(mut self, limit: usize, mut handler: F) -> ServeReport
+    where
+        Res: Send + 'static,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        loop {
+            while handlers.len() >= limit {
+                record_handler(&mut reporter, handlers.join_next().await);
+            }
+            let (request, responder) = match self.recv().await {
+                Ok(payload) => payload,
+                Err(..) => break,
+            };
+            let response = in_request_span(&responder, handler(request));
+            handlers.spawn(async move { responder.respond_outcome(response.await) });
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
+    }
+
+    /// Answers the requests with the async `handler` like
+    /// [`serve_concurrent()`](Self::serve_concurrent()), until `shutdown` completes
+    /// or the channel closes
+    ///
+    /// Once `shutdown` completes, the channel is closed and the requests still
+    /// waiting in it are answered with [`RequestError::ShutDown`]. The handlers in
+    /// flight are given `drain` to finish; the ones still running once it elapses are
+    /// dropped and their requests answered with [`RequestError::ShutDown`] as well.
+    /// Both are counted as shut down in the returned [`ServeReport`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_until_shutdown<F, Fut, S>(
+        mut self,
+        limit: usize,
+        shutdown: S,
+        drain: Duration,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+        S: Future<Output = ()>,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        let (abort, aborted) = watch::channel(false);
+        let mut shutdown = pin!(shutdown);
+        let shut_down = loop {
+            if handlers.len() >= limit {
+                match select(pin!(handlers.join_next()), shutdown.as_mut()).await {
+                    Either::Left((result, _)) => record_handler(&mut reporter, result),
+                    Either::Right(..) => break true,
+                }
+                continue;
+            }
+            match select(pin!(self.recv()), shutdown.as_mut()).await {
+                Either::Left((Ok((request, responder)), _)) => {
+                    let response = in_request_span(&responder, handler(request));
+                    spawn_abortable(&mut handlers, &aborted, responder, response);
+                }
+                Either::Left((Err(..), _)) => break false,
+                Either::Right(..) => break true,
+            }
+        };
+        if shut_down {
+            for (_, responder) in self.close_and_take().await {
+                responder.drop_with(ReceiveError::ShutDown);
+                reporter.record(Outcome::ShutDown);
+            }
+            drain_handlers(&self.channel, &mut handlers, &mut reporter, abort, drain).await;
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve_concurrent()`](Self::serve_concurrent()), but every spawned
+    /// handler receives its own clone of `state` with its request
+    ///
+    /// The state is cloned on the serving task before the handler is spawned, so
+    /// the handler future owns its clone and needs no `Arc` around the closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_state<S, F, Fut>(
+        self,
+        limit: usize,
+        state: S,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        S: Clone,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+    {
+        self.serve_concurrent(limit, |request| handler(state.clone(), request))
+            .await
+    }
+
+    /// Like [`serve_until_shutdown()`](Self::serve_until_shutdown()), but every
+    /// spawned handler receives its own clone of `state` with its request
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_until_shutdown_with_state<St, F, Fut, S>(
+        self,
+        limit: usize,
+        shutdown: S,
+        drain: Duration,
+        state: St,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        St: Clone,
+        F: FnMut(St, Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+        S: Future<Output = ()>,
+    {
+        self.serve_until_shutdown(limit, shutdown, drain, |request| {
+            handler(state.clone(), request)
+        })
+        .await
+    }
+
+    /// Puts a payload back at the front of the queue, so it is the next one to be received
+    ///
+    /// The attempt counter of the responder is incremented.
+    pub fn push_front(&mut self, mut payload: Payload<Req, Res>) {
+        payload.1.attempt += 1;
+        self.requeued_front.push_front(payload);
+    }
+
+    /// Puts a payload back at the end of the queue, to be received again after the
+    /// requests that are already queued
+    ///
+    /// The attempt counter of the responder is incremented. If the queue is full,
+    /// the payload is handed back.
+    pub fn push_back(&mut self, mut payload: Payload<Req, Res>) -> Result<(), Payload<Req, Res>> {
+        payload.1.attempt += 1;
+        match self.request_sender.upgrade() {
+            Some(sender) => match sender.try_send(payload) {
+                Ok(()) => {
+                    self.channel.add_depth(1);
+                    Ok(())
+                }
+                Err(mpsc::error::TrySendError::Full(mut payload)) => {
+                    payload.1.attempt -= 1;
+                    Err(payload)
+                }
+                Err(mpsc::error::TrySendError::Closed(payload)) => {
+                    self.requeued_back.push_back(payload);
+                    Ok(())
+                }
+            },
+            None => {
+                self.requeued_back.push_back(payload);
+                Ok(())
+            }
+        }
+    }
+
+    /// Closes the queue once a sender closed the channel, so the remaining requests
+    /// are still delivered before the receiver sees the channel closed
+    fn close_if_closing(&mut self) {
+        if self.channel.is_closing() {
+            self.request_receiver.close();
+        }
+    }
+
+    /// Polls the queue for the next request, closing it once a sender closed the channel
+    fn poll_recv_queued(&mut self, cx: &mut Context<'_>) -> Poll<Option<Payload<Req, Res>>> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        let payload = ready!(self.request_receiver.poll_recv(cx));
+        if payload.is_some() {
+            self.channel.add_depth(-1);
+        }
+        Poll::Ready(payload)
+    }
+
+    /// Polls the queue for up to `limit` requests, see [`poll_recv_queued()`](Self::poll_recv_queued())
+    fn poll_recv_many_queued(
+        &mut self,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Payload<Req, Res>>,
+        limit: usize,
+    ) -> Poll<usize> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        let received = ready!(self.request_receiver.poll_recv_many(cx, buffer, limit));
+        self.channel.add_depth(-(received as isize));
+        Poll::Ready(received)
+    }
+
+    /// Polls the queue for the request that [`peek()`](Self::peek()) returns,
+    /// returning `false` once the channel is closed and drained
+    ///
+    /// The request stays counted in the depth of the channel until it is received.
+    fn poll_peek_queued(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        if self.peek_requeued_back() {
+            return Poll::Ready(true);
+        }
+        self.peeked = ready!(self.request_receiver.poll_recv(cx));
+        Poll::Ready(self.peeked.is_some())
+    }
+
+    /// Takes the next request out of the channel for [`peek()`](Self::peek()), or the
+    /// first payload put back with [`push_back()`](Self::push_back()) once the channel
+    /// is drained, returning `false` if no payload was put back
+    fn peek_requeued_back(&mut self) -> bool {
+        if self.requeued_back.is_empty() {
+            return false;
+        }
+        match self.request_receiver.try_recv() {
+            Ok(payload) => self.peeked = Some(payload),
+            Err(..) => self.requeued_front.extend(self.requeued_back.pop_front()),
+        }
+        true
+    }
+
+    /// Discards the cancelled and expired requests in front of the queue the way
+    /// receiving does, returning `true` if the next request is already taken out
+    /// of the channel
+    fn discard_stale(&mut self) -> bool {
+        loop {
+            match self.requeued_front.front().or(self.peeked.as_ref()) {
+                Some((_, responder)) if responder.is_stale() => {
+                    if let Some(payload) = self.next_requeued() {
+                        unexpired(payload, &self.hooks);
+                    }
+                }
+                Some(..) => return true,
+                None => return false,
+            }
+        }
+    }
+
+    /// Returns the request taken out of the channel by [`peek()`](Self::peek())
+    fn peeked_request(&self) -> &Req {
+        match self.requeued_front.front().or(self.peeked.as_ref()) {
+            Some((request, _)) => request,
+            None => unreachable!("a request is peeked"),
+        }
+    }
+
+    /// Returns the next requeued payload that is due before the requests in the channel
+    ///
+    /// Payloads put back while no sender was left to enqueue them are only due
+    /// once the channel is drained.
+    fn next_requeued(&mut self) -> Option<Payload<Req, Res>> {
+        if let Some(payload) = self.requeued_front.pop_front() {
+            return Some(payload);
+        }
+        if let Some(payload) = self.peeked.take() {
+            self.channel.add_depth(-1);
+            return Some(payload);
+        }
+        if self.requeued_back.is_empty() {
+            return None;
+        }
+        match self.request_receiver.try_recv() {
+            Ok(payload) => {
+                self.channel.add_depth(-1);
+                Some(payload)
+            }
+            Err(..) => self.requeued_back.pop_front(),
+        }
+    }
+
+    /// Stops delivering requests until [`resume()`](Self::resume()) is called,
+    /// without closing the channel
+    ///
+    /// The receive methods wait while the receiver is paused, and [`try_recv()`](Self::try_recv())
+    /// fails with [`TryRecvError::Empty`]. The queued requests stay in the queue, so
+    /// the senders wait for capacity once it is full. Use a [`PauseHandle`] to pause
+    /// the receiver while it is serving requests.
+    pub fn pause(&self) {
+        self.pause.pause();
+    }
+
+    /// Delivers requests again after [`pause()`](Self::pause())
+    pub fn resume(&self) {
+        self.pause.resume();
+    }
+
+    /// Returns `true` if the receiver is paused
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Returns a handle to pause and resume the receiver from another task
+    pub fn pause_handle(&self) -> PauseHandle {
+        PauseHandle {
+            state: self.pause.clone(),
+        }
+    }
+
+    /// Closes the receiving half of a channel without dropping it.
+    pub fn close(&mut self) {
+        self.request_receiver.close()
+    }
+
+    /// Closes the channel and rejects every request that is still waiting in it
+    ///
+    /// The responders of the remaining requests are dropped, so the requesting
+    /// sides resolve with [`RequestError::RecvError`] right away instead of waiting.
+    /// The requests are moved to the [dead-letter queue](crate::ChannelBuilder::dead_letters())
+    /// of the channel, if any. Returns the number of rejected requests.
+    pub async fn close_and_drain(&mut self) -> usize {
+        let drained = self.close_and_take().await;
+        let count = drained.len();
+        dead_letter_drained(drained, &self.hooks);
+        count
+    }
+
+    /// Closes the channel and takes every request that is still waiting in it
+    async fn close_and_take(&mut self) -> Vec<Payload<Req, Res>> {
+        self.close();
+        let mut queued: Vec<_> = self.requeued_front.drain(..).collect();
+        if let Some(payload) = self.peeked.take() {
+            self.channel.add_depth(-1);
+            queued.push(payload);
+        }
+        queued.extend(self.requeued_back.drain(..));
+        while let Some(payload) = self.request_receiver.recv().await {
+            self.channel.add_depth(-1);
+            queued.push(payload);
+        }
+        queued
+    }
+
+    /// Returns the number of requests waiting to be received, including the ones
+    /// that were put back with [`push_front()`](Self::push_front()) or
+    /// [`push_back()`](Self::push_back())
+    pub fn len(&self) -> usize {
+        self.request_receiver.len()
+            + self.requeued_front.len()
+            + usize::from(self.peeked.is_some())
+            + self.requeued_back.len()
+    }
+
+    /// Returns `true` if there are no requests waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a watch receiver that sees the number of queued requests change
+    ///
+    /// Also see [`RequestSender::watch_depth()`](RequestSender::watch_depth()).
+    pub fn watch_depth(&self) -> watch::Receiver<usize> {
+        self.channel.watch_depth()
+    }
+
+    /// Returns the number of requests that can be sent before the channel is full
+    pub fn capacity(&self) -> usize {
+        self.request_receiver.capacity()
+    }
+
+    /// Returns the buffer capacity the channel was created with
+    pub fn max_capacity(&self) -> usize {
+        self.request_receiver.max_capacity()
+    }
+
+    /// Converts this receiver into a [`SharedRequestReceiver`] that can be cloned and shared
+    /// between several worker tasks
+    pub fn into_shared(self) -> SharedRequestReceiver<Req, Res> {
+        self.into()
+    }
+
+    /// Converts this receiver into a stream
+    pub fn into_stream(self) -> impl Stream<Item = Payload<Req, Res>> {
+        let stream: RequestReceiverStream<Req, Res> = self.into();
+        stream
+    }
+}
+
+impl<Req, T, E> RequestReceiver<Req, Result<T, E>> {
+    /// Like [`serve()`](Self::serve()), but the handler is called again with a clone
+    /// of the request when it fails with an error that `should_retry` accepts, as
+    /// the [`RetryPolicy`] allows
+    ///
+    /// The last error is sent back once the handler runs out of attempts or fails
+    /// with an error not worth retrying, and the request is counted as failed in
+    /// the [`ServeReport`].
+    pub async fn serve_with_retry<P, F, Fut>(
+        mut self,
+        policy: RetryPolicy,
+        should_retry: P,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone,
+        P: Fn(&E) -> bool,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = retry_if(request, &policy, &should_retry, &mut handler);
+            let response = in_request_span(&responder, response).await;
+            reporter.record(responder.respond_result_outcome(response));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve_concurrent()`](Self::serve_concurrent()), but the handler is
+    /// retried like in [`serve_with_retry()`](Self::serve_with_retry())
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_retry<P, F, Fut>(
+        mut self,
+        limit: usize,
+        policy: RetryPolicy,
+        should_retry: P,
+        handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+        P: Fn(&E) -> bool + Send + Sync + 'static,
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let should_retry = Arc::new(should_retry);
+        let handler = Arc::new(handler);
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        loop {
+            while handlers.len() >= limit {
+                record_handler(&mut reporter, handlers.join_next().await);
+            }
+            let (request, responder) = match self.recv().await {
+                Ok(payload) => payload,
+                Err(..) => break,
+            };
+            let (should_retry, handler) = (should_retry.clone(), handler.clone());
+            let response = in_request_span(&responder, async move {
+                retry_if(request, &policy, &*should_retry, |request| handler(request)).await
+            });
+            handlers.spawn(async move { responder.respond_result_outcome(response.await) });
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve_with_retry()`](Self::serve_with_retry()), but every attempt of
+    /// the handler receives a clone of `state` with its request
+    pub async fn serve_with_retry_and_state<S, P, F, Fut>(
+        self,
+        state: S,
+        policy: RetryPolicy,
+        should_retry: P,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone,
+        S: Clone,
+        P: Fn(&E) -> bool,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.serve_with_retry(policy, should_retry, |request| {
+            handler(state.clone(), request)
+        })
+        .await
+    }
+
+    /// Like [`serve_concurrent_with_retry()`](Self::serve_concurrent_with_retry()),
+    /// but every attempt of a spawned handler receives its own clone of `state`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_retry_and_state<S, P, F, Fut>(
+        self,
+        limit: usize,
+        state: S,
+        policy: RetryPolicy,
+        should_retry: P,
+        handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+        S: Clone + Send + Sync + 'static,
+        P: Fn(&E) -> bool + Send + Sync + 'static,
+        F: Fn(S, Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.serve_concurrent_with_retry(limit, policy, should_retry, move |request| {
+            handler(state.clone(), request)
+        })
+        .await
+    }
+}
+
+impl<Res> ResponseReceiver<Res> {
+    pub(crate) fn new(response_receiver: oneshot::Receiver<Res>, state: Arc<RequestState>) -> Self {
+        Self {
+            response_receiver: Some(response_receiver),
+            state,
+        }
+    }
+
+    /// Returns the id the request was assigned when it was sent
+    pub fn request_id(&self) -> RequestId {
+        self.state.id
+    }
+
+    /// Returns the span that was current when the request was sent
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> tracing::Span {
+        self.state.span.clone()
+    }
+
+    /// Returns the OpenTelemetry context that was current when the request was sent
+    #[cfg(feature = "otel")]
+    pub fn otel_context(&self) -> opentelemetry::Context {
+        self.state.otel_context.clone()
+    }
+
+    /// Returns the context the request was sent with, see
+    /// [`RequestSender::send_with_context()`]
+    pub fn context(&self) -> Option<&RequestContext> {
+        self.state.context.as_ref()
+    }
+
+    /// Receives the next value for this receiver.
+    ///
+    /// If there is a `timeout_duration` set, and the responder does not send the
+    /// response within the timeout_duration after the request was sent, it aborts
+    /// waiting and returns [`ReceiveError::TimeoutError`].
+    pub async fn recv(&mut self) -> Result<Res, ReceiveError> {
+        let deadline = poll_fn(|cx| match self.poll_response(cx) {
+            Poll::Ready(result) => Poll::Ready(Either::Left(result)),
+            Poll::Pending => self.state.poll_deadline(cx).map(Either::Right),
+        });
+        let deadline = match deadline.await {
+            Either::Left(result) => return result,
+            Either::Right(deadline) => deadline,
+        };
+        match deadline {
+            Some(deadline) => {
+                let sleep = self.state.sleep_until(deadline);
+                match select(pin!(poll_fn(|cx| self.poll_response(cx))), sleep).await {
+                    Either::Left((result, _)) => result,
+                    Either::Right(..) => {
+                        self.state.cancel(CancelReason::TimedOut);
+                        self.response_receiver = None;
+                        Err(ReceiveError::TimeoutError)
+                    }
+                }
+            }
+            None => poll_fn(|cx| self.poll_response(cx)).await,
+        }
+    }
+
+    /// Receives the response like [`recv()`](Self::recv()), capturing a span trace
+    /// in the span of the request if it fails
+    #[cfg(feature = "tracing-error")]
+    pub async fn recv_traced(&mut self) -> Result<Res, Traced<ReceiveError>> {
+        self.recv()
+            .await
+            .map_err(|err| Traced::in_span(err, &self.state.span))
+    }
+
+    /// Waits until the receiver takes the request out of the queue, before it responds
+    ///
+    /// Use it to tell a request waiting in a busy queue apart from a request being
+    /// handled, for example to report progress or to time out the two phases
+    /// separately. It fails if the request is dropped, expires or is cancelled before
+    /// it is received. Requests sent over the other channel types, like the [`ring`](crate::ring)
+    /// channels, only count as accepted once they are responded to.
+    pub async fn accepted(&self) -> Result<(), ReceiveError> {
+        if poll_fn(|cx| self.state.poll_accepted(cx)).await {
+            return Ok(());
+        }
+        if self.state.is_withdrawn() {
+            return Err(ReceiveError::Cancelled);
+        }
+        Err(self.recv_error())
+    }
+
+    /// Waits for the response like [`recv()`](Self::recv()), cancelling the request
+    /// by id once `token` is cancelled
+    #[cfg(feature = "tokio-util")]
+    pub(crate) async fn recv_with_token(
+        &mut self,
+        token: &CancellationToken,
+    ) -> Result<Res, ReceiveError> {
+        if let Either::Left((result, _)) = select(pin!(self.recv()), pin!(token.cancelled())).await
+        {
+            return result;
+        }
+        self.state.withdraw();
+        self.response_receiver = None;
+        Err(ReceiveError::Cancelled)
+    }
+
+    /// Polls for the response, failing with [`ReceiveError::Cancelled`] once the
+    /// request is cancelled with [`RequestSender::cancel()`]
+    fn poll_response(&mut self, cx: &mut Context<'_>) -> Poll<Result<Res, ReceiveError>> {
+        let response_receiver = match self.response_receiver.as_mut() {
+            Some(response_receiver) => response_receiver,
+            None => return Poll::Ready(Err(ReceiveError::RecvError)),
+        };
+        let result = if self.state.poll_withdrawn(cx).is_ready() {
+            Err(ReceiveError::Cancelled)
+        } else {
+            ready!(Pin::new(response_receiver).poll(cx)).map_err(|_| self.recv_error())
+        };
+        self.response_receiver = None;
+        if result.is_ok() {
+            self.state.trace("response received");
+        }
+        Poll::Ready(result)
+    }
+
+    /// Blocking receive to call outside of asynchronous contexts.
+    ///
+    /// The `timeout_duration` is applied just like in [`recv()`](Self::recv()).
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn blocking_recv(&mut self) -> Result<Res, ReceiveError> {
+        if self.response_receiver.is_none() {
+            return Err(ReceiveError::RecvError);
+        }
+        let deadline = poll_fn(|cx| match self.poll_response(cx) {
+            Poll::Ready(result) => Poll::Ready(Either::Left(result)),
+            Poll::Pending => self.state.poll_deadline(cx).map(Either::Right),
+        });
+        let deadline = match block_on_timeout(deadline, None) {
+            Some(Either::Left(result)) => return result,
+            Some(Either::Right(deadline)) => deadline,
+            None => unreachable!("the deadline is awaited without a timeout"),
+        };
+        let timeout_duration =
+            deadline.map(|deadline| deadline.saturating_duration_since(self.state.now()));
+        match block_on_timeout(poll_fn(|cx| self.poll_response(cx)), timeout_duration) {
+            Some(result) => result,
+            None => {
+                self.state.cancel(CancelReason::TimedOut);
+                self.response_receiver = None;
+                Err(ReceiveError::TimeoutError)
+            }
+        }
+    }
+
+    /// Overrides the response timeout of the channel for this request, counting
+    /// from now
+    ///
+    /// Pass `None` to wait for the response without a timeout. The new deadline is
+    /// also visible to the handler through [`Responder::deadline()`].
+    pub fn set_timeout(&mut self, timeout_duration: Option<Duration>) {
+        self.state
+            .set_deadline(timeout_duration.map(|duration| self.state.now() + duration));
+    }
+
+    /// Returns the error to report when the response channel closed without a response
+    fn recv_error(&self) -> ReceiveError {
+        self.state.drop_error().unwrap_or(ReceiveError::RecvError)
+    }
+
+    /// Converts this receiver into a [`SharedResponseReceiver`] that can be cloned,
+    /// so several tasks can await the same response
+    pub fn shared(self) -> SharedResponseReceiver<Res>
+    where
+        Res: Clone,
+    {
+        SharedResponseReceiver {
+            inner: self.into_future().shared(),
+        }
+    }
+
+    /// Stops waiting for the response, letting the [`Responder`] know that the
+    /// request was cancelled
+    pub fn cancel(mut self) {
+        if let Some(response_receiver) = self.response_receiver.take() {
+            self.state.cancel(CancelReason::Cancelled);
+            drop(response_receiver);
+        }
+    }
+}
+
+impl<Res> IntoFuture for ResponseReceiver<Res> {
+    type Output = Result<Res, ReceiveError>;
+    type IntoFuture = ResponseFuture<Res>;
+
+    /// Waits for the response like [`recv()`](ResponseReceiver::recv()), so the
+    /// receiver can be awaited directly
+    fn into_future(self) -> Self::IntoFuture {
+        ResponseFuture {
+            receiver: self,
+            sleep: None,
+        }
+    }
+}
+
+/// Future that resolves to the response of a request
+///
+/// Instances are created by awaiting a [`ResponseReceiver`]
+pub struct ResponseFuture<Res> {
+    receiver: ResponseReceiver<Res>,
+    sleep: Option<(Instant, ClockSleep)>,
+}
+
+impl<Res> ResponseFuture<Res> {
+    /// Overrides the response timeout of the request, counting from now, like
+    /// [`ResponseReceiver::set_timeout()`]
+    ///
+    /// The future waits for the new deadline from the next time it is polled.
+    pub fn set_timeout(&mut self, timeout_duration: Option<Duration>) {
+        self.receiver.set_timeout(timeout_duration);
+    }
+}
+
+impl<Res: fmt::Debug> fmt::Debug for ResponseFuture<Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ResponseFuture")
+            .field("receiver", &self.receiver)
+            .finish()
+    }
+}
+
+impl<Res> Future for ResponseFuture<Res> {
+    type Output = Result<Res, ReceiveError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        if let Poll::Ready(result) = this.receiver.poll_response(cx) {
+            return Poll::Ready(result);
+        }
+        let deadline = match this.receiver.state.poll_deadline(cx) {
+            Poll::Ready(Some(deadline)) => deadline,
+            Poll::Ready(None) => {
+                this.sleep = None;
+                return Poll::Pending;
+            }
+            Poll::Pending => return Poll::Pending,
+        };
+        let state = &this.receiver.state;
+        let sleep = match &mut this.sleep {
+            Some((at, sleep)) if *at == deadline => sleep,
+            sleep => &mut sleep.insert((deadline, state.sleep_until(deadline))).1,
+        };
+        if sleep.as_mut().poll(cx).is_ready() {
+            this.receiver.state.cancel(CancelReason::TimedOut);
+            this.receiver.response_receiver = None;
+            return Poll::Ready(Err(ReceiveError::TimeoutError));
+        }
+        Poll::Pending
+    }
 }
 
-/// Receive requests values from the associated [`RequestSender`]
+/// A [`ResponseReceiver`] that can be cloned, so several tasks can await the same response
 ///
-/// Instances are created by the [`channel`] function.
-#[derive(Debug)]
-pub struct RequestReceiver<Req, Res> {
-    request_receiver: mpsc::Receiver<Payload<Req, Res>>,
+/// Every clone resolves to a clone of the response. The response timeout of the
+/// request still applies, and the request is cancelled once every clone is dropped
+/// before the response arrives.
+///
+/// Instances are created by calling [`ResponseReceiver::shared()`]
+#[derive(Clone)]
+pub struct SharedResponseReceiver<Res: Clone> {
+    inner: Shared<ResponseFuture<Res>>,
 }
 
-/// Send values back to the [`RequestSender`] or [`RequestReceiver`]
-///
-/// Instances are created by calling [`RequestSender::send_receive()`] or [`RequestSender::send()`]
-#[derive(Debug)]
-pub struct Responder<Res> {
-    response_sender: oneshot::Sender<Res>,
+impl<Res: Clone> SharedResponseReceiver<Res> {
+    /// Returns the response if it has already been received by one of the clones
+    pub fn peek(&self) -> Option<Result<Res, ReceiveError>> {
+        self.inner.peek().cloned()
+    }
 }
 
-/// Receive responses from a [`Responder`]
-///
-/// Instances are created by calling [`RequestSender::send_receive()`] or [`RequestSender::send()`]
-#[derive(Debug)]
-pub struct ResponseReceiver<Res> {
-    pub(crate) response_receiver: Option<oneshot::Receiver<Res>>,
-    pub(crate) timeout_duration: Option<Duration>,
+impl<Res: Clone> Future for SharedResponseReceiver<Res> {
+    type Output = Result<Res, ReceiveError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.poll_unpin(cx)
+    }
 }
 
-impl<Req, Res> RequestSender<Req, Res> {
-    fn new(
-        request_sender: mpsc::Sender<Payload<Req, Res>>,
-        timeout_duration: Option<Duration>,
-    ) -> Self {
-        RequestSender {
-            request_sender,
-            timeout_duration,
+impl<Res: Clone> fmt::Debug for SharedResponseReceiver<Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SharedResponseReceiver")
+            .field("received", &self.inner.peek().is_some())
+            .finish()
+    }
+}
+
+impl<Res> Drop for ResponseReceiver<Res> {
+    fn drop(&mut self) {
+        if self.response_receiver.is_some() {
+            self.state.cancel(CancelReason::Dropped);
         }
     }
+}
 
-    /// Send a request over the MPSC channel, open the response channel
+impl<Res> Drop for Responder<Res> {
+    fn drop(&mut self) {
+        if let Some(state) = &self.state {
+            if self.response_sender.is_some() {
+                state.dropped();
+                if let Some(unanswered) = &self.unanswered {
+                    (unanswered.0)(state.id, state.sent_at(), state.now());
+                }
+            }
+            state.released();
+            state.finish();
+        }
+    }
+}
+
+impl<Res> Responder<Res> {
+    pub(crate) fn new(response_sender: oneshot::Sender<Res>, state: Arc<RequestState>) -> Self {
+        Self {
+            response_sender: Some(response_sender),
+            state: Some(state),
+            sender: None,
+            attempt: 1,
+            late_response: None,
+            unanswered: None,
+            outstanding: None,
+        }
+    }
+
+    /// Creates a responder for a request sent with [`RequestSender::send_forget()`]
+    pub(crate) fn forgotten() -> Self {
+        Self {
+            response_sender: None,
+            state: None,
+            sender: None,
+            attempt: 1,
+            late_response: None,
+            unanswered: None,
+            outstanding: None,
+        }
+    }
+
+    /// Responds a request from the [`RequestSender`] which finishes the request
     ///
-    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    /// The response is discarded if the request was sent with [`RequestSender::send_forget()`]
+    pub fn respond(mut self, response: Res) -> Result<(), RespondError<Res>> {
+        match self.response_sender.take() {
+            Some(response_sender) => {
+                if self
+                    .state
+                    .as_ref()
+                    .is_some_and(|state| state.is_withdrawn())
+                {
+                    return Err(RespondError::new(response));
+                }
+                if let Err(response) = response_sender.send(response) {
+                    if let Some(state) = &self.state {
+                        state.undelivered();
+                        if let (Some(handler), Some(CancelReason::TimedOut)) =
+                            (&self.late_response, state.cancel_reason())
+                        {
+                            (handler.0)(state.id, &response);
+                        }
+                    }
+                    return Err(RespondError::new(response));
+                }
+                if let Some(state) = &self.state {
+                    state.responded();
+                    state.trace("response sent");
+                }
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Responds like [`respond()`](Self::respond()), capturing a span trace in the
+    /// span of the request if the response cannot be delivered
+    #[cfg(feature = "tracing-error")]
+    pub fn respond_traced(self, response: Res) -> Result<(), Traced<RespondError<Res>>> {
+        let span = self.span();
+        self.respond(response)
+            .map_err(|err| Traced::in_span(err, &span))
+    }
+
+    /// Responds like [`respond()`](Self::respond()), and tells how the request ended
+    /// for the [`ServeReport`] of a serve loop
+    pub(crate) fn respond_outcome(self, response: Res) -> Outcome {
+        let state = self.state.clone();
+        match self.respond(response) {
+            Ok(()) => Outcome::Responded,
+            Err(..)
+                if state
+                    .is_some_and(|state| state.cancel_reason() == Some(CancelReason::TimedOut)) =>
+            {
+                Outcome::TimedOut
+            }
+            Err(..) => Outcome::Unanswered,
+        }
+    }
+
+    /// Returns the span that was current when the request was sent
     ///
-    /// This call waits if the request channel is full. It does not wait for a response
-    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
-        let (response_sender, response_receiver) = oneshot::channel::<Res>();
-        let responder = Responder::new(response_sender);
-        let payload = (request, responder);
-        self.request_sender
-            .send(payload)
-            .await
-            .map_err(|payload| SendError(payload.0 .0))?;
-        let receiver = ResponseReceiver::new(response_receiver, self.timeout_duration);
-        Ok(receiver)
+    /// The serve loops, like [`RequestReceiver::serve()`], run the handler of every
+    /// request in its span. It returns a disabled span if the request was sent with
+    /// [`RequestSender::send_forget()`].
+    #[cfg(feature = "tracing")]
+    pub fn span(&self) -> tracing::Span {
+        match &self.state {
+            Some(state) => state.span.clone(),
+            None => tracing::Span::none(),
+        }
     }
 
-    /// Send a request over the MPSC channel, wait for the response and return it
+    /// Returns the OpenTelemetry context that was current when the request was sent
     ///
-    /// This call waits if the request channel is full, and while waiting for the response
-    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
-        let mut receiver = self.send(request).await?;
-        receiver.recv().await.map_err(|err| err.into())
+    /// The serve loops, like [`RequestReceiver::serve()`], run the handler of every
+    /// request with this context attached, so the traces started by the handler
+    /// continue the trace of the requesting side. Attach it yourself when receiving
+    /// requests with [`RequestReceiver::recv()`]. It returns an empty context if the
+    /// request was sent with [`RequestSender::send_forget()`].
+    #[cfg(feature = "otel")]
+    pub fn otel_context(&self) -> opentelemetry::Context {
+        match &self.state {
+            Some(state) => state.otel_context.clone(),
+            None => opentelemetry::Context::new(),
+        }
     }
 
-    /// Checks if the channel has been closed.
+    /// Awaits the handler future and responds with its output
+    ///
+    /// Returns `true` if the requesting side was still waiting for the response.
+    pub async fn respond_with<Fut>(self, handler: Fut) -> bool
+    where
+        Fut: Future<Output = Res>,
+    {
+        let response = handler.await;
+        self.respond(response).is_ok()
+    }
+
+    /// Calls the handler and responds with its output
+    ///
+    /// Returns `true` if the requesting side was still waiting for the response.
+    pub fn respond_with_sync<F>(self, handler: F) -> bool
+    where
+        F: FnOnce() -> Res,
+    {
+        let response = handler();
+        self.respond(response).is_ok()
+    }
+
+    /// Checks if the associated receiver handle for the response listener has been
+    /// dropped, or if the request was cancelled with [`RequestSender::cancel()`]
     pub fn is_closed(&self) -> bool {
-        self.request_sender.is_closed()
+        self.response_sender
+            .as_ref()
+            .is_some_and(|response_sender| response_sender.is_closed())
+            || self
+                .state
+                .as_ref()
+                .is_some_and(|state| state.is_withdrawn())
     }
-}
 
-impl<Req, Res> Clone for RequestSender<Req, Res> {
-    fn clone(&self) -> Self {
-        RequestSender {
-            request_sender: self.request_sender.clone(),
-            timeout_duration: self.timeout_duration,
+    /// Waits until the associated receiver handle for the response listener is dropped
+    ///
+    /// Use it in a `select!` with the work of the handler to abort as soon as the
+    /// requesting side has given up. It never resolves for a request that expects
+    /// no response.
+    pub async fn closed(&mut self) {
+        match (self.response_sender.as_mut(), &self.state) {
+            (Some(response_sender), Some(state)) => {
+                let withdrawn = poll_fn(|cx| state.poll_withdrawn(cx));
+                select(pin!(response_sender.closed()), pin!(withdrawn)).await;
+            }
+            (Some(response_sender), None) => response_sender.closed().await,
+            (None, _) => std::future::pending().await,
         }
     }
-}
 
-impl<Req, Res> RequestReceiver<Req, Res> {
-    fn new(receiver: mpsc::Receiver<Payload<Req, Res>>) -> Self {
-        RequestReceiver {
-            request_receiver: receiver,
+    /// Returns `false` if the request was sent with [`RequestSender::send_forget()`], so
+    /// nobody is waiting for a response
+    pub fn expects_response(&self) -> bool {
+        self.response_sender.is_some()
+    }
+
+    /// Returns the instant the requesting side stops waiting for the response at,
+    /// or `None` if there is no response timeout
+    ///
+    /// The deadline is computed from the response timeout when the request is sent
+    /// with a [`RequestSender`], or when it is dequeued if the channel was built with
+    /// [`ChannelBuilder::timeout_on_dequeue()`](crate::ChannelBuilder::timeout_on_dequeue()).
+    pub fn deadline(&self) -> Option<Instant> {
+        self.state.as_ref().and_then(|state| state.deadline())
+    }
+
+    /// Returns how much time is left before the [`deadline()`](Self::deadline()),
+    /// or `None` if there is no response timeout
+    ///
+    /// It returns a zero duration once the deadline has passed.
+    pub fn time_remaining(&self) -> Option<Duration> {
+        let state = self.state.as_ref()?;
+        state
+            .deadline()
+            .map(|deadline| deadline.saturating_duration_since(state.now()))
+    }
+
+    /// Waits for the response of a request forwarded to another channel and
+    /// responds with it
+    ///
+    /// If the forwarded request fails, this responder is dropped with the same
+    /// [`ReceiveError`], so the requesting side sees a downstream timeout as a
+    /// timeout and a closed downstream channel as a closed channel. If the
+    /// requesting side stops waiting first, the forwarded request is cancelled.
+    ///
+    /// Returns `true` if the response reached the requesting side.
+    pub async fn forward(mut self, mut response_receiver: ResponseReceiver<Res>) -> bool {
+        let result = {
+            let closed = pin!(self.closed());
+            let response = pin!(response_receiver.recv());
+            match select(closed, response).await {
+                Either::Left(..) => None,
+                Either::Right((result, _)) => Some(result),
+            }
+        };
+        match result {
+            Some(Ok(response)) => self.respond(response).is_ok(),
+            Some(Err(err)) => {
+                self.drop_with(err);
+                false
+            }
+            None => {
+                response_receiver.cancel();
+                false
+            }
         }
     }
 
-    /// Receives the next value for this receiver.
-    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
-        match self.request_receiver.recv().await {
-            Some(payload) => Ok(payload),
-            None => Err(RequestError::RecvError),
+    /// Marks the request as taken out of the queue by the receiver
+    pub(crate) fn dequeued(&self) {
+        if let Some(state) = &self.state {
+            state.dequeued();
         }
     }
 
-    /// Closes the receiving half of a channel without dropping it.
-    pub fn close(&mut self) {
-        self.request_receiver.close()
+    /// Reports to the metrics of the channel that the receiver took the request
+    pub(crate) fn received(&self) {
+        if let Some(state) = &self.state {
+            state.received();
+        }
     }
 
-    /// Converts this receiver into a stream
-    pub fn into_stream(self) -> impl Stream<Item = Payload<Req, Res>> {
-        let stream: RequestReceiverStream<Req, Res> = self.into();
-        stream
+    /// Returns `true` if the request has been queued for longer than its time-to-live
+    pub(crate) fn is_expired(&self) -> bool {
+        self.state.as_ref().is_some_and(|state| state.expired())
     }
-}
 
-impl<Res> ResponseReceiver<Res> {
-    pub(crate) fn new(
-        response_receiver: oneshot::Receiver<Res>,
-        timeout_duration: Option<Duration>,
-    ) -> Self {
-        Self {
-            response_receiver: Some(response_receiver),
-            timeout_duration,
+    /// Returns `true` if the request was cancelled by id or expired while queued,
+    /// so the receiver discards it instead of handing it out
+    pub(crate) fn is_stale(&self) -> bool {
+        self.is_expired()
+            || self
+                .state
+                .as_ref()
+                .is_some_and(|state| state.is_withdrawn())
+    }
+
+    /// Drops the responder, letting the requesting side know why with `error`, like
+    /// a panicking handler or an eviction from a full queue
+    pub(crate) fn drop_with(self, error: ReceiveError) {
+        if let Some(state) = &self.state {
+            state.set_drop_error(error);
         }
     }
 
-    /// Receives the next value for this receiver.
+    /// Wraps the responder in a guard that responds with `fallback` if it is
+    /// dropped before [`GuardedResponder::respond()`] is called
     ///
-    /// If there is a `timeout_duration` set, and the sender takes longer than
-    /// the timeout_duration to send the response, it aborts waiting and returns
-    /// [`ReceiveError::TimeoutError`].
-    pub async fn recv(&mut self) -> Result<Res, ReceiveError> {
-        match self.response_receiver.take() {
-            Some(response_receiver) => match self.timeout_duration {
-                Some(duration) => match timeout(duration, response_receiver).await {
-                    Ok(response_result) => response_result.map_err(|err| err.into()),
-                    Err(..) => Err(ReceiveError::TimeoutError),
-                },
-                None => Ok(response_receiver.await?),
-            },
-            None => Err(ReceiveError::RecvError),
+    /// This lets the requesting side see a meaningful response when the handler
+    /// returns early or panics.
+    pub fn or_else_on_drop(self, fallback: Res) -> GuardedResponder<Res> {
+        GuardedResponder {
+            responder: Some(self),
+            fallback: Some(fallback),
+        }
+    }
+
+    /// Returns how many times the request has been delivered, starting at 1 and
+    /// incremented every time the payload is put back into the queue
+    pub fn attempt(&self) -> usize {
+        self.attempt
+    }
+
+    /// Returns the id the request was assigned when it was sent, or `None` if it
+    /// was sent with [`RequestSender::send_forget()`]
+    pub fn request_id(&self) -> Option<RequestId> {
+        self.state.as_ref().map(|state| state.id)
+    }
+
+    /// Returns the context the request was sent with, see
+    /// [`RequestSender::send_with_context()`]
+    pub fn context(&self) -> Option<&RequestContext> {
+        self.state.as_ref().and_then(|state| state.context.as_ref())
+    }
+
+    /// Returns the sender the request came from, or `None` if the request was not
+    /// sent by a [`RequestSender`] or an [`UnboundedRequestSender`](crate::unbounded::UnboundedRequestSender)
+    ///
+    /// Use it to apply per-client policies, or to tell the clients apart in logs.
+    pub fn sender_id(&self) -> Option<&SenderId> {
+        self.sender.as_deref()
+    }
+
+    /// Returns why the requesting side stopped waiting for the response, or `None`
+    /// if it is still waiting
+    pub fn cancel_reason(&self) -> Option<CancelReason> {
+        self.state
+            .as_ref()
+            .and_then(|state| cancel_reason(state, self.is_closed()))
+    }
+
+    /// Returns a token that is cancelled when the requesting side stops waiting for the response
+    ///
+    /// The token can be passed down to sub-tasks and I/O operations of the handler.
+    #[cfg(feature = "tokio-util")]
+    pub fn cancellation_token(&self) -> CancellationToken {
+        match &self.state {
+            Some(state) => state.cancellation_token(),
+            None => CancellationToken::new(),
         }
     }
 }
 
-impl<Res> Responder<Res> {
-    pub(crate) fn new(response_sender: oneshot::Sender<Res>) -> Self {
-        Self { response_sender }
+impl<T, E> Responder<Result<T, E>> {
+    /// Responds like [`respond_outcome()`](Self::respond_outcome()), but a delivered
+    /// error counts as failed
+    pub(crate) fn respond_result_outcome(self, response: Result<T, E>) -> Outcome {
+        let failed = response.is_err();
+        match self.respond_outcome(response) {
+            Outcome::Responded if failed => Outcome::Failed,
+            outcome => outcome,
+        }
     }
+}
 
-    /// Responds a request from the [`RequestSender`] which finishes the request
-    pub fn respond(self, response: Res) -> Result<(), RespondError<Res>> {
-        self.response_sender.send(response).map_err(RespondError)
+/// A [`Responder`] that sends a fallback response when dropped without responding
+///
+/// Instances are created by calling [`Responder::or_else_on_drop()`]
+#[derive(Debug)]
+pub struct GuardedResponder<Res> {
+    responder: Option<Responder<Res>>,
+    fallback: Option<Res>,
+}
+
+impl<Res> GuardedResponder<Res> {
+    /// Responds a request with `response` instead of the fallback
+    pub fn respond(mut self, response: Res) -> Result<(), RespondError<Res>> {
+        match self.responder.take() {
+            Some(responder) => responder.respond(response),
+            None => Err(RespondError::new(response)),
+        }
+    }
+
+    /// Awaits the handler future and responds with its output instead of the fallback
+    ///
+    /// The fallback is sent if the future is dropped or panics before it completes.
+    /// Returns `true` if the requesting side was still waiting for the response.
+    pub async fn respond_with<Fut>(self, handler: Fut) -> bool
+    where
+        Fut: Future<Output = Res>,
+    {
+        let response = handler.await;
+        self.respond(response).is_ok()
+    }
+
+    /// Calls the handler and responds with its output instead of the fallback
+    ///
+    /// The fallback is sent if the handler panics.
+    /// Returns `true` if the requesting side was still waiting for the response.
+    pub fn respond_with_sync<F>(self, handler: F) -> bool
+    where
+        F: FnOnce() -> Res,
+    {
+        let response = handler();
+        self.respond(response).is_ok()
     }
 
     /// Checks if the associated receiver handle for the response listener has been dropped.
     pub fn is_closed(&self) -> bool {
-        self.response_sender.is_closed()
+        self.responder
+            .as_ref()
+            .is_none_or(|responder| responder.is_closed())
+    }
+
+    /// Removes the guard and returns the inner responder
+    pub fn into_inner(mut self) -> Responder<Res> {
+        self.responder
+            .take()
+            .expect("the responder is only taken when the guard is consumed")
+    }
+}
+
+impl<Res> Drop for GuardedResponder<Res> {
+    fn drop(&mut self) {
+        if let (Some(responder), Some(fallback)) = (self.responder.take(), self.fallback.take()) {
+            let _ = responder.respond(fallback);
+        }
+    }
+}
+
+/// Runs the handler future of a request in the span and the OpenTelemetry context
+/// the request was sent in
+#[cfg_attr(
+    not(any(feature = "tracing", feature = "otel")),
+    allow(unused_variables)
+)]
+pub(crate) fn in_request_span<Res, Fut: Future>(
+    responder: &Responder<Res>,
+    handler: Fut,
+) -> impl Future<Output = Fut::Output> {
+    #[cfg(feature = "otel")]
+    let handler =
+        opentelemetry::context::FutureExt::with_context(handler, responder.otel_context());
+    #[cfg(feature = "tracing")]
+    let handler = tracing::Instrument::instrument(handler, responder.span());
+    handler
+}
+
+/// Calls the synchronous handler of a request in the span and the OpenTelemetry
+/// context the request was sent in
+#[cfg_attr(
+    not(any(feature = "tracing", feature = "otel")),
+    allow(unused_variables)
+)]
+pub(crate) fn in_request_span_sync<Res, T>(
+    responder: &Responder<Res>,
+    handler: impl FnOnce() -> T,
+) -> T {
+    #[cfg(feature = "otel")]
+    let _context = responder.otel_context().attach();
+    #[cfg(feature = "tracing")]
+    let _span = responder.span().entered();
+    handler()
+}
+
+/// Records the outcome of a handler spawned by a concurrent serve loop
+pub(crate) fn record_handler(
+    reporter: &mut ServeReporter,
+    result: Option<Result<Outcome, JoinError>>,
+) {
+    match result {
+        Some(Ok(outcome)) => reporter.record(outcome),
+        Some(Err(..)) => reporter.record(Outcome::Panicked),
+        None => {}
+    }
+}
+
+/// Spawns the handler of a request for a serve loop that can shut down, the request
+/// is answered with [`ReceiveError::ShutDown`] instead once `aborted` turns true
+pub(crate) fn spawn_abortable<Res: Send + 'static>(
+    handlers: &mut JoinSet<Outcome>,
+    aborted: &watch::Receiver<bool>,
+    responder: Responder<Res>,
+    response: impl Future<Output = Res> + Send + 'static,
+) {
+    let mut aborted = aborted.clone();
+    handlers.spawn(async move {
+        let aborted = aborted.wait_for(|aborted| *aborted).map(|_| ());
+        match select(pin!(response), pin!(aborted)).await {
+            Either::Left((response, _)) => responder.respond_outcome(response),
+            Either::Right(..) => {
+                responder.drop_with(ReceiveError::ShutDown);
+                Outcome::ShutDown
+            }
+        }
+    });
+}
+
+/// Waits up to `drain` for the handlers in flight, then aborts the ones still running
+pub(crate) async fn drain_handlers(
+    channel: &ChannelState,
+    handlers: &mut JoinSet<Outcome>,
+    reporter: &mut ServeReporter,
+    abort: watch::Sender<bool>,
+    drain: Duration,
+) {
+    let deadline = channel.now() + drain;
+    while let Some(Some(result)) = channel.timeout_at(deadline, handlers.join_next()).await {
+        record_handler(reporter, Some(result));
+    }
+    abort.send_replace(true);
+    while let Some(result) = handlers.join_next().await {
+        record_handler(reporter, Some(result));
+    }
+}
+
+pub(crate) fn cancel_reason(state: &RequestState, is_closed: bool) -> Option<CancelReason> {
+    match state.cancel_reason() {
+        Some(reason) => Some(reason),
+        None if is_closed => Some(CancelReason::Dropped),
+        None => None,
+    }
+}
+
+/// Moves the requests drained from a closed channel to the dead-letter queue of the
+/// receiver, if any
+pub(crate) fn dead_letter_drained<Req, Res>(
+    drained: Vec<Payload<Req, Res>>,
+    hooks: &ReceiverHooks<Req, Res>,
+) {
+    let Some(dead_letters) = &hooks.dead_letters else {
+        return;
+    };
+    for (request, responder) in drained {
+        if let Some(state) = &responder.state {
+            dead_letters.send(DeadLetter::new(
+                Some(request),
+                Some(state.id),
+                DeadLetterReason::Drained,
+                state.sent_at(),
+                state.now(),
+            ));
+        }
+    }
+}
+
+/// Returns the payload unless its request outlived its time-to-live in the queue,
+/// in which case its sender is told that it expired
+///
+/// An expired request is moved to the dead-letter queue of the receiver, and the
+/// responder of an unexpired one is handed the handlers of the receiver.
+pub(crate) fn unexpired<Req, Res>(
+    payload: Payload<Req, Res>,
+    hooks: &ReceiverHooks<Req, Res>,
+) -> Option<Payload<Req, Res>> {
+    let (request, mut responder) = payload;
+    responder.dequeued();
+    if responder
+        .state
+        .as_ref()
+        .is_some_and(|state| state.is_withdrawn())
+    {
+        responder.drop_with(ReceiveError::Cancelled);
+        return None;
+    }
+    if responder.is_expired() {
+        if let (Some(dead_letters), Some(state)) = (&hooks.dead_letters, &responder.state) {
+            dead_letters.send(DeadLetter::new(
+                Some(request),
+                Some(state.id),
+                DeadLetterReason::Expired,
+                state.sent_at(),
+                state.now(),
+            ));
+        }
+        responder.drop_with(ReceiveError::Expired);
+        return None;
+    }
+    responder.received();
+    responder.late_response = hooks.late_response.clone();
+    responder.unanswered = hooks.unanswered.clone();
+    Some((request, responder))
+}
+
+/// Creates the payload of a request together with the receiver of its response
+pub(crate) fn new_payload<Req, Res>(
+    request: Req,
+    channel: &Arc<ChannelState>,
+) -> (Payload<Req, Res>, ResponseReceiver<Res>) {
+    new_payload_with_ttl(request, channel, channel.ttl)
+}
+
+/// Creates the payload of a request that expires once queued for longer than `ttl`
+pub(crate) fn new_payload_with_ttl<Req, Res>(
+    request: Req,
+    channel: &Arc<ChannelState>,
+    ttl: Option<Duration>,
+) -> (Payload<Req, Res>, ResponseReceiver<Res>) {
+    new_payload_with_metadata(request, channel, ttl, None)
+}
+
+/// Creates the payload of a request carrying the given context
+pub(crate) fn new_payload_with_context<Req, Res>(
+    request: Req,
+    channel: &Arc<ChannelState>,
+    context: RequestContext,
+) -> (Payload<Req, Res>, ResponseReceiver<Res>) {
+    new_payload_with_metadata(request, channel, channel.ttl, Some(context))
+}
+
+fn new_payload_with_metadata<Req, Res>(
+    request: Req,
+    channel: &Arc<ChannelState>,
+    ttl: Option<Duration>,
+    context: Option<RequestContext>,
+) -> (Payload<Req, Res>, ResponseReceiver<Res>) {
+    let (response_sender, response_receiver) = oneshot::channel::<Res>();
+    let now = channel.now();
+    let deadline = channel.timeout_duration.map(|duration| now + duration);
+    let expires_at = ttl.map(|ttl| now + ttl);
+    let state = Arc::new(RequestState::new(
+        channel.next_request_id(),
+        context,
+        deadline,
+        expires_at,
+        Some(channel.clone()),
+    ));
+    if let (Some(duration), true) = (channel.timeout_duration, channel.timeout_on_dequeue) {
+        state.set_timeout_on_dequeue(duration);
     }
+    channel.register(&state);
+    let responder = Responder::new(response_sender, state.clone());
+    let receiver = ResponseReceiver::new(response_receiver, state);
+    ((request, responder), receiver)
 }
 
 /// Creates a bounded mpsc request-response channel for communicating between
@@ -196,9 +2857,18 @@ impl<Res> Responder<Res> {
 /// }
 /// ```
 pub fn channel<Req, Res>(buffer: usize) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
+    channel_with_state(buffer, ChannelState::new(None))
+}
+
+/// Creates a bounded channel whose senders share the given channel state
+pub(crate) fn channel_with_state<Req, Res>(
+    buffer: usize,
+    channel: ChannelState,
+) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
     let (sender, receiver) = mpsc::channel::<Payload<Req, Res>>(buffer);
-    let request_sender = RequestSender::new(sender, None);
-    let request_receiver = RequestReceiver::new(receiver);
+    let channel = Arc::new(channel);
+    let request_receiver = RequestReceiver::new(receiver, sender.downgrade(), channel.clone());
+    let request_sender = RequestSender::new(sender, channel);
     (request_sender, request_receiver)
 }
 
@@ -229,17 +2899,116 @@ pub fn channel<Req, Res>(buffer: usize) -> (RequestSender<Req, Res>, RequestRece
 ///         }
 ///     });
 ///     let response = tx.send_receive(8).await;
-///     assert_eq!(response, Err(bmrng::error::RequestError::<i32>::RecvTimeoutError));
+///     assert!(matches!(response, Err(bmrng::error::RequestError::RecvTimeoutError(..))));
 /// }
 /// ```
 pub fn channel_with_timeout<Req, Res>(
     buffer: usize,
     timeout_duration: Duration,
 ) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
-    let (sender, receiver) = mpsc::channel::<Payload<Req, Res>>(buffer);
-    let request_sender = RequestSender::new(sender, Some(timeout_duration));
-    let request_receiver = RequestReceiver::new(receiver);
-    (request_sender, request_receiver)
+    channel_with_state(buffer, ChannelState::new(Some(timeout_duration)))
+}
+
+/// Creates a bounded mpsc request-response channel with both a send timeout and
+/// a response timeout
+///
+/// `send_timeout` bounds how long a sender waits for capacity while the channel is
+/// full, `response_timeout` how long it waits for the response. The two failures
+/// are reported as [`RequestError::SendTimeoutError`] and [`RequestError::RecvTimeoutError`].
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+pub fn channel_with_timeouts<Req, Res>(
+    buffer: usize,
+    send_timeout: Duration,
+    response_timeout: Duration,
+) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
+    let mut channel = ChannelState::new(Some(response_timeout));
+    channel.send_timeout = Some(send_timeout);
+    channel_with_state(buffer, channel)
+}
+
+/// Creates a bounded mpsc request-response channel with a capacity known at compile time
+///
+/// Unlike [`channel()`], a capacity of 0 is rejected at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// let (tx, rx) = bmrng::channel_const::<i32, i32, 8>();
+/// ```
+pub fn channel_const<Req, Res, const N: usize>(
+) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
+    const { assert!(N > 0, "the channel capacity must be greater than 0") };
+    channel(N)
+}
+
+/// Creates a bounded mpsc request-response channel for the [`Request`] type `R`
+///
+/// The response timeout is taken from [`Request::TIMEOUT`].
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+pub fn typed_channel<R: Request>(
+    buffer: usize,
+) -> (
+    RequestSender<R, R::Response>,
+    RequestReceiver<R, R::Response>,
+) {
+    channel_with_state(buffer, ChannelState::new(R::TIMEOUT))
+}
+
+/// Answers the requests of the receiver with a synchronous handler running on
+/// Tokio's blocking thread pool, until the channel closes
+///
+/// Use this for CPU-heavy or FFI-bound handlers that must not block the async workers.
+/// The task resolves to a [`ServeReport`] once the channel is closed.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime
+pub fn spawn_blocking_handler<Req, Res, F>(
+    mut receiver: RequestReceiver<Req, Res>,
+    mut handler: F,
+) -> JoinHandle<ServeReport>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+    F: FnMut(Req) -> Res + Send + 'static,
+{
+    task::spawn_blocking(move || {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = receiver.blocking_recv() {
+            let response = in_request_span_sync(&responder, || handler(request));
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    })
+}
+
+/// Answers the requests of the receiver with a synchronous handler running on
+/// a dedicated OS thread, until the channel closes
+///
+/// The thread returns a [`ServeReport`] once the channel is closed.
+pub fn spawn_thread_handler<Req, Res, F>(
+    mut receiver: RequestReceiver<Req, Res>,
+    mut handler: F,
+) -> thread::JoinHandle<ServeReport>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+    F: FnMut(Req) -> Res + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = receiver.blocking_recv() {
+            let response = in_request_span_sync(&responder, || handler(request));
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    })
 }
 
 /// A wrapper around [`RequestReceiver`] that implements [`Stream`].
@@ -271,7 +3040,23 @@ impl<Req, Res> Stream for RequestReceiverStream<Req, Res> {
     type Item = Payload<Req, Res>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.inner.request_receiver.poll_recv(cx)
+        loop {
+            ready!(self.inner.pause.poll_resumed(cx));
+            let payload = match self.inner.next_requeued() {
+                Some(payload) => payload,
+                None => match self.inner.poll_recv_queued(cx) {
+                    Poll::Ready(Some(payload)) => payload,
+                    poll => return poll,
+                },
+            };
+            if self.inner.pause.is_paused() {
+                self.inner.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.inner.hooks) {
+                return Poll::Ready(Some(payload));
+            }
+        }
     }
 }
 
@@ -294,3 +3079,48 @@ impl<Req, Res> From<RequestReceiver<Req, Res>> for RequestReceiverStream<Req, Re
         RequestReceiverStream::new(receiver)
     }
 }
+
+/// A wrapper around [`RequestReceiver`] that can be cloned, so several worker tasks can
+/// take requests from the same queue
+///
+/// Every request is received by exactly one of the clones. Instances are created
+/// by calling [`RequestReceiver::into_shared()`].
+#[derive(Debug)]
+pub struct SharedRequestReceiver<Req, Res> {
+    inner: Arc<Mutex<RequestReceiver<Req, Res>>>,
+}
+
+impl<Req, Res> SharedRequestReceiver<Req, Res> {
+    /// Receives the next value for this receiver.
+    ///
+    /// The workers waiting on the clones of this receiver are served in turn.
+    pub async fn recv(&self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        self.inner.lock().await.recv().await
+    }
+
+    /// Tries to receive the next value for this receiver without waiting.
+    ///
+    /// Fails with [`TryRecvError::Empty`] while another clone is waiting for a request.
+    pub fn try_recv(&self) -> Result<Payload<Req, Res>, TryRecvError> {
+        match self.inner.try_lock() {
+            Ok(mut receiver) => receiver.try_recv(),
+            Err(..) => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<Req, Res> Clone for SharedRequestReceiver<Req, Res> {
+    fn clone(&self) -> Self {
+        SharedRequestReceiver {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Req, Res> From<RequestReceiver<Req, Res>> for SharedRequestReceiver<Req, Res> {
+    fn from(receiver: RequestReceiver<Req, Res>) -> Self {
+        SharedRequestReceiver {
+            inner: Arc::new(Mutex::new(receiver)),
+        }
+    }
+}
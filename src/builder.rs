@@ -0,0 +1,424 @@
+use crate::bounded::{self, LateResponseHandler, RequestReceiver, RequestSender};
+use crate::clock::Clock;
+use crate::dead_letter::{DeadLetter, DeadLetterReason, DeadLetterSender};
+use crate::metrics::{ChannelMetrics, ChannelObserver};
+use crate::rate_limit::RateLimiter;
+use crate::state::{Admission, ChannelState, Hook, RequestId};
+use crate::unbounded::{self, UnboundedRequestReceiver, UnboundedRequestSender};
+
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+use tokio::sync::Semaphore;
+use tokio::time::Duration;
+
+/// Combines the options of a request-response channel before creating it
+///
+/// Instances are created by calling [`builder()`]
+pub struct ChannelBuilder<Req, Res> {
+    capacity: Option<usize>,
+    response_timeout: Option<Duration>,
+    timeout_on_dequeue: bool,
+    send_timeout: Option<Duration>,
+    ttl: Option<Duration>,
+    admission: Admission,
+    max_outstanding: Option<usize>,
+    cancellable: bool,
+    rate_limit: Option<(u32, u32)>,
+    name: Option<String>,
+    metrics: Option<Hook<dyn ChannelMetrics>>,
+    observer: Option<Hook<dyn ChannelObserver>>,
+    clock: Option<Hook<dyn Clock>>,
+    late_response: Option<LateResponseHandler<Res>>,
+    dead_letters: Option<DeadLetters<Req>>,
+    _types: PhantomData<fn(Req) -> Res>,
+}
+
+/// The dead-letter queue of a channel, with the handlers moving requests to it
+struct DeadLetters<Req> {
+    sender: DeadLetterSender<Req>,
+    rejected: bounded::RejectedHandler<Req>,
+    unanswered: bounded::UnansweredHandler,
+}
+
+impl<Req> Clone for DeadLetters<Req> {
+    fn clone(&self) -> Self {
+        DeadLetters {
+            sender: self.sender.clone(),
+            rejected: self.rejected.clone(),
+            unanswered: self.unanswered.clone(),
+        }
+    }
+}
+
+impl<Req, Res> ChannelBuilder<Req, Res> {
+    /// Sets the buffer capacity of a bounded channel
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = Some(capacity);
+        self
+    }
+
+    /// Sets how long the senders wait for a response, see [`channel_with_timeout()`](crate::channel_with_timeout())
+    pub fn response_timeout(mut self, duration: Duration) -> Self {
+        self.response_timeout = Some(duration);
+        self
+    }
+
+    /// Starts the response timeout when the receiver takes a request out of the
+    /// queue, instead of when the request is sent
+    ///
+    /// The time a request spends waiting in a busy queue then no longer counts
+    /// against its response timeout, only the time the handler takes does. Use
+    /// [`ttl()`](Self::ttl()) to bound the time spent in the queue.
+    pub fn timeout_on_dequeue(mut self) -> Self {
+        self.timeout_on_dequeue = true;
+        self
+    }
+
+    /// Sets how long [`RequestSender::send()`] waits for capacity when the bounded channel is full
+    ///
+    /// The request is handed back in a [`SendError`](crate::error::SendError) once
+    /// the duration elapses, or in a [`RequestError::SendTimeoutError`](crate::error::RequestError::SendTimeoutError)
+    /// by [`RequestSender::send_or_reject()`], [`RequestSender::send_receive()`] and
+    /// [`RequestSender::blocking_send_receive()`]. It bounds [`RequestSender::blocking_send()`] too.
+    pub fn send_timeout(mut self, duration: Duration) -> Self {
+        self.send_timeout = Some(duration);
+        self
+    }
+
+    /// Sets how long a request may wait in the queue before it expires
+    ///
+    /// The receiver skips expired requests, and their senders get a
+    /// [`ReceiveError::Expired`](crate::error::ReceiveError::Expired). Use
+    /// [`RequestSender::send_with_ttl()`] to override it for a single request.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Sets the load-shedding thresholds of a bounded channel
+    ///
+    /// While they are exceeded, every sending method hands the request back right
+    /// away instead of waiting for capacity. [`RequestSender::send_or_reject()`] and
+    /// [`RequestSender::send_receive()`] fail with [`RequestError::Rejected`](crate::error::RequestError::Rejected),
+    /// [`RequestSender::try_send()`] with [`TrySendError::Rejected`](crate::error::TrySendError::Rejected)
+    /// and [`RequestSender::send_timeout()`] with [`SendTimeoutError::Rejected`](crate::error::SendTimeoutError::Rejected),
+    /// while [`RequestSender::send()`] hands it back in a [`SendError`](crate::error::SendError). The age of
+    /// the queued requests is measured with the [clock](Self::clock()) of the channel.
+    /// It is ignored by unbounded channels.
+    pub fn admission(mut self, admission: Admission) -> Self {
+        self.admission = admission;
+        self
+    }
+
+    /// Limits how many requests sent over a bounded channel may wait for a response
+    /// at once, across all the sender clones
+    ///
+    /// The capacity only limits the requests waiting in the queue. With this limit,
+    /// [`RequestSender::send()`] also waits while `max` requests are queued or being
+    /// handled, and [`RequestSender::try_send()`] fails with
+    /// [`TrySendError::Full`](crate::error::TrySendError::Full). A request stops
+    /// counting once it is responded to, or once the requesting side gives up on it.
+    /// It is ignored by unbounded channels.
+    pub fn max_outstanding(mut self, max: usize) -> Self {
+        self.max_outstanding = Some(max);
+        self
+    }
+
+    /// Tracks the requests in flight by id, so they can be cancelled with
+    /// [`RequestSender::cancel()`]
+    ///
+    /// Without it, the channel does not keep a registry of the requests and
+    /// [`RequestSender::cancel()`] always returns `false`. Dropping the
+    /// [`ResponseReceiver`](crate::ResponseReceiver) still cancels a request.
+    pub fn cancellable(mut self) -> Self {
+        self.cancellable = true;
+        self
+    }
+
+    /// Paces the requests sent over a bounded channel to `per_second` on average,
+    /// letting bursts of up to `burst` requests through at once
+    ///
+    /// [`RequestSender::send()`] waits for its turn before it waits for capacity, and
+    /// [`RequestSender::try_send()`] fails with [`TrySendError::Full`](crate::error::TrySendError::Full)
+    /// when the rate is exceeded. With a [send timeout](Self::send_timeout()), a
+    /// request whose turn comes after the timeout is handed back right away instead
+    /// of waiting for nothing. It is ignored by unbounded channels.
+    ///
+    /// # Panics
+    ///
+    /// The channel panics when it is built if `per_second` or `burst` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use tokio::time::{Duration, Instant};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let (tx, rx) = bmrng::builder::<i32, i32>()
+    ///         .capacity(16)
+    ///         .rate_limit(100, 1)
+    ///         .build();
+    ///     tokio::spawn(rx.serve(|input| async move { input }));
+    ///     let start = Instant::now();
+    ///     for input in 0..3 {
+    ///         assert_eq!(tx.send_receive(input).await, Ok(input));
+    ///     }
+    ///     assert!(start.elapsed() >= Duration::from_millis(20));
+    /// }
+    /// ```
+    pub fn rate_limit(mut self, per_second: u32, burst: u32) -> Self {
+        self.rate_limit = Some((per_second, burst));
+        self
+    }
+
+    /// Names the channel
+    ///
+    /// The name is shown in the `Debug` output of the senders and returned by
+    /// [`RequestSender::name()`], to tell channels apart in logs.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Attaches the hooks to call as requests go through the channel
+    ///
+    /// See [`ChannelStats`](crate::metrics::ChannelStats) for an implementation
+    /// counting the requests and recording their latency.
+    pub fn metrics(mut self, metrics: Arc<dyn ChannelMetrics>) -> Self {
+        self.metrics = Some(Hook(metrics));
+        self
+    }
+
+    /// Attaches the callbacks to call when a request is lost, to find out why
+    /// requests go unanswered
+    pub fn observer(mut self, observer: Arc<dyn ChannelObserver>) -> Self {
+        self.observer = Some(Hook(observer));
+        self
+    }
+
+    /// Measures the timeouts, the time-to-live and the rate limit of the requests
+    /// with `clock` instead of the time of the Tokio runtime
+    ///
+    /// Use it to drive the timeouts from virtual time, see [`Clock`]. The latencies
+    /// reported to the [metrics](Self::metrics()) and the instants recorded in the
+    /// [dead letters](Self::dead_letters()) are read from it too.
+    pub fn clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = Some(Hook(clock));
+        self
+    }
+
+    /// Sets the handler of the responses that arrive after the response timeout elapsed
+    ///
+    /// Such a response can no longer be delivered to the requesting side, so it is
+    /// passed to the handler with the id of its request instead, to log or salvage
+    /// it. [`Responder::respond()`](crate::Responder::respond()) still fails with a
+    /// [`RespondError`](crate::error::RespondError).
+    pub fn on_late_response<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(RequestId, &Res) + Send + Sync + 'static,
+    {
+        self.late_response = Some(Hook(Arc::new(handler)));
+        self
+    }
+
+    /// Moves the requests the channel gives up on to a dead-letter queue created
+    /// with [`dead_letter::queue()`](crate::dead_letter::queue())
+    ///
+    /// These are the requests that expire in the queue, the requests whose responder
+    /// is dropped without responding, the requests drained by
+    /// [`RequestReceiver::close_and_drain()`], and the requests rejected by the
+    /// [admission controller](Self::admission()) from any sending method. A rejected
+    /// request is cloned, since it is also handed back in the error of the sending method.
+    /// The request of a dropped responder is usually consumed by the handler, so
+    /// only its id is recorded.
+    pub fn dead_letters(mut self, sender: DeadLetterSender<Req>) -> Self
+    where
+        Req: Clone + Send + 'static,
+    {
+        let rejected = sender.clone();
+        let unanswered = sender.clone();
+        self.dead_letters = Some(DeadLetters {
+            sender,
+            rejected: Hook(Arc::new(move |request: &Req, now| {
+                rejected.send(DeadLetter::new(
+                    Some(request.clone()),
+                    None,
+                    DeadLetterReason::Rejected,
+                    now,
+                    now,
+                ))
+            })),
+            unanswered: Hook(Arc::new(move |id, sent_at, dead_at| {
+                unanswered.send(DeadLetter::new(
+                    None,
+                    Some(id),
+                    DeadLetterReason::Unanswered,
+                    sent_at,
+                    dead_at,
+                ))
+            })),
+        });
+        self
+    }
+
+    /// Creates a bounded channel with the configured options
+    ///
+    /// # Panics
+    ///
+    /// Panics if no capacity was set, or if the capacity is 0, just like the Tokio MPSC channel
+    pub fn build(mut self) -> (RequestSender<Req, Res>, RequestReceiver<Req, Res>) {
+        let capacity = self
+            .capacity
+            .expect("a bounded channel requires a capacity");
+        let late_response = self.late_response.take();
+        let dead_letters = self.dead_letters.take();
+        let (mut sender, mut receiver) = bounded::channel_with_state(capacity, self.into_state());
+        receiver.hooks.late_response = late_response;
+        if let Some(dead_letters) = dead_letters {
+            sender.rejected = Some(dead_letters.rejected);
+            receiver.hooks.unanswered = Some(dead_letters.unanswered);
+            receiver.hooks.dead_letters = Some(dead_letters.sender);
+        }
+        (sender, receiver)
+    }
+
+    /// Creates an unbounded channel with the configured options
+    ///
+    /// The capacity, the send timeout, the admission controller and the rate limit are ignored,
+    /// since sending to an unbounded channel never waits.
+    pub fn build_unbounded(
+        mut self,
+    ) -> (
+        UnboundedRequestSender<Req, Res>,
+        UnboundedRequestReceiver<Req, Res>,
+    ) {
+        self.admission = Admission::default();
+        self.max_outstanding = None;
+        self.rate_limit = None;
+        let late_response = self.late_response.take();
+        let dead_letters = self.dead_letters.take();
+        let (sender, mut receiver) = unbounded::channel_with_state(self.into_state());
+        receiver.hooks.late_response = late_response;
+        if let Some(dead_letters) = dead_letters {
+            receiver.hooks.unanswered = Some(dead_letters.unanswered);
+            receiver.hooks.dead_letters = Some(dead_letters.sender);
+        }
+        (sender, receiver)
+    }
+
+    fn into_state(self) -> ChannelState {
+        let mut state = ChannelState::new(self.response_timeout);
+        state.timeout_on_dequeue = self.timeout_on_dequeue;
+        state.name = self.name;
+        state.send_timeout = self.send_timeout;
+        state.ttl = self.ttl;
+        state.admission = self.admission;
+        state.max_outstanding = self
+            .max_outstanding
+            .map(|max| Arc::new(Semaphore::new(max)));
+        if self.cancellable {
+            state.outstanding = Some(Mutex::default());
+        }
+        state.metrics = self.metrics;
+        state.observer = self.observer;
+        state.clock = self.clock;
+        state.rate_limit = self
+            .rate_limit
+            .map(|(per_second, burst)| RateLimiter::new(per_second, burst, state.now()));
+        state
+    }
+}
+
+impl<Req, Res> Clone for ChannelBuilder<Req, Res> {
+    fn clone(&self) -> Self {
+        ChannelBuilder {
+            capacity: self.capacity,
+            response_timeout: self.response_timeout,
+            timeout_on_dequeue: self.timeout_on_dequeue,
+            send_timeout: self.send_timeout,
+            ttl: self.ttl,
+            admission: self.admission,
+            max_outstanding: self.max_outstanding,
+            cancellable: self.cancellable,
+            rate_limit: self.rate_limit,
+            name: self.name.clone(),
+            metrics: self.metrics.clone(),
+            observer: self.observer.clone(),
+            clock: self.clock.clone(),
+            late_response: self.late_response.clone(),
+            dead_letters: self.dead_letters.clone(),
+            _types: PhantomData,
+        }
+    }
+}
+
+impl<Req, Res> fmt::Debug for ChannelBuilder<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ChannelBuilder")
+            .field("capacity", &self.capacity)
+            .field("response_timeout", &self.response_timeout)
+            .field("timeout_on_dequeue", &self.timeout_on_dequeue)
+            .field("send_timeout", &self.send_timeout)
+            .field("ttl", &self.ttl)
+            .field("admission", &self.admission)
+            .field("max_outstanding", &self.max_outstanding)
+            .field("cancellable", &self.cancellable)
+            .field("rate_limit", &self.rate_limit)
+            .field("name", &self.name)
+            .field("metrics", &self.metrics)
+            .field("observer", &self.observer)
+            .field("clock", &self.clock)
+            .field("late_response", &self.late_response)
+            .field(
+                "dead_letters",
+                &self
+                    .dead_letters
+                    .as_ref()
+                    .map(|dead_letters| &dead_letters.sender),
+            )
+            .finish()
+    }
+}
+
+/// Creates a [`ChannelBuilder`] to configure a bounded or an unbounded channel
+///
+/// # Examples
+///
+/// ```rust
+/// use tokio::time::{Duration, Instant};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, rx) = bmrng::builder::<i32, i32>()
+///         .capacity(16)
+///         .response_timeout(Duration::from_secs(1))
+///         .send_timeout(Duration::from_millis(100))
+///         .name("db-requests")
+///         .build();
+///     tokio::spawn(rx.serve(|input| async move { input * 2 }));
+///     assert_eq!(tx.name(), Some("db-requests"));
+///     assert_eq!(tx.send_receive(21).await, Ok(42));
+/// }
+/// ```
+pub fn builder<Req, Res>() -> ChannelBuilder<Req, Res> {
+    ChannelBuilder {
+        capacity: None,
+        response_timeout: None,
+        timeout_on_dequeue: false,
+        send_timeout: None,
+        ttl: None,
+        admission: Admission::default(),
+        max_outstanding: None,
+        cancellable: false,
+        rate_limit: None,
+        name: None,
+        metrics: None,
+        observer: None,
+        clock: None,
+        late_response: None,
+        dead_letters: None,
+        _types: PhantomData,
+    }
+}
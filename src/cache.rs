@@ -0,0 +1,185 @@
+use crate::bounded::RequestSender;
+use crate::error::RequestError;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::time::{Duration, Instant};
+
+/// A [`RequestSender`] that serves repeated identical requests from a cache
+///
+/// Successful responses are kept for the time-to-live of the cache. Once the cache
+/// is full, the least recently used response is evicted. Errors are never cached,
+/// so a failed request is sent again the next time. Clones of the sender share
+/// the same cache.
+///
+/// Instances are created by the [`CachedRequestSender::new()`] function.
+pub struct CachedRequestSender<Req, Res> {
+    sender: RequestSender<Req, Res>,
+    cache: Arc<Mutex<Lru<Req, Res>>>,
+}
+
+struct Lru<Req, Res> {
+    entries: HashMap<Req, Entry<Res>>,
+    /// The cached requests, least recently used first
+    recency: BTreeMap<u64, Req>,
+    tick: u64,
+    capacity: usize,
+    ttl: Duration,
+}
+
+struct Entry<Res> {
+    response: Res,
+    cached_at: Instant,
+    used_at: u64,
+}
+
+impl<Req, Res> Lru<Req, Res>
+where
+    Req: Hash + Eq + Clone,
+    Res: Clone,
+{
+    fn get(&mut self, request: &Req) -> Option<Res> {
+        let entry = self.entries.get_mut(request)?;
+        if entry.cached_at.elapsed() >= self.ttl {
+            let used_at = entry.used_at;
+            self.entries.remove(request);
+            self.recency.remove(&used_at);
+            return None;
+        }
+        self.tick += 1;
+        let request = self
+            .recency
+            .remove(&entry.used_at)
+            .expect("every cached request has a recency");
+        entry.used_at = self.tick;
+        self.recency.insert(self.tick, request);
+        Some(entry.response.clone())
+    }
+
+    fn insert(&mut self, request: Req, response: Res) {
+        self.remove(&request);
+        while self.entries.len() >= self.capacity {
+            let (_, oldest) = self
+                .recency
+                .pop_first()
+                .expect("a full cache has a least recently used request");
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        self.recency.insert(self.tick, request.clone());
+        let entry = Entry {
+            response,
+            cached_at: Instant::now(),
+            used_at: self.tick,
+        };
+        self.entries.insert(request, entry);
+    }
+
+    fn remove(&mut self, request: &Req) -> bool {
+        match self.entries.remove(request) {
+            Some(entry) => {
+                self.recency.remove(&entry.used_at);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<Req, Res> CachedRequestSender<Req, Res>
+where
+    Req: Hash + Eq + Clone,
+    Res: Clone,
+{
+    /// Wraps the sender with a cache holding up to `capacity` responses, each for `ttl`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the capacity is 0
+    pub fn new(sender: RequestSender<Req, Res>, capacity: usize, ttl: Duration) -> Self {
+        assert!(capacity > 0, "the cache capacity must be greater than 0");
+        CachedRequestSender {
+            sender,
+            cache: Arc::new(Mutex::new(Lru {
+                entries: HashMap::with_capacity(capacity),
+                recency: BTreeMap::new(),
+                tick: 0,
+                capacity,
+                ttl,
+            })),
+        }
+    }
+
+    /// Returns the cached response of an identical request, or sends the request
+    /// over the channel, caches the response and returns it
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        if let Some(response) = self.lock().get(&request) {
+            return Ok(response);
+        }
+        let key = request.clone();
+        let response = self.sender.send_receive(request).await?;
+        self.lock().insert(key, response.clone());
+        Ok(response)
+    }
+
+    /// Removes the cached response of the request, so the next identical request is
+    /// sent over the channel
+    ///
+    /// Returns `true` if a response was cached.
+    pub fn invalidate(&self, request: &Req) -> bool {
+        self.lock().remove(request)
+    }
+
+    /// Removes all the cached responses
+    pub fn clear(&self) {
+        let mut cache = self.lock();
+        cache.entries.clear();
+        cache.recency.clear();
+    }
+
+    /// Returns the number of cached responses, including the expired ones that have
+    /// not been evicted yet
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns `true` if no responses are cached
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Req, Res> CachedRequestSender<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Lru<Req, Res>> {
+        self.cache
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the wrapped sender, to send requests that bypass the cache
+    pub fn inner(&self) -> &RequestSender<Req, Res> {
+        &self.sender
+    }
+}
+
+impl<Req, Res> Clone for CachedRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        CachedRequestSender {
+            sender: self.sender.clone(),
+            cache: self.cache.clone(),
+        }
+    }
+}
+
+impl<Req, Res> fmt::Debug for CachedRequestSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cache = self.lock();
+        fmt.debug_struct("CachedRequestSender")
+            .field("cached", &cache.entries.len())
+            .field("capacity", &cache.capacity)
+            .field("ttl", &cache.ttl)
+            .finish()
+    }
+}
@@ -0,0 +1,315 @@
+use crate::bounded::RequestSender;
+use crate::error::RequestError;
+use crate::Request;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::time::{Duration, Instant};
+
+/// Whether a [`CircuitBreakerSender`] lets requests through
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CircuitState {
+    /// The receiver is healthy, every request is sent
+    Closed,
+    /// Too many recent requests failed, requests fail fast until the cooldown elapses
+    Open,
+    /// The cooldown elapsed, a single trial request decides whether the circuit closes again
+    HalfOpen,
+}
+
+/// The thresholds of a [`CircuitBreakerSender`]
+///
+/// The circuit opens once the share of failed requests among the last
+/// [`window()`](Self::window()) requests reaches the [`failure_rate()`](Self::failure_rate()).
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::CircuitBreaker;
+/// use tokio::time::Duration;
+///
+/// let breaker = CircuitBreaker::new()
+///     .window(10)
+///     .failure_rate(0.5)
+///     .cooldown(Duration::from_secs(1));
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct CircuitBreaker {
+    window: usize,
+    failure_rate: f64,
+    cooldown: Duration,
+}
+
+impl CircuitBreaker {
+    /// Creates thresholds opening the circuit for 5 seconds once half of the last
+    /// 20 requests failed
+    pub fn new() -> Self {
+        CircuitBreaker {
+            window: 20,
+            failure_rate: 0.5,
+            cooldown: Duration::from_secs(5),
+        }
+    }
+
+    /// Sets the number of recent requests the failure rate is computed over
+    ///
+    /// # Panics
+    ///
+    /// Panics if `window` is 0
+    pub fn window(mut self, window: usize) -> Self {
+        assert!(window > 0, "a circuit breaker requires window > 0");
+        self.window = window;
+        self
+    }
+
+    /// Sets the share of failed requests, between 0 and 1, that opens the circuit
+    pub fn failure_rate(mut self, failure_rate: f64) -> Self {
+        self.failure_rate = failure_rate;
+        self
+    }
+
+    /// Sets how long the circuit stays open before a trial request is let through
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        CircuitBreaker::new()
+    }
+}
+
+/// Returns `true` if the error tells that the receiver is unhealthy
+///
+/// A closed channel or a request given up on by the requesting side says nothing
+/// about the health of the receiver, neither do the overflow policies dropping
+/// requests on purpose.
+fn is_failure<T>(err: &RequestError<T>) -> bool {
+    matches!(
+        err,
+        RequestError::RecvError(..)
+            | RequestError::RecvTimeoutError(..)
+            | RequestError::HandlerPanicked
+            | RequestError::SendTimeoutError(..)
+            | RequestError::Expired
+            | RequestError::Rejected(..)
+    )
+}
+
+/// The recent outcomes of the requests, shared by the clones of a [`CircuitBreakerSender`]
+#[derive(Debug)]
+struct Health {
+    /// Whether each of the last requests failed, oldest first
+    outcomes: VecDeque<bool>,
+    failures: usize,
+    opened_at: Option<Instant>,
+    /// Set while the trial request of a half-open circuit is waiting for its response
+    trial: bool,
+}
+
+impl Health {
+    fn state(&self, breaker: &CircuitBreaker) -> CircuitState {
+        match self.opened_at {
+            Some(opened_at) if !self.trial && opened_at.elapsed() >= breaker.cooldown => {
+                CircuitState::HalfOpen
+            }
+            Some(..) => CircuitState::Open,
+            None => CircuitState::Closed,
+        }
+    }
+
+    fn open(&mut self) {
+        self.opened_at = Some(Instant::now());
+        self.outcomes.clear();
+        self.failures = 0;
+    }
+
+    fn record(&mut self, breaker: &CircuitBreaker, failed: bool) {
+        if self.opened_at.is_some() {
+            // The circuit was opened by the requests that finished meanwhile
+            return;
+        }
+        self.outcomes.push_back(failed);
+        self.failures += usize::from(failed);
+        if self.outcomes.len() > breaker.window && self.outcomes.pop_front() == Some(true) {
+            self.failures -= 1;
+        }
+        if self.outcomes.len() == breaker.window
+            && self.failures as f64 >= breaker.failure_rate * breaker.window as f64
+        {
+            self.open();
+        }
+    }
+
+    fn record_trial(&mut self, failed: bool) {
+        self.trial = false;
+        if failed {
+            self.open();
+        } else {
+            self.opened_at = None;
+        }
+    }
+}
+
+/// Lets the next request of a half-open circuit through if the trial request is
+/// cancelled before it gets its response
+struct Trial<'a> {
+    health: &'a Mutex<Health>,
+    finished: bool,
+}
+
+impl Drop for Trial<'_> {
+    fn drop(&mut self) {
+        if !self.finished {
+            lock(self.health).trial = false;
+        }
+    }
+}
+
+fn lock(health: &Mutex<Health>) -> MutexGuard<'_, Health> {
+    health
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// A [`RequestSender`] that fails fast while its receiver is unhealthy
+///
+/// The sender tracks the outcomes of the recent requests sent with
+/// [`send_receive()`](Self::send_receive()). Once too many of them failed, the
+/// circuit opens: requests are handed back right away in a
+/// [`RequestError::CircuitOpen`] for the cooldown of the [`CircuitBreaker`]. Then
+/// a single trial request is sent, and the circuit closes again if it succeeds.
+/// Clones of the sender share the circuit.
+///
+/// Timeouts, dropped or panicking handlers, and requests that stay stuck in or
+/// are shed by an overloaded queue count as failures. Cancelled requests, a
+/// closed channel and a receiver shutting down do not.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::error::RequestError;
+/// use bmrng::{CircuitBreaker, CircuitBreakerSender, CircuitState, Request};
+/// use tokio::time::Duration;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Charge(u32);
+///
+/// impl Request for Charge {
+///     type Response = bool;
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::typed_channel::<Charge>(4);
+///     let breaker = CircuitBreaker::new().window(1).cooldown(Duration::from_secs(1));
+///     let tx = CircuitBreakerSender::new(tx, breaker);
+///     tokio::spawn(async move {
+///         while let Ok((_, responder)) = rx.recv().await {
+///             drop(responder);
+///         }
+///     });
+///     assert!(matches!(
+///         tx.send_receive(Charge(1)).await,
+///         Err(RequestError::RecvError(..))
+///     ));
+///     assert_eq!(tx.state(), CircuitState::Open);
+///     assert_eq!(
+///         tx.send_receive(Charge(2)).await,
+///         Err(RequestError::CircuitOpen(Charge(2)))
+///     );
+/// }
+/// ```
+pub struct CircuitBreakerSender<R: Request> {
+    sender: RequestSender<R, R::Response>,
+    breaker: CircuitBreaker,
+    health: Arc<Mutex<Health>>,
+}
+
+impl<R: Request> CircuitBreakerSender<R> {
+    /// Wraps the sender to stop sending requests while the receiver is unhealthy
+    pub fn new(sender: RequestSender<R, R::Response>, breaker: CircuitBreaker) -> Self {
+        CircuitBreakerSender {
+            sender,
+            breaker,
+            health: Arc::new(Mutex::new(Health {
+                outcomes: VecDeque::with_capacity(breaker.window),
+                failures: 0,
+                opened_at: None,
+                trial: false,
+            })),
+        }
+    }
+
+    /// Send a request over the channel unless the circuit is open, wait for the
+    /// response and return it
+    ///
+    /// Fails right away with [`RequestError::CircuitOpen`] while the circuit is
+    /// open, or while the trial request of a half-open circuit is in flight.
+    pub async fn send_receive(&self, request: R) -> Result<R::Response, RequestError<R>> {
+        let mut trial = {
+            let mut health = lock(&self.health);
+            match health.state(&self.breaker) {
+                CircuitState::Closed => None,
+                CircuitState::Open => return Err(RequestError::CircuitOpen(request)),
+                CircuitState::HalfOpen => {
+                    health.trial = true;
+                    Some(Trial {
+                        health: &self.health,
+                        finished: false,
+                    })
+                }
+            }
+        };
+        let result = self.sender.send_receive(request).await;
+        match &result {
+            Err(RequestError::SendError(..))
+            | Err(RequestError::Cancelled)
+            | Err(RequestError::ShutDown) => {}
+            result => {
+                let failed = result.as_ref().is_err_and(is_failure);
+                let mut health = lock(&self.health);
+                match &mut trial {
+                    Some(trial) => {
+                        trial.finished = true;
+                        health.record_trial(failed);
+                    }
+                    None => health.record(&self.breaker, failed),
+                }
+            }
+        }
+        result
+    }
+
+    /// Returns whether the circuit lets requests through
+    pub fn state(&self) -> CircuitState {
+        lock(&self.health).state(&self.breaker)
+    }
+
+    /// Returns the wrapped sender, to send requests that bypass the circuit
+    pub fn inner(&self) -> &RequestSender<R, R::Response> {
+        &self.sender
+    }
+}
+
+impl<R: Request> Clone for CircuitBreakerSender<R> {
+    fn clone(&self) -> Self {
+        CircuitBreakerSender {
+            sender: self.sender.clone(),
+            breaker: self.breaker,
+            health: self.health.clone(),
+        }
+    }
+}
+
+impl<R: Request> fmt::Debug for CircuitBreakerSender<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CircuitBreakerSender")
+            .field("breaker", &self.breaker)
+            .field("state", &self.state())
+            .finish()
+    }
+}
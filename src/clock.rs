@@ -0,0 +1,76 @@
+use std::future::Future;
+use std::pin::Pin;
+use tokio::time::Instant;
+
+/// The future returned by [`Clock::sleep_until()`]
+pub type ClockSleep = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+/// The source of time the timeouts, the time-to-live and the rate limit of the
+/// requests of a channel are measured with
+///
+/// Channels use [`TokioClock`] by default. Attach another clock with
+/// [`ChannelBuilder::clock()`](crate::ChannelBuilder::clock()) to drive the
+/// timeouts from virtual time, like the clock of a simulation framework or of a
+/// custom test harness, without pausing the time of the whole Tokio runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::{Clock, ClockSleep};
+/// use std::sync::{Arc, Mutex};
+/// use tokio::sync::watch;
+/// use tokio::time::{Duration, Instant};
+///
+/// /// A clock that only moves forward when the test advances it
+/// #[derive(Debug)]
+/// struct ManualClock(watch::Sender<Instant>);
+///
+/// impl Clock for ManualClock {
+///     fn now(&self) -> Instant {
+///         *self.0.borrow()
+///     }
+///
+///     fn sleep_until(&self, deadline: Instant) -> ClockSleep {
+///         let mut now = self.0.subscribe();
+///         Box::pin(async move {
+///             let _ = now.wait_for(|now| *now >= deadline).await;
+///         })
+///     }
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let clock = Arc::new(ManualClock(watch::channel(Instant::now()).0));
+///     let (tx, _rx) = bmrng::builder::<i32, i32>()
+///         .capacity(1)
+///         .response_timeout(Duration::from_secs(60))
+///         .clock(clock.clone())
+///         .build();
+///     let response = tx.send(1).await.unwrap();
+///     clock.0.send_modify(|now| *now += Duration::from_secs(60));
+///     assert!(response.await.is_err());
+/// }
+/// ```
+pub trait Clock: Send + Sync {
+    /// Returns the current instant
+    fn now(&self) -> Instant;
+
+    /// Returns a future completing once the clock reaches `deadline`
+    fn sleep_until(&self, deadline: Instant) -> ClockSleep;
+}
+
+/// The default [`Clock`], reading the time of the Tokio runtime
+///
+/// It honors [`tokio::time::pause()`].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct TokioClock;
+
+impl Clock for TokioClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> ClockSleep {
+        Box::pin(tokio::time::sleep_until(deadline))
+    }
+}
@@ -0,0 +1,136 @@
+use crate::bounded::RequestSender;
+use crate::error::RequestError;
+use crate::Request;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::oneshot;
+
+type Outcome<R> = Result<<R as Request>::Response, RequestError<R>>;
+type InFlight<R> = HashMap<R, Vec<oneshot::Sender<Outcome<R>>>>;
+
+/// A [`RequestSender`] that coalesces concurrent identical requests into one
+///
+/// While a request is waiting for its response, identical requests sent with
+/// [`send_receive()`](Self::send_receive()) are not delivered to the receiver again.
+/// They wait for the same response instead, which is cloned for every caller.
+/// Clones of the sender coalesce their requests with each other.
+pub struct CoalescingSender<R: Request> {
+    sender: RequestSender<R, R::Response>,
+    in_flight: Arc<Mutex<InFlight<R>>>,
+}
+
+/// Removes the in-flight entry of the request, even if the request that leads it
+/// is cancelled, so the waiting callers retry instead of waiting forever
+struct Leader<'a, R: Request + Hash + Eq> {
+    in_flight: &'a Mutex<InFlight<R>>,
+    request: Option<R>,
+}
+
+impl<R: Request + Hash + Eq> Leader<'_, R> {
+    fn take_waiters(&mut self) -> Vec<oneshot::Sender<Outcome<R>>> {
+        match self.request.take() {
+            Some(request) => lock(self.in_flight).remove(&request).unwrap_or_default(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl<R: Request + Hash + Eq> Drop for Leader<'_, R> {
+    fn drop(&mut self) {
+        self.take_waiters();
+    }
+}
+
+fn lock<R: Request>(in_flight: &Mutex<InFlight<R>>) -> MutexGuard<'_, InFlight<R>> {
+    in_flight
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+impl<R> CoalescingSender<R>
+where
+    R: Request + Hash + Eq + Clone,
+    R::Response: Clone,
+{
+    /// Wraps the sender to coalesce its concurrent identical requests
+    pub fn new(sender: RequestSender<R, R::Response>) -> Self {
+        CoalescingSender {
+            sender,
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Send a request over the channel, unless an identical request is already
+    /// waiting for a response, wait for the response and return it
+    ///
+    /// If the request that was delivered fails, every caller waiting for it gets
+    /// the same error.
+    pub async fn send_receive(&self, request: R) -> Result<R::Response, RequestError<R>> {
+        loop {
+            let waiter = {
+                let mut in_flight = lock(&self.in_flight);
+                match in_flight.get_mut(&request) {
+                    Some(waiters) => {
+                        let (sender, receiver) = oneshot::channel();
+                        waiters.push(sender);
+                        Some(receiver)
+                    }
+                    None => {
+                        in_flight.insert(request.clone(), Vec::new());
+                        None
+                    }
+                }
+            };
+            match waiter {
+                Some(receiver) => match receiver.await {
+                    Ok(outcome) => return outcome,
+                    // The leading caller was cancelled, so one of the waiters takes over
+                    Err(..) => continue,
+                },
+                None => {
+                    let mut leader = Leader {
+                        in_flight: &self.in_flight,
+                        request: Some(request.clone()),
+                    };
+                    let outcome = self.sender.send_receive(request).await;
+                    for waiter in leader.take_waiters() {
+                        let _ = waiter.send(outcome.clone());
+                    }
+                    return outcome;
+                }
+            }
+        }
+    }
+
+    /// Returns the number of distinct requests waiting for a response
+    pub fn in_flight(&self) -> usize {
+        lock(&self.in_flight).len()
+    }
+}
+
+impl<R: Request> CoalescingSender<R> {
+    /// Returns the wrapped sender, to send requests that are never coalesced
+    pub fn inner(&self) -> &RequestSender<R, R::Response> {
+        &self.sender
+    }
+}
+
+impl<R: Request> Clone for CoalescingSender<R> {
+    fn clone(&self) -> Self {
+        CoalescingSender {
+            sender: self.sender.clone(),
+            in_flight: self.in_flight.clone(),
+        }
+    }
+}
+
+impl<R: Request> fmt::Debug for CoalescingSender<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("CoalescingSender")
+            .field("in_flight", &lock(&self.in_flight).len())
+            .finish()
+    }
+}
@@ -0,0 +1,179 @@
+use crate::state::RequestId;
+
+use std::fmt;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+
+/// The reason why a request ended up in the dead-letter queue
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DeadLetterReason {
+    /// The request stayed queued for longer than its time-to-live
+    Expired,
+    /// The receiver took the request, but dropped its responder without responding
+    Unanswered,
+    /// The request was rejected by the admission controller of an overloaded channel
+    Rejected,
+    /// The request was still queued when the receiver closed and drained the channel
+    Drained,
+}
+
+/// A request the channel gave up on, with the reason and the timestamps
+#[derive(Debug, Clone)]
+pub struct DeadLetter<Req> {
+    request: Option<Req>,
+    id: Option<RequestId>,
+    reason: DeadLetterReason,
+    sent_at: Instant,
+    dead_at: Instant,
+}
+
+impl<Req> DeadLetter<Req> {
+    pub(crate) fn new(
+        request: Option<Req>,
+        id: Option<RequestId>,
+        reason: DeadLetterReason,
+        sent_at: Instant,
+        dead_at: Instant,
+    ) -> Self {
+        DeadLetter {
+            request,
+            id,
+            reason,
+            sent_at,
+            dead_at,
+        }
+    }
+
+    /// Returns the request, unless the handler consumed it before dropping its
+    /// responder unanswered
+    pub fn request(&self) -> Option<&Req> {
+        self.request.as_ref()
+    }
+
+    /// Returns the request to retry it, see [`request()`](Self::request())
+    pub fn into_request(self) -> Option<Req> {
+        self.request
+    }
+
+    /// Returns the id of the request, unless it was rejected before it was sent
+    pub fn id(&self) -> Option<RequestId> {
+        self.id
+    }
+
+    /// Returns why the channel gave up on the request
+    pub fn reason(&self) -> DeadLetterReason {
+        self.reason
+    }
+
+    /// Returns the instant the request was sent at, read from the
+    /// [clock](crate::Clock) of the channel
+    pub fn sent_at(&self) -> Instant {
+        self.sent_at
+    }
+
+    /// Returns the instant the channel gave up on the request at
+    pub fn dead_at(&self) -> Instant {
+        self.dead_at
+    }
+}
+
+/// The sending half of a dead-letter queue, attached to a channel with
+/// [`ChannelBuilder::dead_letters()`](crate::ChannelBuilder::dead_letters())
+///
+/// Instances are created by the [`queue()`] function.
+pub struct DeadLetterSender<Req> {
+    sender: mpsc::UnboundedSender<DeadLetter<Req>>,
+}
+
+impl<Req> DeadLetterSender<Req> {
+    /// Moves the dead letter to the queue, or drops it if the queue was dropped
+    pub(crate) fn send(&self, letter: DeadLetter<Req>) {
+        let _ = self.sender.send(letter);
+    }
+}
+
+impl<Req> Clone for DeadLetterSender<Req> {
+    fn clone(&self) -> Self {
+        DeadLetterSender {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<Req> fmt::Debug for DeadLetterSender<Req> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DeadLetterSender")
+            .field("closed", &self.sender.is_closed())
+            .finish()
+    }
+}
+
+/// The receiving half of a dead-letter queue
+///
+/// Instances are created by the [`queue()`] function.
+pub struct DeadLetterReceiver<Req> {
+    receiver: mpsc::UnboundedReceiver<DeadLetter<Req>>,
+}
+
+impl<Req> DeadLetterReceiver<Req> {
+    /// Receives the next dead letter
+    ///
+    /// Returns `None` once the channels the queue is attached to are dropped and
+    /// the queue is drained.
+    pub async fn recv(&mut self) -> Option<DeadLetter<Req>> {
+        self.receiver.recv().await
+    }
+
+    /// Receives the next dead letter without waiting, or returns `None` if the queue is empty
+    pub fn try_recv(&mut self) -> Option<DeadLetter<Req>> {
+        self.receiver.try_recv().ok()
+    }
+
+    /// Returns the number of dead letters in the queue
+    pub fn len(&self) -> usize {
+        self.receiver.len()
+    }
+
+    /// Returns `true` if the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.receiver.is_empty()
+    }
+}
+
+impl<Req> fmt::Debug for DeadLetterReceiver<Req> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DeadLetterReceiver")
+            .field("len", &self.receiver.len())
+            .finish()
+    }
+}
+
+/// Creates an unbounded dead-letter queue to attach to one or more channels
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::dead_letter::DeadLetterReason;
+/// use tokio::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<String>();
+///     let (tx, mut rx) = bmrng::builder::<String, usize>()
+///         .capacity(4)
+///         .ttl(Duration::from_millis(10))
+///         .dead_letters(dead_letters)
+///         .build();
+///     let _response = tx.send("stale".to_string()).await.unwrap();
+///     tokio::time::sleep(Duration::from_millis(20)).await;
+///     assert!(rx.try_recv().is_err());
+///
+///     let letter = janitor.recv().await.unwrap();
+///     assert_eq!(letter.reason(), DeadLetterReason::Expired);
+///     assert_eq!(letter.into_request(), Some("stale".to_string()));
+/// }
+/// ```
+pub fn queue<Req>() -> (DeadLetterSender<Req>, DeadLetterReceiver<Req>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    (DeadLetterSender { sender }, DeadLetterReceiver { receiver })
+}
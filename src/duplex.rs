@@ -0,0 +1,131 @@
+use crate::bounded::{
+    channel_with_state, Payload, RequestReceiver, RequestSender, ResponseReceiver,
+};
+use crate::error::{RequestError, SendError};
+use crate::state::ChannelState;
+use crate::Request;
+
+use tokio::time::Duration;
+
+/// One end of a bidirectional request-response pair, sending `Out` requests to
+/// the other end and receiving its `In` requests
+///
+/// Instances are created by the [`duplex()`] and [`duplex_with_timeout()`] functions.
+#[derive(Debug)]
+pub struct DuplexHandle<Out: Request, In: Request> {
+    sender: RequestSender<Out, Out::Response>,
+    receiver: RequestReceiver<In, In::Response>,
+}
+
+impl<Out: Request, In: Request> DuplexHandle<Out, In> {
+    /// Send a request to the other end, open the response channel
+    pub async fn send(
+        &self,
+        request: Out,
+    ) -> Result<ResponseReceiver<Out::Response>, SendError<Out>> {
+        self.sender.send(request).await
+    }
+
+    /// Send a request to the other end, wait for the response and return it
+    pub async fn send_receive(&self, request: Out) -> Result<Out::Response, RequestError<Out>> {
+        self.sender.send_receive(request).await
+    }
+
+    /// Receives the next request sent by the other end
+    pub async fn recv(&mut self) -> Result<Payload<In, In::Response>, RequestError<In>> {
+        self.receiver.recv().await
+    }
+
+    /// Returns the sender to the other end
+    pub fn sender(&self) -> &RequestSender<Out, Out::Response> {
+        &self.sender
+    }
+
+    /// Splits the handle into its sender and receiver, so both directions can
+    /// be driven by separate tasks
+    pub fn into_parts(
+        self,
+    ) -> (
+        RequestSender<Out, Out::Response>,
+        RequestReceiver<In, In::Response>,
+    ) {
+        (self.sender, self.receiver)
+    }
+}
+
+fn new_duplex<A: Request, B: Request>(
+    buffer: usize,
+    a_timeout: Option<Duration>,
+    b_timeout: Option<Duration>,
+) -> (DuplexHandle<A, B>, DuplexHandle<B, A>) {
+    let (a_sender, a_receiver) = channel_with_state(buffer, ChannelState::new(a_timeout));
+    let (b_sender, b_receiver) = channel_with_state(buffer, ChannelState::new(b_timeout));
+    (
+        DuplexHandle {
+            sender: a_sender,
+            receiver: b_receiver,
+        },
+        DuplexHandle {
+            sender: b_sender,
+            receiver: a_receiver,
+        },
+    )
+}
+
+/// Creates a pair of handles wired to each other, where the first one sends `A`
+/// requests and answers `B` requests, and the second one the other way around
+///
+/// Both directions have the given buffer capacity, and each one takes its response
+/// timeout from the [`Request::TIMEOUT`] of its request type.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+///
+/// # Examples
+///
+/// ```rust
+/// #[derive(Debug, PartialEq)]
+/// struct Ping;
+/// #[derive(Debug, PartialEq)]
+/// struct Pong;
+///
+/// impl bmrng::Request for Ping {
+///     type Response = &'static str;
+/// }
+///
+/// impl bmrng::Request for Pong {
+///     type Response = &'static str;
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (mut left, mut right) = bmrng::duplex::<Ping, Pong>(1);
+///     tokio::spawn(async move {
+///         let (_ping, responder) = right.recv().await.unwrap();
+///         let _ = responder.respond("pong");
+///         assert_eq!(right.send_receive(Pong).await, Ok("ping"));
+///     });
+///     assert_eq!(left.send_receive(Ping).await, Ok("pong"));
+///     let (_pong, responder) = left.recv().await.unwrap();
+///     let _ = responder.respond("ping");
+/// }
+/// ```
+pub fn duplex<A: Request, B: Request>(buffer: usize) -> (DuplexHandle<A, B>, DuplexHandle<B, A>) {
+    new_duplex(buffer, A::TIMEOUT, B::TIMEOUT)
+}
+
+/// Creates a pair of handles wired to each other like [`duplex()`], with the same
+/// response timeout in both directions
+///
+/// The timeout overrides the [`Request::TIMEOUT`] of both request types.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+pub fn duplex_with_timeout<A: Request, B: Request>(
+    buffer: usize,
+    timeout_duration: Duration,
+) -> (DuplexHandle<A, B>, DuplexHandle<B, A>) {
+    new_duplex(buffer, Some(timeout_duration), Some(timeout_duration))
+}
@@ -0,0 +1,317 @@
+use crate::bounded::{channel as bounded_channel, RequestReceiver, RequestSender, Responder};
+use crate::error::{ReceiveError, RequestError, RespondError};
+use crate::metrics::TypeMetrics;
+use crate::state::Hook;
+use crate::Request;
+
+use std::any::{type_name, Any};
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::Arc;
+use tokio::time::Instant;
+
+type AnyResponse = Box<dyn Any + Send>;
+
+/// A request of any [`Request`] type, boxed to be sent over a dynamic channel
+struct DynRequest {
+    request: Box<dyn Any + Send>,
+    type_name: &'static str,
+    sent_at: Instant,
+}
+
+impl fmt::Debug for DynRequest {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DynRequest")
+            .field("type_name", &self.type_name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Send requests of any [`Request`] type to the associated [`DynRequestReceiver`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct DynRequestSender {
+    sender: RequestSender<DynRequest, AnyResponse>,
+    metrics: Option<Hook<dyn TypeMetrics>>,
+}
+
+/// Receive requests of any [`Request`] type from the associated [`DynRequestSender`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct DynRequestReceiver {
+    receiver: RequestReceiver<DynRequest, AnyResponse>,
+    metrics: Option<Hook<dyn TypeMetrics>>,
+}
+
+/// A request received from a [`DynRequestSender`], together with its responder
+///
+/// Use [`downcast()`](Self::downcast()) to get the concrete request and a
+/// [`TypedResponder`] that only accepts the matching response type.
+#[derive(Debug)]
+pub struct DynPayload {
+    request: DynRequest,
+    responder: Box<Responder<AnyResponse>>,
+}
+
+/// Send the response of a request of type `R` back to the [`DynRequestSender`]
+///
+/// Instances are created by calling [`DynPayload::downcast()`]
+pub struct TypedResponder<R> {
+    responder: Responder<AnyResponse>,
+    request_type: PhantomData<fn(R)>,
+}
+
+impl DynRequestSender {
+    /// Send a request over the channel, wait for the response and return it
+    ///
+    /// The response timeout is taken from [`Request::TIMEOUT`]. With the `tracing`
+    /// feature, the request is sent in a span labeled with the name of its type.
+    pub async fn send_receive<R>(&self, request: R) -> Result<R::Response, RequestError<R>>
+    where
+        R: Request + Send + 'static,
+        R::Response: Send + 'static,
+    {
+        let exchange = self.exchange(request);
+        #[cfg(feature = "tracing")]
+        let exchange = tracing::Instrument::instrument(
+            exchange,
+            tracing::trace_span!("dynamic request", request_type = type_name::<R>()),
+        );
+        exchange.await
+    }
+
+    async fn exchange<R>(&self, request: R) -> Result<R::Response, RequestError<R>>
+    where
+        R: Request + Send + 'static,
+        R::Response: Send + 'static,
+    {
+        let metrics = self
+            .metrics
+            .as_ref()
+            .map(|metrics| metrics.0.for_type(type_name::<R>()));
+        let request = DynRequest {
+            request: Box::new(request),
+            type_name: type_name::<R>(),
+            sent_at: Instant::now(),
+        };
+        let sent_at = request.sent_at;
+        let mut receiver = self
+            .sender
+            .send_or_reject(request)
+            .await
+            .map_err(|err| err.map_request(downcast_request))?;
+        if let Some(metrics) = &metrics {
+            metrics.on_send();
+        }
+        if R::TIMEOUT.is_some() {
+            receiver.set_timeout(R::TIMEOUT);
+        }
+        let result = receiver.recv().await;
+        if let Some(metrics) = &metrics {
+            match &result {
+                Ok(..) => metrics.on_respond(sent_at.elapsed()),
+                Err(ReceiveError::TimeoutError) => metrics.on_timeout(),
+                Err(..) => metrics.on_drop(),
+            }
+        }
+        #[cfg(feature = "tracing")]
+        tracing::trace!(error = ?result.as_ref().err(), "dynamic request finished");
+        let response = result?;
+        Ok(*response
+            .downcast::<R::Response>()
+            .expect("the typed responder only sends the response type of the request"))
+    }
+
+    /// Checks if the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+impl Clone for DynRequestSender {
+    fn clone(&self) -> Self {
+        DynRequestSender {
+            sender: self.sender.clone(),
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+impl DynRequestReceiver {
+    /// Receives the next request for this receiver.
+    pub async fn recv(&mut self) -> Result<DynPayload, RequestError<()>> {
+        match self.receiver.recv().await {
+            Ok((request, responder)) => {
+                if let Some(metrics) = &self.metrics {
+                    metrics
+                        .0
+                        .for_type(request.type_name)
+                        .on_recv(request.sent_at.elapsed());
+                }
+                Ok(DynPayload {
+                    request,
+                    responder: Box::new(responder),
+                })
+            }
+            Err(..) => Err(RequestError::recv_error()),
+        }
+    }
+
+    /// Closes the receiving half of a channel without dropping it.
+    pub fn close(&mut self) {
+        self.receiver.close()
+    }
+}
+
+impl DynPayload {
+    /// Returns `true` if the request is of type `R`
+    pub fn is<R: Request + 'static>(&self) -> bool {
+        self.request.request.is::<R>()
+    }
+
+    /// Returns the name of the request type, for logging unexpected requests
+    pub fn type_name(&self) -> &'static str {
+        self.request.type_name
+    }
+
+    /// Returns the request and its typed responder if the request is of type `R`,
+    /// or the payload itself otherwise
+    pub fn downcast<R: Request + 'static>(self) -> Result<(R, TypedResponder<R>), DynPayload> {
+        if !self.is::<R>() {
+            return Err(self);
+        }
+        let request = downcast_request::<R>(self.request);
+        let responder = TypedResponder {
+            responder: *self.responder,
+            request_type: PhantomData,
+        };
+        Ok((request, responder))
+    }
+
+    /// Returns the untyped responder, to reject a request of an unexpected type by dropping it
+    pub fn into_responder(self) -> Responder<Box<dyn Any + Send>> {
+        *self.responder
+    }
+}
+
+impl<R> TypedResponder<R>
+where
+    R: Request,
+    R::Response: Send + 'static,
+{
+    /// Responds the request from the [`DynRequestSender`] which finishes the request
+    pub fn respond(self, response: R::Response) -> Result<(), RespondError<R::Response>> {
+        self.responder
+            .respond(Box::new(response))
+            .map_err(|RespondError(response, trace)| {
+                RespondError(
+                    *response
+                        .downcast()
+                        .expect("the response is handed back unchanged"),
+                    trace,
+                )
+            })
+    }
+
+    /// Checks if the associated receiver handle for the response listener has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.responder.is_closed()
+    }
+}
+
+impl<R> fmt::Debug for TypedResponder<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("TypedResponder")
+            .field("request_type", &type_name::<R>())
+            .field("responder", &self.responder)
+            .finish()
+    }
+}
+
+fn downcast_request<R: 'static>(request: DynRequest) -> R {
+    *request
+        .request
+        .downcast::<R>()
+        .expect("the request type is checked before downcasting")
+}
+
+/// Creates a bounded mpsc request-response channel that transports requests of any
+/// [`Request`] type
+///
+/// The receiving side downcasts every [`DynPayload`] to the request types it
+/// handles, and the requesting side gets back the response type declared by the
+/// request, without a central enum of all the request types.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+///
+/// # Examples
+///
+/// ```rust
+/// struct GetName(u32);
+/// impl bmrng::Request for GetName {
+///     type Response = String;
+/// }
+///
+/// struct GetAge(u32);
+/// impl bmrng::Request for GetAge {
+///     type Response = u8;
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::dynamic::channel(16);
+///     tokio::spawn(async move {
+///         while let Ok(payload) = rx.recv().await {
+///             let payload = match payload.downcast::<GetName>() {
+///                 Ok((GetName(id), responder)) => {
+///                     let _ = responder.respond(format!("user {}", id));
+///                     continue;
+///                 }
+///                 Err(payload) => payload,
+///             };
+///             if let Ok((GetAge(_), responder)) = payload.downcast::<GetAge>() {
+///                 let _ = responder.respond(42);
+///             }
+///         }
+///     });
+///     assert_eq!(tx.send_receive(GetName(7)).await.ok(), Some("user 7".to_string()));
+///     assert_eq!(tx.send_receive(GetAge(7)).await.ok(), Some(42));
+/// }
+/// ```
+pub fn channel(buffer: usize) -> (DynRequestSender, DynRequestReceiver) {
+    new_channel(buffer, None)
+}
+
+/// Creates a dynamic channel like [`channel()`], calling the [`ChannelMetrics`](crate::metrics::ChannelMetrics)
+/// that `metrics` provides for the type of every request
+///
+/// See [`TypeStats`](crate::metrics::TypeStats) for an implementation keeping
+/// counters per request type.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+pub fn channel_with_metrics(
+    buffer: usize,
+    metrics: Arc<dyn TypeMetrics>,
+) -> (DynRequestSender, DynRequestReceiver) {
+    new_channel(buffer, Some(Hook(metrics)))
+}
+
+fn new_channel(
+    buffer: usize,
+    metrics: Option<Hook<dyn TypeMetrics>>,
+) -> (DynRequestSender, DynRequestReceiver) {
+    let (sender, receiver) = bounded_channel(buffer);
+    (
+        DynRequestSender {
+            sender,
+            metrics: metrics.clone(),
+        },
+        DynRequestReceiver { receiver, metrics },
+    )
+}
@@ -1,6 +1,9 @@
 use std::error::Error;
 use std::fmt;
 use tokio::sync::mpsc::error::SendError as MpscSendError;
+use tokio::sync::mpsc::error::SendTimeoutError as MpscSendTimeoutError;
+use tokio::sync::mpsc::error::TryRecvError as MpscTryRecvError;
+use tokio::sync::mpsc::error::TrySendError as MpscTrySendError;
 use tokio::sync::oneshot;
 
 /// Error thrown when a [`RequestSender::send()`](crate::RequestSender::send()) or [`UnboundedRequestSender::send()`](crate::unbounded::UnboundedRequestSender::send())
@@ -29,16 +32,194 @@ impl<T> fmt::Display for SendError<T> {
 
 impl<T> Error for SendError<T> where T: fmt::Debug {}
 
+/// Error thrown when a [`RequestSender::try_send()`](crate::RequestSender::try_send()) call fails
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TrySendError<T> {
+    /// The request channel is full, the request is handed back
+    Full(T),
+    /// The request channel is closed, the request is handed back
+    Closed(T),
+    /// The [admission controller](crate::Admission) of the channel sheds the request
+    /// because the channel is overloaded, the request is handed back
+    Rejected(T),
+}
+
+impl<T> TrySendError<T> {
+    /// Consumes the error, returning the request that failed to send
+    pub fn into_inner(self) -> T {
+        match self {
+            TrySendError::Full(request)
+            | TrySendError::Closed(request)
+            | TrySendError::Rejected(request) => request,
+        }
+    }
+}
+
+impl<T> From<MpscTrySendError<T>> for TrySendError<T> {
+    fn from(err: MpscTrySendError<T>) -> Self {
+        match err {
+            MpscTrySendError::Full(request) => TrySendError::Full(request),
+            MpscTrySendError::Closed(request) => TrySendError::Closed(request),
+        }
+    }
+}
+
+impl<T> fmt::Display for TrySendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                TrySendError::Full(..) => "no available capacity",
+                TrySendError::Closed(..) => "channel closed",
+                TrySendError::Rejected(..) => "request rejected by an overloaded channel",
+            }
+        )
+    }
+}
+
+impl<T> Error for TrySendError<T> where T: fmt::Debug {}
+
+/// Error thrown when a [`RequestSender::send_timeout()`](crate::RequestSender::send_timeout()) call fails
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum SendTimeoutError<T> {
+    /// The request channel stayed full for the whole duration, the request is handed back
+    Timeout(T),
+    /// The request channel is closed, the request is handed back
+    Closed(T),
+    /// The [admission controller](crate::Admission) of the channel sheds the request
+    /// because the channel is overloaded, the request is handed back
+    Rejected(T),
+}
+
+impl<T> SendTimeoutError<T> {
+    /// Consumes the error, returning the request that failed to send
+    pub fn into_inner(self) -> T {
+        match self {
+            SendTimeoutError::Timeout(request)
+            | SendTimeoutError::Closed(request)
+            | SendTimeoutError::Rejected(request) => request,
+        }
+    }
+}
+
+impl<T> From<MpscSendTimeoutError<T>> for SendTimeoutError<T> {
+    fn from(err: MpscSendTimeoutError<T>) -> Self {
+        match err {
+            MpscSendTimeoutError::Timeout(request) => SendTimeoutError::Timeout(request),
+            MpscSendTimeoutError::Closed(request) => SendTimeoutError::Closed(request),
+        }
+    }
+}
+
+impl<T> fmt::Display for SendTimeoutError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                SendTimeoutError::Timeout(..) => "timed out waiting on send operation",
+                SendTimeoutError::Closed(..) => "channel closed",
+                SendTimeoutError::Rejected(..) => "request rejected by an overloaded channel",
+            }
+        )
+    }
+}
+
+impl<T> Error for SendTimeoutError<T> where T: fmt::Debug {}
+
+/// Where an error was produced
+///
+/// With the `tracing-error` feature, it holds the `SpanTrace` captured when the
+/// error was constructed, in the span of the request when there is one. The span
+/// trace is only recorded if the subscriber has a `tracing_error::ErrorLayer`.
+/// Without the feature it is empty.
+///
+/// Traces take no part in comparisons, so errors that only differ by their trace
+/// are equal.
+// Not `Copy` without the feature either, so enabling it keeps the same traits
+#[allow(missing_copy_implementations)]
+#[derive(Clone)]
+pub struct ErrorTrace {
+    #[cfg(feature = "tracing-error")]
+    span_trace: tracing_error::SpanTrace,
+}
+
+impl ErrorTrace {
+    /// Captures the trace of the current span
+    pub fn capture() -> Self {
+        ErrorTrace {
+            #[cfg(feature = "tracing-error")]
+            span_trace: tracing_error::SpanTrace::capture(),
+        }
+    }
+
+    /// Returns the span trace captured with the error
+    #[cfg(feature = "tracing-error")]
+    pub fn span_trace(&self) -> &tracing_error::SpanTrace {
+        &self.span_trace
+    }
+}
+
+impl fmt::Debug for ErrorTrace {
+    #[cfg(feature = "tracing-error")]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_tuple("ErrorTrace")
+            .field(&self.span_trace)
+            .finish()
+    }
+
+    #[cfg(not(feature = "tracing-error"))]
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("ErrorTrace")
+    }
+}
+
+impl PartialEq for ErrorTrace {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for ErrorTrace {}
+
 /// Errors that can occur when a [`RequestReceiver`](crate::RequestReceiver)
 /// or [`UnboundedReceiver`](crate::unbounded::UnboundedRequestReceiver) handles a request
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum RequestError<T> {
     /// Error occurring when the channel from [`RequestSender`](crate::RequestSender) to [`RequestReceiver`](crate::RequestReceiver) is closed
-    RecvError,
+    RecvError(ErrorTrace),
     /// Error occurring when the Responder fails to send a response before the timeout
-    RecvTimeoutError,
+    RecvTimeoutError(ErrorTrace),
     /// Error occurring when the channel from [`RequestReceiver`](crate::RequestReceiver) to [RequestSender](crate::RequestSender) is closed
     SendError(T),
+    /// Error occurring when the handler panicked while handling the request in a
+    /// [`serve_catch_unwind()`](crate::RequestReceiver::serve_catch_unwind()) loop
+    HandlerPanicked,
+    /// Error occurring when the request channel stays full for the whole send timeout
+    /// of the channel, the request is handed back
+    SendTimeoutError(T),
+    /// Error occurring when the request is dropped by the overflow policy of a full
+    /// [`ring`](crate::ring) channel
+    Evicted,
+    /// Error occurring when a newer request replaces the request in a
+    /// [`latest`](crate::latest) channel before it is received
+    Superseded,
+    /// Error occurring when the request stays queued for longer than its time-to-live,
+    /// so the receiver skips it
+    Expired,
+    /// Error occurring when the [admission controller](crate::Admission) of the channel
+    /// sheds the request because the channel is overloaded, the request is handed back
+    Rejected(T),
+    /// Error occurring when the request is cancelled by id with
+    /// [`RequestSender::cancel()`](crate::RequestSender::cancel())
+    Cancelled,
+    /// Error occurring when a [`CircuitBreakerSender`](crate::CircuitBreakerSender)
+    /// fails fast because too many recent requests failed, the request is handed back
+    CircuitOpen(T),
+    /// Error occurring when the receiver shuts down before the request is answered,
+    /// see [`serve_until_shutdown()`](crate::RequestReceiver::serve_until_shutdown())
+    ShutDown,
 }
 
 /// Errors that can occur when a [`ResponseReceiver`](crate::ResponseReceiver) is
@@ -49,6 +230,61 @@ pub enum ReceiveError {
     RecvError,
     /// Error occurring when the Responder fails to send a response before the timeout
     TimeoutError,
+    /// Error occurring when the handler panicked while handling the request in a
+    /// [`serve_catch_unwind()`](crate::RequestReceiver::serve_catch_unwind()) loop
+    HandlerPanicked,
+    /// Error occurring when the request is dropped by the overflow policy of a full
+    /// [`ring`](crate::ring) channel
+    Evicted,
+    /// Error occurring when a newer request replaces the request in a
+    /// [`latest`](crate::latest) channel before it is received
+    Superseded,
+    /// Error occurring when the request stays queued for longer than its time-to-live,
+    /// so the receiver skips it
+    Expired,
+    /// Error occurring when the request is cancelled by id with
+    /// [`RequestSender::cancel()`](crate::RequestSender::cancel())
+    Cancelled,
+    /// Error occurring when the receiver shuts down before the request is answered,
+    /// see [`serve_until_shutdown()`](crate::RequestReceiver::serve_until_shutdown())
+    ShutDown,
+}
+
+impl<T> RequestError<T> {
+    /// Returns a [`RequestError::RecvError`] capturing the trace of the current span
+    pub(crate) fn recv_error() -> Self {
+        RequestError::RecvError(ErrorTrace::capture())
+    }
+
+    /// Returns the span trace captured with a [`RequestError::RecvError`] or a
+    /// [`RequestError::RecvTimeoutError`]
+    #[cfg(feature = "tracing-error")]
+    pub fn span_trace(&self) -> Option<&tracing_error::SpanTrace> {
+        match self {
+            RequestError::RecvError(trace) | RequestError::RecvTimeoutError(trace) => {
+                Some(trace.span_trace())
+            }
+            _ => None,
+        }
+    }
+
+    /// Maps the request handed back by the error, if any
+    pub(crate) fn map_request<U>(self, f: impl FnOnce(T) -> U) -> RequestError<U> {
+        match self {
+            RequestError::RecvError(trace) => RequestError::RecvError(trace),
+            RequestError::RecvTimeoutError(trace) => RequestError::RecvTimeoutError(trace),
+            RequestError::SendError(request) => RequestError::SendError(f(request)),
+            RequestError::HandlerPanicked => RequestError::HandlerPanicked,
+            RequestError::SendTimeoutError(request) => RequestError::SendTimeoutError(f(request)),
+            RequestError::Evicted => RequestError::Evicted,
+            RequestError::Superseded => RequestError::Superseded,
+            RequestError::Expired => RequestError::Expired,
+            RequestError::Rejected(request) => RequestError::Rejected(f(request)),
+            RequestError::Cancelled => RequestError::Cancelled,
+            RequestError::CircuitOpen(request) => RequestError::CircuitOpen(f(request)),
+            RequestError::ShutDown => RequestError::ShutDown,
+        }
+    }
 }
 
 impl<T> From<SendError<T>> for RequestError<T> {
@@ -57,6 +293,16 @@ impl<T> From<SendError<T>> for RequestError<T> {
     }
 }
 
+impl<T> From<SendTimeoutError<T>> for RequestError<T> {
+    fn from(err: SendTimeoutError<T>) -> RequestError<T> {
+        match err {
+            SendTimeoutError::Timeout(request) => RequestError::SendTimeoutError(request),
+            SendTimeoutError::Closed(request) => RequestError::SendError(request),
+            SendTimeoutError::Rejected(request) => RequestError::Rejected(request),
+        }
+    }
+}
+
 impl<T> From<RespondError<T>> for RequestError<T> {
     fn from(err: RespondError<T>) -> RequestError<T> {
         RequestError::SendError(err.0)
@@ -66,8 +312,14 @@ impl<T> From<RespondError<T>> for RequestError<T> {
 impl<T> From<ReceiveError> for RequestError<T> {
     fn from(err: ReceiveError) -> RequestError<T> {
         match err {
-            ReceiveError::RecvError => RequestError::RecvError,
-            ReceiveError::TimeoutError => RequestError::RecvTimeoutError,
+            ReceiveError::RecvError => RequestError::recv_error(),
+            ReceiveError::TimeoutError => RequestError::RecvTimeoutError(ErrorTrace::capture()),
+            ReceiveError::HandlerPanicked => RequestError::HandlerPanicked,
+            ReceiveError::Evicted => RequestError::Evicted,
+            ReceiveError::Superseded => RequestError::Superseded,
+            ReceiveError::Expired => RequestError::Expired,
+            ReceiveError::Cancelled => RequestError::Cancelled,
+            ReceiveError::ShutDown => RequestError::ShutDown,
         }
     }
 }
@@ -85,9 +337,18 @@ impl<T> fmt::Display for RequestError<T> {
             fmt,
             "{}",
             match self {
-                RequestError::RecvError => "request channel closed",
-                RequestError::RecvTimeoutError => "request timed out",
+                RequestError::RecvError(..) => "request channel closed",
+                RequestError::RecvTimeoutError(..) => "request timed out",
                 RequestError::SendError(..) => "channel closed",
+                RequestError::HandlerPanicked => "request handler panicked",
+                RequestError::SendTimeoutError(..) => "timed out waiting on send operation",
+                RequestError::Evicted => "request evicted from a full channel",
+                RequestError::Superseded => "request superseded by a newer one",
+                RequestError::Expired => "request expired in the queue",
+                RequestError::Rejected(..) => "request rejected by an overloaded channel",
+                RequestError::Cancelled => "request cancelled",
+                RequestError::CircuitOpen(..) => "circuit breaker open",
+                RequestError::ShutDown => "request handler shut down",
             }
         )
     }
@@ -103,6 +364,12 @@ impl fmt::Display for ReceiveError {
             match self {
                 ReceiveError::RecvError => "receive channel closed",
                 ReceiveError::TimeoutError => "request timed out",
+                ReceiveError::HandlerPanicked => "request handler panicked",
+                ReceiveError::Evicted => "request evicted from a full channel",
+                ReceiveError::Superseded => "request superseded by a newer one",
+                ReceiveError::Expired => "request expired in the queue",
+                ReceiveError::Cancelled => "request cancelled",
+                ReceiveError::ShutDown => "request handler shut down",
             }
         )
     }
@@ -110,10 +377,116 @@ impl fmt::Display for ReceiveError {
 
 impl Error for ReceiveError {}
 
+/// Error thrown by [`PollRequestSender`](crate::PollRequestSender) when the channel is
+/// closed, or when a request is sent without a reserved slot
+///
+/// The request is handed back if it could not be sent.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PollSendError<T>(pub Option<T>);
+
+impl<T> PollSendError<T> {
+    /// Consumes the error, returning the request that failed to send, if any
+    pub fn into_inner(self) -> Option<T> {
+        self.0
+    }
+}
+
+impl<T> fmt::Display for PollSendError<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self.0 {
+                Some(..) => "no slot reserved",
+                None => "channel closed",
+            }
+        )
+    }
+}
+
+impl<T> Error for PollSendError<T> where T: fmt::Debug {}
+
+/// Error thrown when a [`RequestReceiver::try_recv()`](crate::RequestReceiver::try_recv()) or
+/// [`UnboundedRequestReceiver::try_recv()`](crate::unbounded::UnboundedRequestReceiver::try_recv()) call fails
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TryRecvError {
+    /// There are no requests waiting, but the channel is still open
+    Empty,
+    /// There are no requests waiting and all the senders have been dropped
+    Disconnected,
+}
+
+impl From<MpscTryRecvError> for TryRecvError {
+    fn from(err: MpscTryRecvError) -> TryRecvError {
+        match err {
+            MpscTryRecvError::Empty => TryRecvError::Empty,
+            MpscTryRecvError::Disconnected => TryRecvError::Disconnected,
+        }
+    }
+}
+
+impl fmt::Display for TryRecvError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                TryRecvError::Empty => "receiving on an empty channel",
+                TryRecvError::Disconnected => "receiving on a closed channel",
+            }
+        )
+    }
+}
+
+impl Error for TryRecvError {}
+
+/// Error thrown when a [`RequestReceiver::recv_timeout()`](crate::RequestReceiver::recv_timeout()) or
+/// [`UnboundedRequestReceiver::recv_timeout()`](crate::unbounded::UnboundedRequestReceiver::recv_timeout()) call fails
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum RecvTimeoutError {
+    /// No request arrived before the timeout elapsed, but the channel is still open
+    Timeout,
+    /// There are no requests waiting and all the senders have been dropped
+    Disconnected,
+}
+
+impl fmt::Display for RecvTimeoutError {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            fmt,
+            "{}",
+            match self {
+                RecvTimeoutError::Timeout => "timed out waiting on a channel",
+                RecvTimeoutError::Disconnected => "receiving on a closed channel",
+            }
+        )
+    }
+}
+
+impl Error for RecvTimeoutError {}
+
 /// Error thrown when a Responder fails to respond.
 /// The channel was closed by the receiver, the original request sender
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct RespondError<T>(pub T);
+#[derive(Debug, Clone, PartialEq)]
+pub struct RespondError<T>(pub T, pub ErrorTrace);
+
+impl<T> RespondError<T> {
+    /// Returns an error handing back `response`, capturing the trace of the current span
+    pub(crate) fn new(response: T) -> Self {
+        RespondError(response, ErrorTrace::capture())
+    }
+
+    /// Consumes the error, returning the response that failed to send
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+
+    /// Returns the span trace captured with the error
+    #[cfg(feature = "tracing-error")]
+    pub fn span_trace(&self) -> &tracing_error::SpanTrace {
+        self.1.span_trace()
+    }
+}
 
 impl<T> fmt::Display for RespondError<T> {
     #[cfg(not(tarpaulin_include))]
@@ -124,6 +497,113 @@ impl<T> fmt::Display for RespondError<T> {
 
 impl<T> Error for RespondError<T> where T: fmt::Debug {}
 
+/// Error thrown when a [`RequestSender::send_receive_retained()`](crate::RequestSender::send_receive_retained()) or
+/// [`UnboundedRequestSender::send_receive_retained()`](crate::unbounded::UnboundedRequestSender::send_receive_retained())
+/// call fails
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetainedError<T, R> {
+    /// The error the request failed with
+    pub error: RequestError<T>,
+    /// The part of the request retained before it was sent, handed back only with
+    /// [`RequestError::RecvTimeoutError`]
+    pub retained: Option<R>,
+}
+
+impl<T, R> fmt::Display for RetainedError<T, R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(fmt)
+    }
+}
+
+impl<T, R> Error for RetainedError<T, R>
+where
+    T: fmt::Debug,
+    R: fmt::Debug,
+{
+}
+
+/// An error together with the [`SpanTrace`](tracing_error::SpanTrace) captured
+/// where it was produced
+///
+/// The trace is captured in the span of the request when there is one, so an
+/// error bubbling up through several layers can be traced back to the failing
+/// channel and call site. The span trace is only recorded if the subscriber has
+/// a [`tracing_error::ErrorLayer`].
+///
+/// Returned by the `_traced` variants of the send and receive methods, like
+/// [`RequestSender::send_receive_traced()`](crate::RequestSender::send_receive_traced()).
+#[cfg(feature = "tracing-error")]
+pub struct Traced<E> {
+    error: E,
+    span_trace: tracing_error::SpanTrace,
+}
+
+#[cfg(feature = "tracing-error")]
+impl<E> Traced<E> {
+    /// Wraps the error, capturing the span trace of the current span
+    pub fn new(error: E) -> Self {
+        Traced {
+            error,
+            span_trace: tracing_error::SpanTrace::capture(),
+        }
+    }
+
+    /// Wraps the error, capturing the span trace of `span`
+    pub(crate) fn in_span(error: E, span: &tracing::Span) -> Self {
+        span.in_scope(|| Traced::new(error))
+    }
+
+    /// Returns the wrapped error
+    pub fn error(&self) -> &E {
+        &self.error
+    }
+
+    /// Returns the span trace captured with the error
+    pub fn span_trace(&self) -> &tracing_error::SpanTrace {
+        &self.span_trace
+    }
+
+    /// Returns the wrapped error, discarding the span trace
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+
+    /// Converts the wrapped error, keeping the span trace
+    pub fn map<F, U>(self, f: F) -> Traced<U>
+    where
+        F: FnOnce(E) -> U,
+    {
+        Traced {
+            error: f(self.error),
+            span_trace: self.span_trace,
+        }
+    }
+}
+
+#[cfg(feature = "tracing-error")]
+impl<E: fmt::Debug> fmt::Debug for Traced<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Traced")
+            .field("error", &self.error)
+            .field("span_trace", &self.span_trace)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tracing-error")]
+impl<E: fmt::Display> fmt::Display for Traced<E> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.error.fmt(fmt)
+    }
+}
+
+#[cfg(feature = "tracing-error")]
+impl<E: Error> Error for Traced<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.error.source()
+    }
+}
+
 #[cfg(test)]
 pub mod tests {
     pub use super::*;
@@ -149,18 +629,66 @@ pub mod tests {
         assert_eq!(r_err, RequestError::SendError(42));
     }
 
+    #[test]
+    fn send_timeout_error_into_request_error() {
+        let r_err: RequestError<i32> = SendTimeoutError::Timeout(42).into();
+        assert_eq!(r_err, RequestError::SendTimeoutError(42));
+        let r_err: RequestError<i32> = SendTimeoutError::Closed(42).into();
+        assert_eq!(r_err, RequestError::SendError(42));
+    }
+
     #[test]
     fn reply_error_to_request_error() {
-        let err = RespondError(21);
+        let err = RespondError::new(21);
         let q_err: RequestError<i32> = err.into();
         assert_eq!(q_err, RequestError::SendError(21));
     }
 
+    #[test]
+    fn mpsc_try_send_err_into_try_send_error() {
+        let err: TrySendError<u32> = MpscTrySendError::Full(42).into();
+        assert_eq!(err, TrySendError::Full(42));
+        let err: TrySendError<u32> = MpscTrySendError::Closed(42).into();
+        assert_eq!(err, TrySendError::Closed(42));
+        assert_eq!(err.into_inner(), 42);
+    }
+
+    #[test]
+    fn mpsc_try_recv_err_into_try_recv_error() {
+        let err: TryRecvError = MpscTryRecvError::Empty.into();
+        assert_eq!(err, TryRecvError::Empty);
+        let err: TryRecvError = MpscTryRecvError::Disconnected.into();
+        assert_eq!(err, TryRecvError::Disconnected);
+    }
+
+    #[test]
+    fn mpsc_send_timeout_err_into_send_timeout_error() {
+        let err: SendTimeoutError<u32> = MpscSendTimeoutError::Timeout(42).into();
+        assert_eq!(err, SendTimeoutError::Timeout(42));
+        let err: SendTimeoutError<u32> = MpscSendTimeoutError::Closed(42).into();
+        assert_eq!(err, SendTimeoutError::Closed(42));
+        assert_eq!(err.into_inner(), 42);
+    }
+
+    #[test]
+    fn try_send_error_display() {
+        assert_eq!("no available capacity", TrySendError::Full(1).to_string());
+        assert_eq!("channel closed", TrySendError::Closed(1).to_string());
+    }
+
     #[test]
     fn receive_error_display() {
         let err = ReceiveError::RecvError;
         assert_eq!("receive channel closed", err.to_string());
         let err = ReceiveError::TimeoutError;
         assert_eq!("request timed out", err.to_string());
+        let err = ReceiveError::HandlerPanicked;
+        assert_eq!("request handler panicked", err.to_string());
+        let err = ReceiveError::Evicted;
+        assert_eq!("request evicted from a full channel", err.to_string());
+        let err = ReceiveError::Superseded;
+        assert_eq!("request superseded by a newer one", err.to_string());
+        let err = ReceiveError::Expired;
+        assert_eq!("request expired in the queue", err.to_string());
     }
 }
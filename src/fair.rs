@@ -0,0 +1,388 @@
+use crate::bounded::{new_payload, Payload, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::state::ChannelState;
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt;
+use std::ops::Bound;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+use tokio::time::Duration;
+
+/// Send requests to the associated [`FairRequestReceiver`] through a lane of
+/// their own
+///
+/// Every clone of the sender gets a new lane, see [`clone_with_weight()`](Self::clone_with_weight()).
+/// Instances are created by the [`channel()`] and [`channel_with_timeout()`] functions.
+pub struct FairRequestSender<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+    lane: u64,
+}
+
+/// Receive requests from the associated [`FairRequestSender`]s, taking turns
+/// between their lanes
+///
+/// Instances are created by the [`channel()`] and [`channel_with_timeout()`] functions.
+pub struct FairRequestReceiver<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+struct Shared<Req, Res> {
+    queue: Mutex<Queue<Req, Res>>,
+    /// The number of requests every lane can hold
+    capacity: usize,
+    /// Notified when a payload is queued, or when the last sender is dropped
+    not_empty: Notify,
+    /// Notified when a payload is received, or when the receiver is dropped
+    not_full: Notify,
+    channel: Arc<ChannelState>,
+}
+
+struct Queue<Req, Res> {
+    lanes: BTreeMap<u64, Lane<Req, Res>>,
+    next_lane: u64,
+    /// The lane whose turn it is
+    current: u64,
+    len: usize,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Lane<Req, Res> {
+    entries: VecDeque<Payload<Req, Res>>,
+    /// The number of requests received from the lane in one turn
+    weight: u32,
+    /// The number of requests left to receive from the lane in its current turn
+    credit: u32,
+    /// Set once the sender of the lane is dropped, so the lane is removed once drained
+    closed: bool,
+}
+
+impl<Req, Res> Shared<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Queue<Req, Res>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<Req, Res> Queue<Req, Res> {
+    /// Adds a lane for a new sender and returns its id
+    fn add_lane(&mut self, weight: u32) -> u64 {
+        assert!(weight > 0, "a fair channel lane requires weight > 0");
+        let id = self.next_lane;
+        self.next_lane += 1;
+        self.lanes.insert(
+            id,
+            Lane {
+                entries: VecDeque::new(),
+                weight,
+                credit: weight,
+                closed: false,
+            },
+        );
+        self.senders += 1;
+        id
+    }
+
+    /// Returns the id of the lane after `id`, wrapping around
+    fn next_after(&self, id: u64) -> Option<u64> {
+        self.lanes
+            .range((Bound::Excluded(id), Bound::Unbounded))
+            .chain(self.lanes.iter())
+            .map(|(id, _)| *id)
+            .next()
+    }
+
+    /// Takes the next request of the lane whose turn it is, handing the turn to
+    /// the next lane once the lane is empty or used up its weight
+    fn pop(&mut self) -> Option<Payload<Req, Res>> {
+        if self.len == 0 {
+            return None;
+        }
+        loop {
+            let id = match self.lanes.contains_key(&self.current) {
+                true => self.current,
+                false => self.next_after(self.current)?,
+            };
+            let lane = self.lanes.get_mut(&id).expect("the lane exists");
+            let payload = match lane.credit {
+                0 => None,
+                _ => lane.entries.pop_front(),
+            };
+            match payload {
+                Some(payload) => {
+                    lane.credit -= 1;
+                    self.len -= 1;
+                    self.current = id;
+                    if lane.credit == 0 || lane.entries.is_empty() {
+                        self.end_turn(id);
+                    }
+                    return Some(payload);
+                }
+                None => self.end_turn(id),
+            }
+        }
+    }
+
+    /// Refills the credit of the lane for its next turn and hands the turn to the
+    /// next lane, removing the lane if it is drained and its sender is gone
+    fn end_turn(&mut self, id: u64) {
+        let lane = self.lanes.get_mut(&id).expect("the lane exists");
+        lane.credit = lane.weight;
+        let remove = lane.closed && lane.entries.is_empty();
+        self.current = self.next_after(id).unwrap_or(id);
+        if remove {
+            self.lanes.remove(&id);
+        }
+    }
+}
+
+impl<Req, Res> FairRequestSender<Req, Res> {
+    /// Send a request through the lane of this sender, open the response channel
+    ///
+    /// This call waits if the lane of this sender is full, even if the lanes of
+    /// the other senders have capacity left.
+    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        let (payload, receiver) = new_payload(request, &self.shared.channel);
+        let mut payload = Some(payload);
+        loop {
+            let not_full = self.shared.not_full.notified();
+            tokio::pin!(not_full);
+            not_full.as_mut().enable();
+            {
+                let mut queue = self.shared.lock();
+                if !queue.receiver_alive {
+                    let payload = payload.take().expect("the payload is queued once");
+                    return Err(SendError(payload.0));
+                }
+                let lane = queue.lanes.get_mut(&self.lane).expect("the lane exists");
+                if lane.entries.len() < self.shared.capacity {
+                    lane.entries
+                        .push_back(payload.take().expect("the payload is queued once"));
+                    queue.len += 1;
+                    drop(queue);
+                    self.shared.not_empty.notify_waiters();
+                    return Ok(receiver);
+                }
+            }
+            not_full.await;
+        }
+    }
+
+    /// Send a request through the lane of this sender, wait for the response and return it
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request).await?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Creates another sender with a lane of its own, from which up to `weight`
+    /// requests are received in a row when it is its turn
+    ///
+    /// Clones created with [`Clone::clone()`] have a weight of 1.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is 0
+    pub fn clone_with_weight(&self, weight: u32) -> Self {
+        let lane = self.shared.lock().add_lane(weight);
+        FairRequestSender {
+            shared: self.shared.clone(),
+            lane,
+        }
+    }
+
+    /// Returns the number of requests received from the lane of this sender in a row
+    pub fn weight(&self) -> u32 {
+        self.shared.lock().lanes[&self.lane].weight
+    }
+
+    /// Returns the number of requests waiting in the lane of this sender
+    pub fn len(&self) -> usize {
+        self.shared.lock().lanes[&self.lane].entries.len()
+    }
+
+    /// Returns `true` if no requests are waiting in the lane of this sender
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks if the channel has been closed
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<Req, Res> FairRequestReceiver<Req, Res> {
+    /// Receives the next request, waiting until one is available
+    ///
+    /// The lanes of the senders take turns: up to the weight of a lane, requests
+    /// are received from it before the next lane that has requests waiting.
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            let not_empty = self.shared.not_empty.notified();
+            tokio::pin!(not_empty);
+            not_empty.as_mut().enable();
+            {
+                let mut queue = self.shared.lock();
+                if let Some(payload) = queue.pop() {
+                    drop(queue);
+                    self.shared.not_full.notify_waiters();
+                    return Ok(payload);
+                }
+                if queue.senders == 0 {
+                    return Err(RequestError::recv_error());
+                }
+            }
+            not_empty.await;
+        }
+    }
+
+    /// Returns the number of requests waiting to be received, across all the lanes
+    pub fn len(&self) -> usize {
+        self.shared.lock().len
+    }
+
+    /// Returns `true` if there are no requests waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Req, Res> Clone for FairRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        self.clone_with_weight(1)
+    }
+}
+
+impl<Req, Res> Drop for FairRequestSender<Req, Res> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.senders -= 1;
+        let lane = queue.lanes.get_mut(&self.lane).expect("the lane exists");
+        lane.closed = true;
+        if lane.entries.is_empty() {
+            queue.lanes.remove(&self.lane);
+        }
+        if queue.senders == 0 {
+            drop(queue);
+            self.shared.not_empty.notify_waiters();
+        }
+    }
+}
+
+impl<Req, Res> Drop for FairRequestReceiver<Req, Res> {
+    fn drop(&mut self) {
+        let lanes = {
+            let mut queue = self.shared.lock();
+            queue.receiver_alive = false;
+            queue.len = 0;
+            queue.lanes.retain(|_, lane| !lane.closed);
+            queue
+                .lanes
+                .values_mut()
+                .map(|lane| std::mem::take(&mut lane.entries))
+                .collect::<Vec<_>>()
+        };
+        drop(lanes);
+        self.shared.not_full.notify_waiters();
+    }
+}
+
+impl<Req, Res> fmt::Debug for FairRequestSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FairRequestSender")
+            .field("capacity", &self.shared.capacity)
+            .field("lane", &self.lane)
+            .field("channel", &self.shared.channel)
+            .finish()
+    }
+}
+
+impl<Req, Res> fmt::Debug for FairRequestReceiver<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("FairRequestReceiver")
+            .field("capacity", &self.shared.capacity)
+            .field("lanes", &self.shared.lock().lanes.len())
+            .finish()
+    }
+}
+
+fn new_channel<Req, Res>(
+    capacity: usize,
+    timeout_duration: Option<Duration>,
+) -> (FairRequestSender<Req, Res>, FairRequestReceiver<Req, Res>) {
+    assert!(capacity > 0, "fair channel requires capacity > 0");
+    let mut queue = Queue {
+        lanes: BTreeMap::new(),
+        next_lane: 0,
+        current: 0,
+        len: 0,
+        senders: 0,
+        receiver_alive: true,
+    };
+    let lane = queue.add_lane(1);
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(queue),
+        capacity,
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+        channel: Arc::new(ChannelState::new(timeout_duration)),
+    });
+    (
+        FairRequestSender {
+            shared: shared.clone(),
+            lane,
+        },
+        FairRequestReceiver { shared },
+    )
+}
+
+/// Creates a bounded request-response channel whose receiver takes turns between
+/// the senders, so a chatty sender cannot starve the others
+///
+/// Every sender, and every clone of it, queues its requests in a lane of its own
+/// holding up to `capacity` requests. The receiver takes them round-robin across
+/// the lanes, or by weight with [`FairRequestSender::clone_with_weight()`].
+///
+/// # Panics
+///
+/// Panics if the capacity is 0
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let (chatty, mut rx) = bmrng::fair::channel::<&str, ()>(8);
+///     let quiet = chatty.clone();
+///     let _first = chatty.send("chatty 1").await.unwrap();
+///     let _second = chatty.send("chatty 2").await.unwrap();
+///     let _third = quiet.send("quiet").await.unwrap();
+///
+///     let (request, _responder) = rx.recv().await.unwrap();
+///     assert_eq!(request, "chatty 1");
+///     let (request, _responder) = rx.recv().await.unwrap();
+///     assert_eq!(request, "quiet");
+/// }
+/// ```
+pub fn channel<Req, Res>(
+    capacity: usize,
+) -> (FairRequestSender<Req, Res>, FairRequestReceiver<Req, Res>) {
+    new_channel(capacity, None)
+}
+
+/// Creates a bounded request-response channel whose receiver takes turns between
+/// the senders, with a response timeout
+///
+/// Also see [`bmrng::channel_with_timeout()`](crate::channel_with_timeout())
+///
+/// # Panics
+///
+/// Panics if the capacity is 0
+pub fn channel_with_timeout<Req, Res>(
+    capacity: usize,
+    timeout_duration: Duration,
+) -> (FairRequestSender<Req, Res>, FairRequestReceiver<Req, Res>) {
+    new_channel(capacity, Some(timeout_duration))
+}
@@ -0,0 +1,133 @@
+use crate::bounded::{RequestSender, ResponseReceiver};
+use crate::error::RequestError;
+use crate::state::CancelReason;
+
+use futures_util::future::join_all;
+use futures_util::stream::{FuturesUnordered, StreamExt};
+use std::sync::Arc;
+
+/// Send a clone of the request to every sender, wait for all of them and
+/// return their results in the same order as `senders`
+pub async fn send_receive_all<Req, Res>(
+    senders: &[RequestSender<Req, Res>],
+    request: Req,
+) -> Vec<Result<Res, RequestError<Req>>>
+where
+    Req: Clone,
+{
+    join_all(
+        senders
+            .iter()
+            .map(|sender| sender.send_receive(request.clone())),
+    )
+    .await
+}
+
+/// Send the same request to every sender without cloning it, wait for all of
+/// them and return their results in the same order as `senders`
+///
+/// The request is moved into an [`Arc`] once, and every channel receives a
+/// reference to it, so `Req` does not need to implement [`Clone`].
+pub async fn send_receive_all_shared<Req, Res>(
+    senders: &[RequestSender<Arc<Req>, Res>],
+    request: impl Into<Arc<Req>>,
+) -> Vec<Result<Res, RequestError<Arc<Req>>>> {
+    send_receive_all(senders, request.into()).await
+}
+
+/// Send a clone of the request to every sender and return the first successful response
+///
+/// The remaining requests are abandoned as soon as a response arrives, so their
+/// responders observe the closed response channel.
+/// If every request fails, the last error is returned.
+pub async fn send_receive_first<Req, Res>(
+    senders: &[RequestSender<Req, Res>],
+    request: Req,
+) -> Result<Res, RequestError<Req>>
+where
+    Req: Clone,
+{
+    send_receive_quorum(senders, request, 1)
+        .await
+        .map(|mut responses| responses.remove(0))
+}
+
+/// Send a clone of the request to every sender and return as soon as `quorum`
+/// of them responded successfully
+///
+/// The responses are returned in the order they arrived. If the quorum cannot
+/// be reached anymore, the last error is returned.
+pub async fn send_receive_quorum<Req, Res>(
+    senders: &[RequestSender<Req, Res>],
+    request: Req,
+    quorum: usize,
+) -> Result<Vec<Res>, RequestError<Req>>
+where
+    Req: Clone,
+{
+    let mut pending: FuturesUnordered<_> = senders
+        .iter()
+        .map(|sender| sender.send_receive(request.clone()))
+        .collect();
+    let mut responses = Vec::with_capacity(quorum);
+    let mut last_error = None;
+    while responses.len() < quorum && responses.len() + pending.len() >= quorum {
+        match pending.next().await {
+            Some(Ok(response)) => responses.push(response),
+            Some(Err(err)) => last_error = Some(err),
+            None => break,
+        }
+    }
+    if responses.len() >= quorum {
+        Ok(responses)
+    } else {
+        Err(last_error.unwrap_or(RequestError::SendError(request)))
+    }
+}
+
+/// Send a clone of the request to every sender and return the first successful response,
+/// cancelling the requests that lost the race
+///
+/// Unlike [`send_receive_first()`], the losing requests are cancelled with
+/// [`CancelReason::Cancelled`], so their handlers can tell from
+/// [`Responder::cancel_reason()`](crate::Responder::cancel_reason()) that the work
+/// is no longer needed. If every request fails, the last error is returned.
+pub async fn race_send_receive<Req, Res>(
+    senders: &[RequestSender<Req, Res>],
+    request: Req,
+) -> Result<Res, RequestError<Req>>
+where
+    Req: Clone,
+{
+    let mut pending: FuturesUnordered<_> = senders
+        .iter()
+        .map(|sender| {
+            let request = request.clone();
+            async move {
+                let receiver = sender.send(request).await?;
+                let mut racer = Racer(receiver);
+                racer.0.recv().await.map_err(RequestError::from)
+            }
+        })
+        .collect();
+    let mut last_error = None;
+    while let Some(result) = pending.next().await {
+        match result {
+            Ok(response) => return Ok(response),
+            Err(err) => last_error = Some(err),
+        }
+    }
+    Err(last_error.unwrap_or(RequestError::SendError(request)))
+}
+
+/// Cancels the request of a racing response receiver that is dropped before it
+/// received the response
+struct Racer<Res>(ResponseReceiver<Res>);
+
+impl<Res> Drop for Racer<Res> {
+    fn drop(&mut self) {
+        if self.0.response_receiver.is_some() {
+            self.0.state.cancel(CancelReason::Cancelled);
+        }
+    }
+}
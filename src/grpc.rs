@@ -0,0 +1,65 @@
+use crate::bounded::{RequestReceiver, RequestSender};
+use crate::error::RequestError;
+use crate::serve::{ServeReport, ServeReporter};
+
+use std::future::Future;
+use tonic::{Request, Response, Status};
+
+impl<T> From<RequestError<T>> for Status {
+    fn from(err: RequestError<T>) -> Status {
+        match err {
+            RequestError::RecvError(..) => Status::internal("request handler dropped the request"),
+            RequestError::RecvTimeoutError(..) => Status::deadline_exceeded("request timed out"),
+            RequestError::SendError(..) => Status::unavailable("request channel closed"),
+            RequestError::HandlerPanicked => Status::internal("request handler panicked"),
+            RequestError::SendTimeoutError(..) => {
+                Status::resource_exhausted("request channel stayed full")
+            }
+            RequestError::Evicted => {
+                Status::resource_exhausted("request evicted from a full channel")
+            }
+            RequestError::Superseded => Status::aborted("request superseded by a newer one"),
+            RequestError::Expired => Status::deadline_exceeded("request expired in the queue"),
+            RequestError::Rejected(..) => Status::unavailable("request channel overloaded"),
+            RequestError::Cancelled => Status::cancelled("request cancelled"),
+            RequestError::CircuitOpen(..) => Status::unavailable("request channel circuit open"),
+            RequestError::ShutDown => Status::unavailable("request handler shut down"),
+        }
+    }
+}
+
+/// Forward a gRPC request into a bmrng channel and wait for the response
+///
+/// Call this from the methods of a tonic service implementation to hand each RPC
+/// over to the [`RequestReceiver`] of the channel. Channel errors are translated
+/// into the corresponding [`Status`].
+pub async fn forward<Req, Res>(
+    sender: &RequestSender<Req, Result<Res, Status>>,
+    request: Request<Req>,
+) -> Result<Response<Res>, Status> {
+    sender
+        .send_receive(request.into_inner())
+        .await?
+        .map(Response::new)
+}
+
+/// Answer the requests of a bmrng channel by calling a gRPC client
+///
+/// `call` is typically a closure that clones a generated tonic client and calls
+/// one of its methods. The requests are handled one at a time until the channel
+/// closes, then a [`ServeReport`] is returned.
+pub async fn serve_client<Req, Res, F, Fut>(
+    mut receiver: RequestReceiver<Req, Result<Res, Status>>,
+    mut call: F,
+) -> ServeReport
+where
+    F: FnMut(Request<Req>) -> Fut,
+    Fut: Future<Output = Result<Response<Res>, Status>>,
+{
+    let mut reporter = ServeReporter::start();
+    while let Ok((request, responder)) = receiver.recv().await {
+        let response = call(Request::new(request)).await;
+        reporter.record(responder.respond_outcome(response.map(Response::into_inner)));
+    }
+    reporter.finish()
+}
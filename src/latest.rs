@@ -0,0 +1,100 @@
+use crate::bounded::{Payload, ResponseReceiver};
+use crate::error::{ReceiveError, RequestError, SendError};
+use crate::ring::{self, Overflow, RingRequestReceiver, RingRequestSender};
+
+/// Send requests to the associated [`LatestRequestReceiver`], replacing the pending one
+///
+/// Instances are created by the [`channel()`] function.
+#[derive(Debug)]
+pub struct LatestRequestSender<Req, Res> {
+    inner: RingRequestSender<Req, Res>,
+}
+
+/// Receive the newest request sent by the associated [`LatestRequestSender`]s
+///
+/// Instances are created by the [`channel()`] function.
+#[derive(Debug)]
+pub struct LatestRequestReceiver<Req, Res> {
+    inner: RingRequestReceiver<Req, Res>,
+}
+
+impl<Req, Res> LatestRequestSender<Req, Res> {
+    /// Send a request over the channel, open the response channel
+    ///
+    /// This call never waits. If a request is still pending, it is replaced and its
+    /// sender fails with [`ReceiveError::Superseded`].
+    pub fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.inner
+            .try_send(request)
+            .map_err(|err| SendError(err.into_inner()))
+    }
+
+    /// Send a request over the channel, wait for the response and return it
+    ///
+    /// It fails with [`RequestError::Superseded`] if a newer request replaces it
+    /// before it is received.
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request)?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Checks if the channel has been closed
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+}
+
+impl<Req, Res> LatestRequestReceiver<Req, Res> {
+    /// Receives the newest request, waiting until one is sent
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        self.inner.recv().await
+    }
+
+    /// Returns `true` if a request is waiting to be received
+    pub fn has_pending(&self) -> bool {
+        !self.inner.is_empty()
+    }
+}
+
+impl<Req, Res> Clone for LatestRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        LatestRequestSender {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+/// Creates a request-response channel that only keeps the newest pending request
+///
+/// Every request sent while another one is pending replaces it, and the replaced
+/// request fails with [`ReceiveError::Superseded`]. Use it when only the most recent
+/// request matters, like applying settings.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::error::ReceiveError;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::latest::channel::<&str, bool>();
+///     let old = tx.send("light theme").unwrap();
+///     let new = tx.send("dark theme").unwrap();
+///     assert_eq!(old.await, Err(ReceiveError::Superseded));
+///     let (theme, responder) = rx.recv().await.unwrap();
+///     assert_eq!(theme, "dark theme");
+///     let _ = responder.respond(true);
+///     assert_eq!(new.await, Ok(true));
+/// }
+/// ```
+pub fn channel<Req, Res>() -> (
+    LatestRequestSender<Req, Res>,
+    LatestRequestReceiver<Req, Res>,
+) {
+    let (sender, receiver) =
+        ring::channel_evicting(1, Overflow::DropOldest, ReceiveError::Superseded);
+    (
+        LatestRequestSender { inner: sender },
+        LatestRequestReceiver { inner: receiver },
+    )
+}
@@ -24,14 +24,106 @@
 //! See [`bmrng::channel()`](crate::channel()) for a channel with backpressure and
 //! [`bmrng::unbounded::channel()`](crate::unbounded::channel()) for a channel without backpressure.
 
+mod blocking;
 mod bounded;
 pub use self::bounded::{
-    channel, channel_with_timeout, Payload, RequestReceiver, RequestReceiverStream, RequestSender,
-    Responder, ResponseReceiver,
+    channel, channel_const, channel_with_timeout, channel_with_timeouts, spawn_blocking_handler,
+    spawn_thread_handler, typed_channel, GuardedResponder, OwnedPermit, Payload, PayloadExt,
+    Permit, RequestReceiver, RequestReceiverStream, RequestSender, Responder, ResponseFuture,
+    ResponseReceiver, SharedRequestReceiver, SharedResponseReceiver, WeakRequestSender,
 };
+mod builder;
+pub use self::builder::{builder, ChannelBuilder};
+mod circuit;
+pub use self::circuit::{CircuitBreaker, CircuitBreakerSender, CircuitState};
+mod clock;
+pub use self::clock::{Clock, ClockSleep, TokioClock};
+mod coalesce;
+pub use self::coalesce::CoalescingSender;
+mod duplex;
+pub use self::duplex::{duplex, duplex_with_timeout, DuplexHandle};
+mod merge;
+pub use self::merge::{merge, Merge};
+mod pause;
+pub use self::pause::PauseHandle;
+mod poll;
+pub use self::poll::PollRequestSender;
+mod rate_limit;
+mod rendezvous;
+pub use self::rendezvous::{rendezvous_channel, RendezvousReceiver, RendezvousSender};
+mod request;
+pub use self::request::Request;
+mod retry;
+pub use self::retry::{Backoff, RetryPolicy};
+mod scope;
+pub use self::scope::scope;
+mod send;
+pub use self::send::{BoxRequestSender, RequestSend};
+mod serve;
+mod sink;
+pub use self::serve::ServeReport;
+pub use self::sink::{RequestSenderSink, ResponseReceiverStream};
+mod state;
+mod stream_ext;
+pub use self::state::{Admission, CancelReason, RequestContext, RequestId, SenderId};
+pub use self::stream_ext::{PayloadStreamExt, SplitPayloads};
+/// Serve repeated identical requests from a cache of their responses
+pub mod cache;
+/// Collect the requests a channel gives up on, to inspect or retry them
+pub mod dead_letter;
+/// Channels transporting requests of different [`Request`] types
+pub mod dynamic;
 /// The errors produced by this crate
+///
+/// With the `tracing-error` feature, the closed channel and timeout errors, and
+/// the `RespondError`, carry the `SpanTrace` captured where they were produced.
+/// The `_traced` variants of the send and receive methods wrap any of their
+/// errors in a `Traced` error carrying the `SpanTrace` of the failed request.
 pub mod error;
+/// Request channels whose receiver takes turns between the senders, so a chatty
+/// sender cannot starve the others
+pub mod fair;
+/// Send the same request to multiple channels
+///
+/// The request is cloned once for every channel. To deliver a large request body
+/// without copying it, use an `Arc<Req>` as the request type of the channels and
+/// send it with [`fanout::send_receive_all_shared()`], so only the reference count
+/// is cloned.
+pub mod fanout;
+/// Bridge bmrng channels and tonic gRPC services
+#[cfg(feature = "tonic")]
+pub mod grpc;
+/// Request channels that only keep the newest pending request
+pub mod latest;
+/// Hooks to instrument and observe channels, and counters implementing them
+pub mod metrics;
+/// Request-response pairs for a single request
+pub mod oneshot;
+/// Request channels whose requests are received by priority
+pub mod priority;
+/// Request channels whose handlers report progress before the final response
+pub mod progress;
+/// Bounded channels that can drop requests instead of waiting when full
+pub mod ring;
+/// Dispatch requests to one handler task per request type
+pub mod router;
+/// Send every request to a set of registered receivers and gather their responses
+pub mod scatter;
+/// Request channels split into shards, routed by a key of the request
+pub mod sharded;
+/// Request channels answered with a stream of response items
+///
+/// Use this for request-subscribe patterns, like tailing logs.
+pub mod streaming;
+/// Mock receivers answering requests from a closure or a script, assertions on
+/// the requests they received, and recordings of request-response traffic to
+/// replay in regression tests
+pub mod test;
+/// Use bounded bmrng channels as tower services
+#[cfg(feature = "tower")]
+pub mod tower;
 /// The unbounded channel alternative
 pub mod unbounded;
 pub use unbounded::channel as unbounded_channel;
 pub use unbounded::channel_with_timeout as unbounded_channel_with_timeout;
+pub use unbounded::typed_channel as unbounded_typed_channel;
@@ -0,0 +1,112 @@
+use crate::bounded::{Payload, RequestReceiver, RequestReceiverStream};
+
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A stream of the requests of several receivers
+///
+/// Instances are created by the [`merge()`](crate::merge()) and
+/// [`unbounded::merge()`](crate::unbounded::merge()) functions, or from any payload
+/// streams with [`Merge::new()`]. The stream ends once all the inner streams ended.
+#[derive(Debug)]
+pub struct Merge<S> {
+    /// The inner streams, `None` once they ended
+    streams: Vec<Option<S>>,
+    next: usize,
+    biased: bool,
+}
+
+impl<S> Merge<S> {
+    /// Merges the given payload streams, polling them round-robin
+    pub fn new(streams: impl IntoIterator<Item = S>) -> Self {
+        Merge {
+            streams: streams.into_iter().map(Some).collect(),
+            next: 0,
+            biased: false,
+        }
+    }
+
+    /// Polls the streams in the order they were given instead of round-robin, so
+    /// the requests of the first streams always take precedence
+    pub fn biased(mut self) -> Self {
+        self.biased = true;
+        self
+    }
+
+    /// Returns the number of inner streams that have not ended yet
+    pub fn len(&self) -> usize {
+        self.streams
+            .iter()
+            .filter(|stream| stream.is_some())
+            .count()
+    }
+
+    /// Returns `true` if all the inner streams have ended
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<S, Req, Res> Stream for Merge<S>
+where
+    S: Stream<Item = Payload<Req, Res>> + Unpin,
+{
+    type Item = Payload<Req, Res>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let len = this.streams.len();
+        let start = if this.biased { 0 } else { this.next };
+        let mut pending = false;
+        for offset in 0..len {
+            let index = (start + offset) % len;
+            let Some(stream) = this.streams[index].as_mut() else {
+                continue;
+            };
+            match Pin::new(stream).poll_next(cx) {
+                Poll::Ready(Some(payload)) => {
+                    this.next = (index + 1) % len;
+                    return Poll::Ready(Some(payload));
+                }
+                Poll::Ready(None) => this.streams[index] = None,
+                Poll::Pending => pending = true,
+            }
+        }
+        if pending {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
+
+/// Merges the requests of several bounded receivers into one stream
+///
+/// The receivers are polled round-robin, so a busy channel does not starve the
+/// others. Call [`Merge::biased()`] to prefer the first receivers instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use futures_util::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (reads, reads_rx) = bmrng::channel::<&str, usize>(1);
+///     let (writes, writes_rx) = bmrng::channel::<&str, usize>(1);
+///     tokio::spawn(async move {
+///         let mut requests = bmrng::merge(vec![reads_rx, writes_rx]);
+///         while let Some((request, responder)) = requests.next().await {
+///             let _ = responder.respond(request.len());
+///         }
+///     });
+///     assert_eq!(reads.send_receive("read").await, Ok(4));
+///     assert_eq!(writes.send_receive("write").await, Ok(5));
+/// }
+/// ```
+pub fn merge<Req, Res>(
+    receivers: impl IntoIterator<Item = RequestReceiver<Req, Res>>,
+) -> Merge<RequestReceiverStream<Req, Res>> {
+    Merge::new(receivers.into_iter().map(RequestReceiverStream::new))
+}
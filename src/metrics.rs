@@ -0,0 +1,362 @@
+use crate::state::RequestId;
+
+use std::any::type_name;
+use std::collections::BTreeMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::time::Duration;
+
+/// Hooks called by a channel as its requests go through it
+///
+/// Attach an implementation to a channel with [`ChannelBuilder::metrics()`](crate::ChannelBuilder::metrics()),
+/// for example to export the activity of the channel to a monitoring system.
+/// Every hook does nothing by default. The hooks are called synchronously on the
+/// sending or the receiving task, so they should be cheap, like incrementing a counter.
+pub trait ChannelMetrics: Send + Sync {
+    /// Called when a request is sent over the channel
+    fn on_send(&self) {}
+
+    /// Called when the receiver takes a request out of the queue, with the time the
+    /// request waited in the queue
+    fn on_recv(&self, _queued: Duration) {}
+
+    /// Called when a response is delivered, with the end-to-end latency since the
+    /// request was sent
+    ///
+    /// It is called by the responding side right after the response is handed over,
+    /// so the requesting side may see the response before the hook is called.
+    fn on_respond(&self, _latency: Duration) {}
+
+    /// Called when the requesting side stops waiting because the response timeout elapsed
+    fn on_timeout(&self) {}
+
+    /// Called when a responder is dropped without responding, including the
+    /// requests that expired in the queue
+    fn on_drop(&self) {}
+}
+
+/// Callbacks called by a channel when one of its requests is lost, to tell apart
+/// the reasons why requests go unanswered
+///
+/// Attach an implementation to a channel with [`ChannelBuilder::observer()`](crate::ChannelBuilder::observer()).
+/// Every callback does nothing by default.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::metrics::ChannelObserver;
+/// use bmrng::RequestId;
+/// use std::sync::Arc;
+///
+/// struct LogLostRequests;
+///
+/// impl ChannelObserver for LogLostRequests {
+///     fn on_responder_dropped(&self, id: RequestId) {
+///         eprintln!("{} was received but never answered", id);
+///     }
+/// }
+///
+/// let (tx, rx) = bmrng::builder::<i32, i32>()
+///     .capacity(16)
+///     .observer(Arc::new(LogLostRequests))
+///     .build();
+/// ```
+pub trait ChannelObserver: Send + Sync {
+    /// Called when the receiver took a request, but dropped its responder without responding
+    fn on_responder_dropped(&self, _id: RequestId) {}
+
+    /// Called when a request is dropped before the receiver took it, because the
+    /// receiver was dropped or the request expired in the queue
+    fn on_request_dropped(&self, _id: RequestId) {}
+
+    /// Called when a response could not be delivered because the response timeout
+    /// had elapsed, with the time since the request was sent
+    fn on_late_response(&self, _id: RequestId, _elapsed: Duration) {}
+}
+
+/// The upper bounds of the default latency buckets of [`ChannelStats`]
+const DEFAULT_BUCKETS: [Duration; 10] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_secs(5),
+];
+
+/// A [`ChannelMetrics`] implementation counting the requests of a channel and
+/// recording their end-to-end latency in a histogram
+///
+/// Keep an `Arc` of it to read the counters while the channel is in use.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::metrics::ChannelStats;
+/// use std::sync::Arc;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let stats = Arc::new(ChannelStats::new());
+///     let (tx, rx) = bmrng::builder::<i32, i32>()
+///         .capacity(1)
+///         .metrics(stats.clone())
+///         .build();
+///     let server = tokio::spawn(rx.serve(|input| async move { input * 2 }));
+///     assert_eq!(tx.send_receive(21).await, Ok(42));
+///     drop(tx);
+///     server.await.unwrap();
+///     assert_eq!(stats.sent(), 1);
+///     assert_eq!(stats.responded(), 1);
+///     assert_eq!(stats.latency().count(), 1);
+/// }
+/// ```
+pub struct ChannelStats {
+    sent: AtomicU64,
+    received: AtomicU64,
+    responded: AtomicU64,
+    timed_out: AtomicU64,
+    dropped: AtomicU64,
+    bounds: Vec<Duration>,
+    /// The number of responses per latency bucket, the last one having no upper bound
+    buckets: Vec<AtomicU64>,
+    latency_sum_nanos: AtomicU64,
+}
+
+impl ChannelStats {
+    /// Creates the counters with latency buckets from 1 millisecond up to 5 seconds
+    pub fn new() -> Self {
+        Self::with_buckets(DEFAULT_BUCKETS.to_vec())
+    }
+
+    /// Creates the counters with the given upper bounds of the latency buckets
+    ///
+    /// The bounds are sorted, and a bucket without an upper bound is added after them.
+    pub fn with_buckets(mut bounds: Vec<Duration>) -> Self {
+        bounds.sort();
+        bounds.dedup();
+        ChannelStats {
+            sent: AtomicU64::new(0),
+            received: AtomicU64::new(0),
+            responded: AtomicU64::new(0),
+            timed_out: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            buckets: (0..=bounds.len()).map(|_| AtomicU64::new(0)).collect(),
+            bounds,
+            latency_sum_nanos: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of requests sent over the channel
+    pub fn sent(&self) -> u64 {
+        self.sent.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests taken out of the queue by the receiver
+    pub fn received(&self) -> u64 {
+        self.received.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of responses delivered to the requesting side
+    pub fn responded(&self) -> u64 {
+        self.responded.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of requests whose response timeout elapsed
+    pub fn timed_out(&self) -> u64 {
+        self.timed_out.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of responders dropped without responding
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns a snapshot of the end-to-end latency of the delivered responses
+    pub fn latency(&self) -> LatencyHistogram {
+        let mut cumulative = 0;
+        let buckets = self
+            .buckets
+            .iter()
+            .enumerate()
+            .map(|(index, count)| {
+                cumulative += count.load(Ordering::Relaxed);
+                (self.bounds.get(index).copied(), cumulative)
+            })
+            .collect();
+        LatencyHistogram {
+            buckets,
+            sum: Duration::from_nanos(self.latency_sum_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl Default for ChannelStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelMetrics for ChannelStats {
+    fn on_send(&self) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_recv(&self, _queued: Duration) {
+        self.received.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_respond(&self, latency: Duration) {
+        self.responded.fetch_add(1, Ordering::Relaxed);
+        let bucket = self.bounds.partition_point(|bound| *bound < latency);
+        self.buckets[bucket].fetch_add(1, Ordering::Relaxed);
+        let nanos = u64::try_from(latency.as_nanos()).unwrap_or(u64::MAX);
+        self.latency_sum_nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    fn on_timeout(&self) {
+        self.timed_out.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn on_drop(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl fmt::Debug for ChannelStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ChannelStats")
+            .field("sent", &self.sent())
+            .field("received", &self.received())
+            .field("responded", &self.responded())
+            .field("timed_out", &self.timed_out())
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+/// A snapshot of the latency histogram of a [`ChannelStats`]
+///
+/// The buckets are cumulative like the ones of a Prometheus histogram: each bucket
+/// counts the responses whose latency was at most its upper bound.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    buckets: Vec<(Option<Duration>, u64)>,
+    sum: Duration,
+}
+
+impl LatencyHistogram {
+    /// Returns the upper bound of every bucket with its cumulative count
+    ///
+    /// The last bucket has no upper bound, so it counts all the responses.
+    pub fn buckets(&self) -> &[(Option<Duration>, u64)] {
+        &self.buckets
+    }
+
+    /// Returns the number of responses recorded
+    pub fn count(&self) -> u64 {
+        self.buckets.last().map_or(0, |(_, count)| *count)
+    }
+
+    /// Returns the sum of the latencies recorded
+    pub fn sum(&self) -> Duration {
+        self.sum
+    }
+}
+
+/// Provides the [`ChannelMetrics`] of every request type sent over a
+/// [dynamic](crate::dynamic) channel
+///
+/// Attach an implementation with [`dynamic::channel_with_metrics()`](crate::dynamic::channel_with_metrics()),
+/// so a single shared channel still yields a latency and error breakdown per
+/// request type.
+pub trait TypeMetrics: Send + Sync {
+    /// Returns the hooks to call for the requests of the type named `type_name`
+    ///
+    /// It is called for every request, so it should be cheap, like a map lookup.
+    fn for_type(&self, type_name: &'static str) -> Arc<dyn ChannelMetrics>;
+}
+
+/// A [`TypeMetrics`] implementation keeping a [`ChannelStats`] per request type
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::metrics::TypeStats;
+/// use std::sync::Arc;
+///
+/// #[derive(Debug)]
+/// struct Ping;
+///
+/// impl bmrng::Request for Ping {
+///     type Response = ();
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let stats = Arc::new(TypeStats::new());
+///     let (tx, mut rx) = bmrng::dynamic::channel_with_metrics(16, stats.clone());
+///     tokio::spawn(async move {
+///         while let Ok(payload) = rx.recv().await {
+///             if let Ok((Ping, responder)) = payload.downcast::<Ping>() {
+///                 let _ = responder.respond(());
+///             }
+///         }
+///     });
+///     tx.send_receive(Ping).await.unwrap();
+///     assert_eq!(stats.of::<Ping>().map(|stats| stats.responded()), Some(1));
+/// }
+/// ```
+#[derive(Default)]
+pub struct TypeStats {
+    stats: Mutex<BTreeMap<&'static str, Arc<ChannelStats>>>,
+}
+
+impl TypeStats {
+    /// Creates an empty set of counters, the counters of a request type are added
+    /// when its first request is sent
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counters of the request type named `type_name`, if any request
+    /// of that type was sent
+    pub fn get(&self, type_name: &str) -> Option<Arc<ChannelStats>> {
+        self.lock().get(type_name).cloned()
+    }
+
+    /// Returns the counters of the request type `R`, if any request of that type was sent
+    pub fn of<R: 'static>(&self) -> Option<Arc<ChannelStats>> {
+        self.get(type_name::<R>())
+    }
+
+    /// Returns the names of the request types sent so far, in alphabetical order
+    pub fn type_names(&self) -> Vec<&'static str> {
+        self.lock().keys().copied().collect()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<&'static str, Arc<ChannelStats>>> {
+        self.stats.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl TypeMetrics for TypeStats {
+    fn for_type(&self, type_name: &'static str) -> Arc<dyn ChannelMetrics> {
+        self.lock()
+            .entry(type_name)
+            .or_insert_with(|| Arc::new(ChannelStats::new()))
+            .clone()
+    }
+}
+
+impl fmt::Debug for TypeStats {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_map().entries(self.lock().iter()).finish()
+    }
+}
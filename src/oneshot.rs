@@ -0,0 +1,111 @@
+use crate::bounded::{new_payload, Payload, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::state::ChannelState;
+
+use std::sync::Arc;
+
+use tokio::sync::oneshot;
+use tokio::time::Duration;
+
+/// Send a single request to the associated [`ResponderSlot`]
+///
+/// Instances are created by the [`request`] function.
+#[derive(Debug)]
+pub struct PendingRequest<Req, Res> {
+    payload_sender: oneshot::Sender<Payload<Req, Res>>,
+    channel: Arc<ChannelState>,
+}
+
+/// Receive the single request sent by the associated [`PendingRequest`]
+///
+/// Instances are created by the [`request`] function.
+#[derive(Debug)]
+pub struct ResponderSlot<Req, Res> {
+    payload_receiver: oneshot::Receiver<Payload<Req, Res>>,
+}
+
+impl<Req, Res> PendingRequest<Req, Res> {
+    fn new(
+        payload_sender: oneshot::Sender<Payload<Req, Res>>,
+        timeout_duration: Option<Duration>,
+    ) -> Self {
+        PendingRequest {
+            payload_sender,
+            channel: Arc::new(ChannelState::new(timeout_duration)),
+        }
+    }
+
+    /// Send the request to the [`ResponderSlot`], open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response
+    pub fn send(self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        let (payload, receiver) = new_payload(request, &self.channel);
+        self.payload_sender
+            .send(payload)
+            .map_err(|payload| SendError(payload.0))?;
+        Ok(receiver)
+    }
+
+    /// Send the request to the [`ResponderSlot`], wait for the response and return it
+    pub async fn send_receive(self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request)?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Checks if the [`ResponderSlot`] has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.payload_sender.is_closed()
+    }
+}
+
+impl<Req, Res> ResponderSlot<Req, Res> {
+    fn new(payload_receiver: oneshot::Receiver<Payload<Req, Res>>) -> Self {
+        ResponderSlot { payload_receiver }
+    }
+
+    /// Receives the request, waiting until the [`PendingRequest`] sends it.
+    pub async fn recv(self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        self.payload_receiver
+            .await
+            .map_err(|_| RequestError::recv_error())
+    }
+}
+
+/// Creates a request-response pair for a single request, without constructing
+/// a whole mpsc channel
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let (pending, slot) = bmrng::oneshot::request::<i32, i32>();
+///     tokio::spawn(async move {
+///         if let Ok((input, responder)) = slot.recv().await {
+///             let res = responder.respond(input * input);
+///             assert!(res.is_ok());
+///         }
+///     });
+///     assert_eq!(pending.send_receive(4).await, Ok(16));
+/// }
+/// ```
+pub fn request<Req, Res>() -> (PendingRequest<Req, Res>, ResponderSlot<Req, Res>) {
+    let (sender, receiver) = oneshot::channel::<Payload<Req, Res>>();
+    (
+        PendingRequest::new(sender, None),
+        ResponderSlot::new(receiver),
+    )
+}
+
+/// Creates a request-response pair for a single request with a response timeout
+///
+/// Also see [`bmrng::channel_with_timeout()`](crate::channel_with_timeout())
+pub fn request_with_timeout<Req, Res>(
+    timeout_duration: Duration,
+) -> (PendingRequest<Req, Res>, ResponderSlot<Req, Res>) {
+    let (sender, receiver) = oneshot::channel::<Payload<Req, Res>>();
+    (
+        PendingRequest::new(sender, Some(timeout_duration)),
+        ResponderSlot::new(receiver),
+    )
+}
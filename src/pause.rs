@@ -0,0 +1,112 @@
+use std::fmt;
+use std::future::poll_fn;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+/// Whether a receiver is paused, shared with its [`PauseHandle`]s
+#[derive(Debug, Default)]
+pub(crate) struct PauseState {
+    paused: AtomicBool,
+    /// The tasks waiting for the receiver to resume
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl PauseState {
+    fn lock(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub(crate) fn pause(&self) {
+        self.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub(crate) fn resume(&self) {
+        self.paused.store(false, Ordering::SeqCst);
+        let wakers = std::mem::take(&mut *self.lock());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    pub(crate) fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Returns `Poll::Ready` once the receiver is not paused
+    pub(crate) fn poll_resumed(&self, cx: &mut Context<'_>) -> Poll<()> {
+        if !self.is_paused() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.lock();
+        if !self.is_paused() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    /// Waits until the receiver is not paused
+    pub(crate) async fn resumed(&self) {
+        poll_fn(|cx| self.poll_resumed(cx)).await
+    }
+}
+
+/// Pauses and resumes the deliveries of a receiver from another task
+///
+/// While the receiver is paused, its receive methods wait, or find no requests
+/// without waiting, and the queued requests stay in the queue. The senders of a
+/// bounded channel wait for capacity once the queue is full. Instances are created
+/// by calling [`RequestReceiver::pause_handle()`](crate::RequestReceiver::pause_handle()).
+///
+/// # Examples
+///
+/// ```rust
+/// use tokio::time::{timeout, Duration};
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, rx) = bmrng::channel::<i32, i32>(4);
+///     let pause = rx.pause_handle();
+///     tokio::spawn(rx.serve(|input| async move { input * 2 }));
+///
+///     pause.pause();
+///     let mut response = tx.send(21).await.unwrap();
+///     assert!(timeout(Duration::from_millis(10), response.recv()).await.is_err());
+///     pause.resume();
+///     assert_eq!(response.recv().await, Ok(42));
+/// }
+/// ```
+#[derive(Clone)]
+pub struct PauseHandle {
+    pub(crate) state: Arc<PauseState>,
+}
+
+impl PauseHandle {
+    /// Stops delivering requests until [`resume()`](Self::resume()) is called
+    pub fn pause(&self) {
+        self.state.pause();
+    }
+
+    /// Delivers requests again, waking the tasks waiting for them
+    pub fn resume(&self) {
+        self.state.resume();
+    }
+
+    /// Returns `true` if the receiver is paused
+    pub fn is_paused(&self) -> bool {
+        self.state.is_paused()
+    }
+}
+
+impl fmt::Debug for PauseHandle {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PauseHandle")
+            .field("paused", &self.is_paused())
+            .finish()
+    }
+}
@@ -0,0 +1,135 @@
+use crate::bounded::{OwnedPermit, RequestSender, ResponseReceiver};
+use crate::error::{PollSendError, SendError};
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type ReserveFuture<Req, Res> =
+    Pin<Box<dyn Future<Output = Result<OwnedPermit<Req, Res>, SendError<()>>> + Send>>;
+
+/// A wrapper around [`RequestSender`] with a poll-based API, for hand-written
+/// futures and state machines
+///
+/// Reserve a slot with [`poll_reserve()`](Self::poll_reserve()), then send the
+/// request with [`send_item()`](Self::send_item()). Instances are created by
+/// calling [`PollRequestSender::new()`].
+pub struct PollRequestSender<Req, Res> {
+    sender: Option<RequestSender<Req, Res>>,
+    permit: Option<OwnedPermit<Req, Res>>,
+    reserve: Option<ReserveFuture<Req, Res>>,
+}
+
+impl<Req, Res> PollRequestSender<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    /// Creates a poll-based sender sending its requests with `sender`
+    pub fn new(sender: RequestSender<Req, Res>) -> Self {
+        PollRequestSender {
+            sender: Some(sender),
+            permit: None,
+            reserve: None,
+        }
+    }
+
+    /// Waits for capacity in the request channel and reserves a slot for one request
+    ///
+    /// Once it returns `Ready(Ok(()))`, the next [`send_item()`](Self::send_item())
+    /// call succeeds. It fails if the channel or this sender is closed.
+    pub fn poll_reserve(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), PollSendError<Req>>> {
+        if self.permit.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        let sender = match &self.sender {
+            Some(sender) => sender,
+            None => return Poll::Ready(Err(PollSendError(None))),
+        };
+        let reserve = match &mut self.reserve {
+            Some(reserve) => reserve,
+            None => self
+                .reserve
+                .insert(Box::pin(sender.clone().reserve_owned())),
+        };
+        match reserve.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                self.reserve = None;
+                match result {
+                    Ok(permit) => {
+                        self.permit = Some(permit);
+                        Poll::Ready(Ok(()))
+                    }
+                    Err(..) => Poll::Ready(Err(PollSendError(None))),
+                }
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Sends a request using the slot reserved by [`poll_reserve()`](Self::poll_reserve())
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response.
+    /// If no slot is reserved, the request is handed back in the error.
+    pub fn send_item(&mut self, request: Req) -> Result<ResponseReceiver<Res>, PollSendError<Req>> {
+        match self.permit.take() {
+            Some(permit) => Ok(permit.send(request)),
+            None => Err(PollSendError(Some(request))),
+        }
+    }
+
+    /// Releases the reserved slot, or stops waiting for one
+    ///
+    /// Returns `true` if a slot was reserved or being reserved.
+    pub fn abort_send(&mut self) -> bool {
+        let aborted = self.permit.is_some() || self.reserve.is_some();
+        self.permit = None;
+        self.reserve = None;
+        aborted
+    }
+
+    /// Closes this sender, so no more requests can be sent with it
+    ///
+    /// The channel itself is only closed once every [`RequestSender`] is dropped.
+    pub fn close(&mut self) {
+        self.abort_send();
+        self.sender = None;
+    }
+
+    /// Checks if this sender or the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.sender.as_ref().is_none_or(|sender| sender.is_closed())
+    }
+
+    /// Returns the inner [`RequestSender`], or `None` if this sender is closed
+    pub fn get_ref(&self) -> Option<&RequestSender<Req, Res>> {
+        self.sender.as_ref()
+    }
+
+    /// Consumes this sender, returning the inner [`RequestSender`], or `None` if
+    /// this sender is closed
+    pub fn into_inner(self) -> Option<RequestSender<Req, Res>> {
+        self.sender
+    }
+}
+
+impl<Req, Res> Clone for PollRequestSender<Req, Res> {
+    /// Clones the sender, without the slot reserved by this one
+    fn clone(&self) -> Self {
+        PollRequestSender {
+            sender: self.sender.clone(),
+            permit: None,
+            reserve: None,
+        }
+    }
+}
+
+impl<Req, Res> fmt::Debug for PollRequestSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PollRequestSender")
+            .field("closed", &self.sender.is_none())
+            .field("ready", &self.permit.is_some())
+            .finish_non_exhaustive()
+    }
+}
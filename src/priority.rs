@@ -0,0 +1,341 @@
+use crate::bounded::{new_payload, Payload, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::state::ChannelState;
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+use tokio::time::{Duration, Instant};
+
+/// Send requests with a priority to the associated [`PriorityRequestReceiver`]
+///
+/// Instances are created by the [`channel()`], [`channel_with_timeout()`] and
+/// [`channel_with_aging()`] functions.
+pub struct PriorityRequestSender<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+/// Receive requests from the associated [`PriorityRequestSender`]s, the most
+/// urgent first
+///
+/// Instances are created by the [`channel()`], [`channel_with_timeout()`] and
+/// [`channel_with_aging()`] functions.
+pub struct PriorityRequestReceiver<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+struct Shared<Req, Res> {
+    queue: Mutex<Queue<Req, Res>>,
+    capacity: usize,
+    /// How long a request waits in the queue to gain one priority level
+    aging: Option<Duration>,
+    /// The instant the queue times of the requests are measured from
+    epoch: Instant,
+    /// Notified when a payload is queued, or when the last sender is dropped
+    not_empty: Notify,
+    /// Notified when a payload is received, or when the receiver is dropped
+    not_full: Notify,
+    channel: Arc<ChannelState>,
+}
+
+struct Queue<Req, Res> {
+    entries: BinaryHeap<Entry<Req, Res>>,
+    sequence: u64,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+struct Entry<Req, Res> {
+    /// The priority of the request, minus the aging it does not have on older requests
+    rank: i128,
+    sequence: Reverse<u64>,
+    payload: Payload<Req, Res>,
+}
+
+impl<Req, Res> PartialEq for Entry<Req, Res> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<Req, Res> Eq for Entry<Req, Res> {}
+
+impl<Req, Res> PartialOrd for Entry<Req, Res> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<Req, Res> Ord for Entry<Req, Res> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.rank, self.sequence).cmp(&(other.rank, other.sequence))
+    }
+}
+
+impl<Req, Res> Shared<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Queue<Req, Res>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Ranks a request so that comparing ranks at any later instant gives the same
+    /// order as comparing the aged priorities
+    fn rank(&self, priority: u32) -> i128 {
+        match self.aging {
+            Some(aging) => {
+                let queued_at = Instant::now().duration_since(self.epoch).as_nanos() as i128;
+                i128::from(priority) * aging.as_nanos() as i128 - queued_at
+            }
+            None => i128::from(priority),
+        }
+    }
+}
+
+impl<Req, Res> PriorityRequestSender<Req, Res> {
+    /// Send a request with the given priority, open the response channel
+    ///
+    /// Requests with a higher priority are received first, and requests with the
+    /// same priority are received in the order they were sent. This call waits if
+    /// the channel is full.
+    pub async fn send_with_priority(
+        &self,
+        request: Req,
+        priority: u32,
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        let (payload, receiver) = new_payload(request, &self.shared.channel);
+        let mut payload = Some(payload);
+        loop {
+            let not_full = self.shared.not_full.notified();
+            tokio::pin!(not_full);
+            not_full.as_mut().enable();
+            {
+                let mut queue = self.shared.lock();
+                if !queue.receiver_alive {
+                    let payload = payload.take().expect("the payload is queued once");
+                    return Err(SendError(payload.0));
+                }
+                if queue.entries.len() < self.shared.capacity {
+                    queue.sequence += 1;
+                    let entry = Entry {
+                        rank: self.shared.rank(priority),
+                        sequence: Reverse(queue.sequence),
+                        payload: payload.take().expect("the payload is queued once"),
+                    };
+                    queue.entries.push(entry);
+                    drop(queue);
+                    self.shared.not_empty.notify_waiters();
+                    return Ok(receiver);
+                }
+            }
+            not_full.await;
+        }
+    }
+
+    /// Send a request with the lowest priority, `0`, open the response channel
+    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_with_priority(request, 0).await
+    }
+
+    /// Send a request with the given priority, wait for the response and return it
+    pub async fn send_receive_with_priority(
+        &self,
+        request: Req,
+        priority: u32,
+    ) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send_with_priority(request, priority).await?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Send a request with the lowest priority, wait for the response and return it
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        self.send_receive_with_priority(request, 0).await
+    }
+
+    /// Checks if the channel has been closed
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<Req, Res> PriorityRequestReceiver<Req, Res> {
+    /// Receives the most urgent request, waiting until one is available
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            let not_empty = self.shared.not_empty.notified();
+            tokio::pin!(not_empty);
+            not_empty.as_mut().enable();
+            {
+                let mut queue = self.shared.lock();
+                if let Some(entry) = queue.entries.pop() {
+                    drop(queue);
+                    self.shared.not_full.notify_waiters();
+                    return Ok(entry.payload);
+                }
+                if queue.senders == 0 {
+                    return Err(RequestError::recv_error());
+                }
+            }
+            not_empty.await;
+        }
+    }
+
+    /// Returns the number of requests waiting to be received
+    pub fn len(&self) -> usize {
+        self.shared.lock().entries.len()
+    }
+
+    /// Returns `true` if there are no requests waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<Req, Res> Clone for PriorityRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        PriorityRequestSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Req, Res> Drop for PriorityRequestSender<Req, Res> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.senders -= 1;
+        if queue.senders == 0 {
+            drop(queue);
+            self.shared.not_empty.notify_waiters();
+        }
+    }
+}
+
+impl<Req, Res> Drop for PriorityRequestReceiver<Req, Res> {
+    fn drop(&mut self) {
+        let entries = {
+            let mut queue = self.shared.lock();
+            queue.receiver_alive = false;
+            std::mem::take(&mut queue.entries)
+        };
+        drop(entries);
+        self.shared.not_full.notify_waiters();
+    }
+}
+
+impl<Req, Res> fmt::Debug for PriorityRequestSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PriorityRequestSender")
+            .field("capacity", &self.shared.capacity)
+            .field("aging", &self.shared.aging)
+            .field("channel", &self.shared.channel)
+            .finish()
+    }
+}
+
+impl<Req, Res> fmt::Debug for PriorityRequestReceiver<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("PriorityRequestReceiver")
+            .field("capacity", &self.shared.capacity)
+            .field("aging", &self.shared.aging)
+            .finish()
+    }
+}
+
+fn new_channel<Req, Res>(
+    capacity: usize,
+    timeout_duration: Option<Duration>,
+    aging: Option<Duration>,
+) -> (
+    PriorityRequestSender<Req, Res>,
+    PriorityRequestReceiver<Req, Res>,
+) {
+    assert!(capacity > 0, "priority channel requires capacity > 0");
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            entries: BinaryHeap::with_capacity(capacity),
+            sequence: 0,
+            senders: 1,
+            receiver_alive: true,
+        }),
+        capacity,
+        aging,
+        epoch: Instant::now(),
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+        channel: Arc::new(ChannelState::new(timeout_duration)),
+    });
+    (
+        PriorityRequestSender {
+            shared: shared.clone(),
+        },
+        PriorityRequestReceiver { shared },
+    )
+}
+
+/// Creates a bounded request-response channel whose requests are received by
+/// priority
+///
+/// # Panics
+///
+/// Panics if the capacity is 0
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::priority::channel::<&str, ()>(8);
+///     let _bulk = tx.send("bulk").await.unwrap();
+///     let _urgent = tx.send_with_priority("urgent", 10).await.unwrap();
+///     let (request, _responder) = rx.recv().await.unwrap();
+///     assert_eq!(request, "urgent");
+/// }
+/// ```
+pub fn channel<Req, Res>(
+    capacity: usize,
+) -> (
+    PriorityRequestSender<Req, Res>,
+    PriorityRequestReceiver<Req, Res>,
+) {
+    new_channel(capacity, None, None)
+}
+
+/// Creates a bounded request-response channel whose requests are received by
+/// priority, with a response timeout
+///
+/// Also see [`bmrng::channel_with_timeout()`](crate::channel_with_timeout())
+///
+/// # Panics
+///
+/// Panics if the capacity is 0
+pub fn channel_with_timeout<Req, Res>(
+    capacity: usize,
+    timeout_duration: Duration,
+) -> (
+    PriorityRequestSender<Req, Res>,
+    PriorityRequestReceiver<Req, Res>,
+) {
+    new_channel(capacity, Some(timeout_duration), None)
+}
+
+/// Creates a bounded request-response channel whose requests are received by
+/// priority, where a request gains one priority level for every `aging` it waits
+///
+/// Aging prevents a steady flow of urgent requests from starving the others.
+///
+/// # Panics
+///
+/// Panics if the capacity or `aging` is 0
+pub fn channel_with_aging<Req, Res>(
+    capacity: usize,
+    aging: Duration,
+) -> (
+    PriorityRequestSender<Req, Res>,
+    PriorityRequestReceiver<Req, Res>,
+) {
+    assert!(!aging.is_zero(), "priority aging requires a duration > 0");
+    new_channel(capacity, None, Some(aging))
+}
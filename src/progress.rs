@@ -0,0 +1,192 @@
+use crate::bounded::{new_payload, Responder, ResponseReceiver};
+use crate::error::{ReceiveError, RequestError, RespondError, SendError};
+use crate::state::ChannelState;
+
+use futures_core::Stream;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// The internal data sent in the MPSC request channel, a tuple that contains the request and the responder reporting its progress
+pub type Payload<Req, P, Res> = (Req, ProgressResponder<P, Res>);
+
+/// Send requests to the associated [`ProgressRequestReceiver`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct ProgressRequestSender<Req, P, Res> {
+    request_sender: mpsc::Sender<Payload<Req, P, Res>>,
+    buffer: usize,
+    channel: Arc<ChannelState>,
+}
+
+/// Receive requests from the associated [`ProgressRequestSender`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct ProgressRequestReceiver<Req, P, Res> {
+    request_receiver: mpsc::Receiver<Payload<Req, P, Res>>,
+}
+
+/// Report progress updates, then send the final response of a request
+#[derive(Debug)]
+pub struct ProgressResponder<P, Res> {
+    progress_sender: mpsc::Sender<P>,
+    responder: Responder<Res>,
+}
+
+/// Receive the progress updates and the final response sent by a [`ProgressResponder`]
+///
+/// The progress updates are available through [`progress()`](Self::progress()) or
+/// the [`Stream`] implementation, which ends once the final response is sent.
+#[derive(Debug)]
+pub struct ProgressReceiver<P, Res> {
+    progress_receiver: mpsc::Receiver<P>,
+    response_receiver: ResponseReceiver<Res>,
+}
+
+impl<Req, P, Res> ProgressRequestSender<Req, P, Res> {
+    /// Send a request over the MPSC channel, open the progress and response channels
+    ///
+    /// This call waits if the request channel is full
+    pub async fn send(&self, request: Req) -> Result<ProgressReceiver<P, Res>, SendError<Req>> {
+        let (progress_sender, progress_receiver) = mpsc::channel(self.buffer);
+        let ((request, responder), response_receiver) = new_payload(request, &self.channel);
+        let responder = ProgressResponder {
+            progress_sender,
+            responder,
+        };
+        self.request_sender
+            .send((request, responder))
+            .await
+            .map_err(|payload| SendError(payload.0 .0))?;
+        Ok(ProgressReceiver {
+            progress_receiver,
+            response_receiver,
+        })
+    }
+
+    /// Checks if the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.request_sender.is_closed()
+    }
+}
+
+impl<Req, P, Res> Clone for ProgressRequestSender<Req, P, Res> {
+    fn clone(&self) -> Self {
+        ProgressRequestSender {
+            request_sender: self.request_sender.clone(),
+            buffer: self.buffer,
+            channel: self.channel.clone(),
+        }
+    }
+}
+
+impl<Req, P, Res> ProgressRequestReceiver<Req, P, Res> {
+    /// Receives the next value for this receiver.
+    pub async fn recv(&mut self) -> Result<Payload<Req, P, Res>, RequestError<Req>> {
+        match self.request_receiver.recv().await {
+            Some(payload) => Ok(payload),
+            None => Err(RequestError::recv_error()),
+        }
+    }
+
+    /// Closes the receiving half of a channel without dropping it.
+    pub fn close(&mut self) {
+        self.request_receiver.close()
+    }
+}
+
+impl<P, Res> ProgressResponder<P, Res> {
+    /// Reports a progress update to the [`ProgressReceiver`]
+    ///
+    /// This call does not wait. If the requesting side is not keeping up with the
+    /// updates and the progress buffer is full, the update is skipped. It fails if
+    /// the [`ProgressReceiver`] has been dropped.
+    pub fn progress(&self, progress: P) -> Result<(), RespondError<P>> {
+        match self.progress_sender.try_send(progress) {
+            Ok(()) | Err(mpsc::error::TrySendError::Full(..)) => Ok(()),
+            Err(mpsc::error::TrySendError::Closed(progress)) => Err(RespondError::new(progress)),
+        }
+    }
+
+    /// Responds the request with the final response, which ends the progress updates
+    pub fn respond(self, response: Res) -> Result<(), RespondError<Res>> {
+        self.responder.respond(response)
+    }
+
+    /// Checks if the associated [`ProgressReceiver`] has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.responder.is_closed()
+    }
+}
+
+impl<P, Res> ProgressReceiver<P, Res> {
+    /// Receives the next progress update, or `None` once the final response is
+    /// sent and all the updates were received
+    pub async fn progress(&mut self) -> Option<P> {
+        self.progress_receiver.recv().await
+    }
+
+    /// Waits for the final response, skipping the progress updates that were not received
+    pub async fn recv(&mut self) -> Result<Res, ReceiveError> {
+        self.response_receiver.recv().await
+    }
+}
+
+impl<P, Res> Stream for ProgressReceiver<P, Res> {
+    type Item = P;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.progress_receiver.poll_recv(cx)
+    }
+}
+
+/// Creates a bounded mpsc request channel where the handler can report progress
+/// updates before the final response
+///
+/// `buffer` is the capacity of the request channel and of the progress updates of
+/// every request.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::progress::channel::<u32, u32, &str>(8);
+///     tokio::spawn(async move {
+///         while let Ok((steps, responder)) = rx.recv().await {
+///             for step in 1..=steps {
+///                 let _ = responder.progress(step);
+///             }
+///             let _ = responder.respond("done");
+///         }
+///     });
+///     let mut receiver = tx.send(3).await.unwrap();
+///     while let Some(step) = receiver.progress().await {
+///         println!("step {}", step);
+///     }
+///     assert_eq!(receiver.recv().await, Ok("done"));
+/// }
+/// ```
+pub fn channel<Req, P, Res>(
+    buffer: usize,
+) -> (
+    ProgressRequestSender<Req, P, Res>,
+    ProgressRequestReceiver<Req, P, Res>,
+) {
+    let (request_sender, request_receiver) = mpsc::channel(buffer);
+    (
+        ProgressRequestSender {
+            request_sender,
+            buffer,
+            channel: Arc::new(ChannelState::default()),
+        },
+        ProgressRequestReceiver { request_receiver },
+    )
+}
@@ -0,0 +1,71 @@
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use tokio::time::{Duration, Instant};
+
+/// A token bucket pacing the requests sent over a channel, see
+/// [`ChannelBuilder::rate_limit()`](crate::ChannelBuilder::rate_limit())
+///
+/// Implemented as the generic cell rate algorithm: instead of counting tokens,
+/// the bucket tracks when the next request would be due at the sustained rate.
+/// The instants are read from the [clock](crate::Clock) of the channel.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    /// The time between two requests at the sustained rate
+    interval: Duration,
+    /// How far ahead of the sustained rate a burst may run
+    tolerance: Duration,
+    /// When the next request is due at the sustained rate
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// # Panics
+    ///
+    /// Panics if `per_second` or `burst` is 0
+    pub(crate) fn new(per_second: u32, burst: u32, now: Instant) -> Self {
+        assert!(per_second > 0, "the rate limit must be positive");
+        assert!(burst > 0, "the rate limit burst must be positive");
+        let interval = Duration::from_secs(1) / per_second;
+        RateLimiter {
+            interval,
+            tolerance: interval * (burst - 1),
+            next: Mutex::new(now),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Instant> {
+        self.next.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Takes a token at `now` and returns the instant the request may be sent at, or
+    /// `None` without taking it if that is after the `deadline`
+    pub(crate) fn reserve(&self, now: Instant, deadline: Option<Instant>) -> Option<Instant> {
+        let mut next = self.lock();
+        let due = (*next).max(now);
+        let at = due
+            .checked_sub(self.tolerance)
+            .map_or(now, |at| at.max(now));
+        if deadline.is_some_and(|deadline| at > deadline) {
+            return None;
+        }
+        *next = due + self.interval;
+        Some(at)
+    }
+
+    /// Takes a token without waiting, or returns `false` if none is available at `now`
+    pub(crate) fn try_acquire(&self, now: Instant) -> bool {
+        self.reserve(now, Some(now)).is_some()
+    }
+
+    /// Blocks the current thread until a token taken at `now` is available, or
+    /// returns `false` right away if it would not be before the `deadline`
+    pub(crate) fn blocking_acquire(&self, now: Instant, deadline: Option<Instant>) -> bool {
+        match self.reserve(now, deadline) {
+            Some(at) => {
+                thread::sleep(at.saturating_duration_since(now));
+                true
+            }
+            None => false,
+        }
+    }
+}
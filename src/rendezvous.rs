@@ -0,0 +1,248 @@
+use crate::bounded::{new_payload, Payload, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::state::ChannelState;
+
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+/// Send requests to the associated [`RendezvousReceiver`], one hand-off at a time
+///
+/// Instances are created by the [`rendezvous_channel()`] function.
+pub struct RendezvousSender<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+/// Receive requests from the associated [`RendezvousSender`]s
+///
+/// Instances are created by the [`rendezvous_channel()`] function.
+pub struct RendezvousReceiver<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+struct Shared<Req, Res> {
+    slot: Mutex<Slot<Req, Res>>,
+    /// Notified when a payload is put into the slot, or when the last sender is dropped
+    filled: Notify,
+    /// Notified when the slot is emptied, or when the receiver is dropped
+    emptied: Notify,
+    channel: Arc<ChannelState>,
+}
+
+struct Slot<Req, Res> {
+    payload: Option<Payload<Req, Res>>,
+    /// The number of the last payload put into the slot
+    offered: u64,
+    /// The number of the last payload taken by the receiver
+    taken: u64,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+/// Withdraws an offered payload from the slot if the send is cancelled before
+/// the receiver takes it
+struct Offer<'a, Req, Res> {
+    shared: &'a Shared<Req, Res>,
+    number: u64,
+}
+
+impl<Req, Res> Shared<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Slot<Req, Res>> {
+        self.slot
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Takes the payload numbered `number` back out of the slot, if it is still there
+    fn withdraw(&self, number: u64) -> Option<Payload<Req, Res>> {
+        let mut slot = self.lock();
+        if slot.offered != number || slot.taken >= number {
+            return None;
+        }
+        let payload = slot.payload.take();
+        drop(slot);
+        self.emptied.notify_waiters();
+        payload
+    }
+}
+
+impl<Req, Res> Drop for Offer<'_, Req, Res> {
+    fn drop(&mut self) {
+        self.shared.withdraw(self.number);
+    }
+}
+
+impl<Req, Res> RendezvousSender<Req, Res> {
+    /// Send a request, waiting until the receiver takes it out of the channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response.
+    /// The request is withdrawn if this future is dropped before the hand-off.
+    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        let (payload, receiver) = new_payload(request, &self.shared.channel);
+        let mut payload = Some(payload);
+        let offer = loop {
+            let emptied = self.shared.emptied.notified();
+            tokio::pin!(emptied);
+            emptied.as_mut().enable();
+            {
+                let mut slot = self.shared.lock();
+                if !slot.receiver_alive {
+                    let payload = payload.take().expect("the payload is offered once");
+                    return Err(SendError(payload.0));
+                }
+                if slot.payload.is_none() {
+                    slot.offered += 1;
+                    slot.payload = payload.take();
+                    break Offer {
+                        shared: &self.shared,
+                        number: slot.offered,
+                    };
+                }
+            }
+            emptied.await;
+        };
+        self.shared.filled.notify_waiters();
+        loop {
+            let emptied = self.shared.emptied.notified();
+            tokio::pin!(emptied);
+            emptied.as_mut().enable();
+            {
+                let slot = self.shared.lock();
+                if slot.taken >= offer.number {
+                    return Ok(receiver);
+                }
+                if !slot.receiver_alive {
+                    drop(slot);
+                    let payload = self
+                        .shared
+                        .withdraw(offer.number)
+                        .expect("only the receiver takes an offered payload");
+                    return Err(SendError(payload.0));
+                }
+            }
+            emptied.await;
+        }
+    }
+
+    /// Send a request, wait for the receiver to take it and for the response, and return it
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request).await?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Checks if the receiver has been dropped
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<Req, Res> RendezvousReceiver<Req, Res> {
+    /// Receives the next request, waiting until a sender offers one
+    ///
+    /// The sender of the request completes its send when this returns.
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            let filled = self.shared.filled.notified();
+            tokio::pin!(filled);
+            filled.as_mut().enable();
+            {
+                let mut slot = self.shared.lock();
+                if let Some(payload) = slot.payload.take() {
+                    slot.taken = slot.offered;
+                    drop(slot);
+                    self.shared.emptied.notify_waiters();
+                    return Ok(payload);
+                }
+                if slot.senders == 0 {
+                    return Err(RequestError::recv_error());
+                }
+            }
+            filled.await;
+        }
+    }
+}
+
+impl<Req, Res> Clone for RendezvousSender<Req, Res> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        RendezvousSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Req, Res> Drop for RendezvousSender<Req, Res> {
+    fn drop(&mut self) {
+        let mut slot = self.shared.lock();
+        slot.senders -= 1;
+        if slot.senders == 0 {
+            drop(slot);
+            self.shared.filled.notify_waiters();
+        }
+    }
+}
+
+impl<Req, Res> Drop for RendezvousReceiver<Req, Res> {
+    fn drop(&mut self) {
+        self.shared.lock().receiver_alive = false;
+        self.shared.emptied.notify_waiters();
+    }
+}
+
+impl<Req, Res> fmt::Debug for RendezvousSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RendezvousSender")
+            .field("channel", &self.shared.channel)
+            .finish()
+    }
+}
+
+impl<Req, Res> fmt::Debug for RendezvousReceiver<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RendezvousReceiver")
+            .field("channel", &self.shared.channel)
+            .finish()
+    }
+}
+
+/// Creates a request-response channel without a buffer, where a send only completes
+/// once the receiver takes the request
+///
+/// Unlike [`bmrng::channel()`](crate::channel()), which panics with a capacity of 0,
+/// this hands every request directly from a sender to the receiver.
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::rendezvous_channel::<i32, i32>();
+///     tokio::spawn(async move {
+///         while let Ok((input, responder)) = rx.recv().await {
+///             let _ = responder.respond(input * input);
+///         }
+///     });
+///     assert_eq!(tx.send_receive(4).await, Ok(16));
+/// }
+/// ```
+pub fn rendezvous_channel<Req, Res>() -> (RendezvousSender<Req, Res>, RendezvousReceiver<Req, Res>)
+{
+    let shared = Arc::new(Shared {
+        slot: Mutex::new(Slot {
+            payload: None,
+            offered: 0,
+            taken: 0,
+            senders: 1,
+            receiver_alive: true,
+        }),
+        filled: Notify::new(),
+        emptied: Notify::new(),
+        channel: Arc::new(ChannelState::new(None)),
+    });
+    (
+        RendezvousSender {
+            shared: shared.clone(),
+        },
+        RendezvousReceiver { shared },
+    )
+}
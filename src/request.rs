@@ -0,0 +1,29 @@
+use tokio::time::Duration;
+
+/// A request type that declares the type of its response
+///
+/// Channels created with [`typed_channel()`](crate::typed_channel()) or
+/// [`unbounded::typed_channel()`](crate::unbounded::typed_channel()) use the
+/// associated [`TIMEOUT`](Request::TIMEOUT) as their response timeout.
+///
+/// # Examples
+///
+/// ```rust
+/// use tokio::time::Duration;
+///
+/// struct GenerateReport;
+///
+/// impl bmrng::Request for GenerateReport {
+///     type Response = String;
+///     const TIMEOUT: Option<Duration> = Some(Duration::from_secs(30));
+/// }
+///
+/// let (tx, rx) = bmrng::typed_channel::<GenerateReport>(16);
+/// ```
+pub trait Request {
+    /// The type of the response sent back for this request
+    type Response;
+
+    /// The default response timeout for this request type, `None` to wait indefinitely
+    const TIMEOUT: Option<Duration> = None;
+}
@@ -0,0 +1,173 @@
+use crate::error::RequestError;
+
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::hash::{BuildHasher, Hasher};
+use tokio::time::{sleep, Duration};
+
+/// How long to wait between two attempts of a [`RetryPolicy`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Backoff {
+    /// Wait the same duration before every retry
+    Fixed(Duration),
+    /// Wait `initial` before the first retry, then double the wait up to `max`
+    Exponential {
+        /// The wait before the first retry
+        initial: Duration,
+        /// The longest wait between two attempts
+        max: Duration,
+    },
+}
+
+/// The retry policy of [`RequestSender::send_receive_with_retry()`](crate::RequestSender::send_receive_with_retry())
+/// and [`RequestReceiver::serve_with_retry()`](crate::RequestReceiver::serve_with_retry())
+///
+/// A request is sent again when waiting for its response fails with
+/// [`RequestError::RecvTimeoutError`] or [`RequestError::RecvError`]. Other errors,
+/// like a closed channel, are returned right away.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::{Backoff, RetryPolicy};
+/// use tokio::time::Duration;
+///
+/// let policy = RetryPolicy::new(5)
+///     .backoff(Backoff::Exponential {
+///         initial: Duration::from_millis(10),
+///         max: Duration::from_secs(1),
+///     })
+///     .jitter(true);
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: usize,
+    backoff: Backoff,
+    jitter: bool,
+}
+
+impl RetryPolicy {
+    /// Creates a policy sending a request at most `max_attempts` times, without
+    /// waiting between the attempts
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_attempts` is 0
+    pub fn new(max_attempts: usize) -> Self {
+        assert!(max_attempts > 0, "a retry policy requires max_attempts > 0");
+        RetryPolicy {
+            max_attempts,
+            backoff: Backoff::Fixed(Duration::ZERO),
+            jitter: false,
+        }
+    }
+
+    /// Sets how long to wait between two attempts
+    pub fn backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Randomizes every wait between half and all of its backoff duration, so
+    /// senders failing together do not retry together
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Returns how long to wait after the failed attempt number `attempt`, starting at 1
+    fn delay(&self, attempt: usize) -> Duration {
+        let delay = match self.backoff {
+            Backoff::Fixed(delay) => delay,
+            Backoff::Exponential { initial, max } => {
+                let exponent = u32::try_from(attempt - 1).unwrap_or(u32::MAX);
+                2u32.checked_pow(exponent)
+                    .and_then(|factor| initial.checked_mul(factor))
+                    .map_or(max, |delay| delay.min(max))
+            }
+        };
+        if self.jitter {
+            let half = delay / 2;
+            let random = RandomState::new().build_hasher().finish();
+            half + half.mul_f64(random as f64 / u64::MAX as f64)
+        } else {
+            delay
+        }
+    }
+}
+
+fn is_retryable<T>(err: &RequestError<T>) -> bool {
+    matches!(
+        err,
+        RequestError::RecvError(..) | RequestError::RecvTimeoutError(..)
+    )
+}
+
+/// Sends clones of `request` with `send_receive` until it succeeds, fails with an
+/// error that is not worth retrying, or runs out of attempts
+pub(crate) async fn retry<Req, Res, F, Fut>(
+    request: Req,
+    policy: &RetryPolicy,
+    send_receive: F,
+) -> Result<Res, RequestError<Req>>
+where
+    Req: Clone,
+    F: FnMut(Req) -> Fut,
+    Fut: Future<Output = Result<Res, RequestError<Req>>>,
+{
+    retry_if(request, policy, is_retryable, send_receive).await
+}
+
+/// Calls `handler` with clones of `request` until it succeeds, fails with an error
+/// `should_retry` rejects, or runs out of attempts
+pub(crate) async fn retry_if<Req, Res, E, P, F, Fut>(
+    request: Req,
+    policy: &RetryPolicy,
+    should_retry: P,
+    mut handler: F,
+) -> Result<Res, E>
+where
+    Req: Clone,
+    P: Fn(&E) -> bool,
+    F: FnMut(Req) -> Fut,
+    Fut: Future<Output = Result<Res, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match handler(request.clone()).await {
+            Err(err) if should_retry(&err) && attempt < policy.max_attempts => {
+                sleep(policy.delay(attempt)).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exponential_delay() {
+        let policy = RetryPolicy::new(10).backoff(Backoff::Exponential {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(50),
+        });
+        assert_eq!(policy.delay(1), Duration::from_millis(10));
+        assert_eq!(policy.delay(2), Duration::from_millis(20));
+        assert_eq!(policy.delay(3), Duration::from_millis(40));
+        assert_eq!(policy.delay(4), Duration::from_millis(50));
+        assert_eq!(policy.delay(100), Duration::from_millis(50));
+    }
+
+    #[test]
+    fn jitter_delay() {
+        let policy = RetryPolicy::new(2)
+            .backoff(Backoff::Fixed(Duration::from_millis(100)))
+            .jitter(true);
+        let delay = policy.delay(1);
+        assert!(delay >= Duration::from_millis(50) && delay <= Duration::from_millis(100));
+    }
+}
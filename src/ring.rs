@@ -0,0 +1,292 @@
+use crate::bounded::{new_payload, Payload, ResponseReceiver};
+use crate::error::{ReceiveError, RequestError, TrySendError};
+use crate::state::ChannelState;
+
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::Notify;
+
+/// What a [`RingRequestSender`] does with a request sent while the channel is full
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Overflow {
+    /// Wait for capacity, like the bounded channel
+    Block,
+    /// Reject the request that is being sent
+    DropNewest,
+    /// Evict the oldest queued request to make room, failing its sender with
+    /// [`RequestError::Evicted`]
+    DropOldest,
+}
+
+/// Send values to the associated [`RingRequestReceiver`]
+///
+/// Instances are created by the [`channel()`] function.
+pub struct RingRequestSender<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+/// Receive requests from the associated [`RingRequestSender`]s
+///
+/// Instances are created by the [`channel()`] function.
+pub struct RingRequestReceiver<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+struct Shared<Req, Res> {
+    queue: Mutex<Queue<Req, Res>>,
+    capacity: usize,
+    overflow: Overflow,
+    /// Notified when a payload is queued, or when the last sender is dropped
+    not_empty: Notify,
+    /// Notified when a payload is received, or when the receiver is dropped
+    not_full: Notify,
+    /// The error reported to the senders of evicted requests
+    evicted_as: ReceiveError,
+    channel: Arc<ChannelState>,
+}
+
+struct Queue<Req, Res> {
+    payloads: VecDeque<Payload<Req, Res>>,
+    senders: usize,
+    receiver_alive: bool,
+}
+
+impl<Req, Res> Shared<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Queue<Req, Res>> {
+        self.queue
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<Req, Res> RingRequestSender<Req, Res> {
+    /// Send a request over the channel, open the response channel
+    ///
+    /// Return the [`ResponseReceiver`] which can be used to wait for a response.
+    /// When the channel is full, the [`Overflow`] policy decides whether this call
+    /// waits, fails with [`TrySendError::Full`] or evicts the oldest queued request.
+    pub async fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, TrySendError<Req>> {
+        let (mut payload, receiver) = new_payload(request, &self.shared.channel);
+        loop {
+            let not_full = self.shared.not_full.notified();
+            tokio::pin!(not_full);
+            not_full.as_mut().enable();
+            payload = match self.push(payload) {
+                Ok(()) => return Ok(receiver),
+                Err(TrySendError::Full(payload)) if self.shared.overflow == Overflow::Block => {
+                    payload
+                }
+                Err(err) => return Err(map_payload_error(err)),
+            };
+            not_full.await;
+        }
+    }
+
+    /// Attempts to immediately send a request over the channel, open the response channel
+    ///
+    /// Unlike [`send()`](Self::send()), this fails with [`TrySendError::Full`] instead of
+    /// waiting when the policy is [`Overflow::Block`].
+    pub fn try_send(&self, request: Req) -> Result<ResponseReceiver<Res>, TrySendError<Req>> {
+        let (payload, receiver) = new_payload(request, &self.shared.channel);
+        self.push(payload).map_err(map_payload_error)?;
+        Ok(receiver)
+    }
+
+    /// Queues the payload, applying the overflow policy if the queue is full
+    fn push(&self, payload: Payload<Req, Res>) -> Result<(), TrySendError<Payload<Req, Res>>> {
+        let mut queue = self.shared.lock();
+        if !queue.receiver_alive {
+            return Err(TrySendError::Closed(payload));
+        }
+        let evicted = if queue.payloads.len() < self.shared.capacity {
+            None
+        } else if self.shared.overflow == Overflow::DropOldest {
+            queue.payloads.pop_front()
+        } else {
+            return Err(TrySendError::Full(payload));
+        };
+        queue.payloads.push_back(payload);
+        drop(queue);
+        if let Some((_, responder)) = evicted {
+            responder.drop_with(self.shared.evicted_as);
+        }
+        self.shared.not_empty.notify_waiters();
+        Ok(())
+    }
+
+    /// Send a request over the channel, wait for the response and return it
+    ///
+    /// It fails with [`RequestError::Evicted`] if the request is dropped by the
+    /// [`Overflow`] policy, either when it is sent or while it is queued.
+    pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request).await.map_err(|err| match err {
+            TrySendError::Full(..) => RequestError::Evicted,
+            TrySendError::Closed(request) => RequestError::SendError(request),
+            TrySendError::Rejected(request) => RequestError::Rejected(request),
+        })?;
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Checks if the channel has been closed
+    pub fn is_closed(&self) -> bool {
+        !self.shared.lock().receiver_alive
+    }
+}
+
+impl<Req, Res> RingRequestReceiver<Req, Res> {
+    /// Receives the next request, waiting until one is available
+    pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            let not_empty = self.shared.not_empty.notified();
+            tokio::pin!(not_empty);
+            not_empty.as_mut().enable();
+            {
+                let mut queue = self.shared.lock();
+                if let Some(payload) = queue.payloads.pop_front() {
+                    drop(queue);
+                    self.shared.not_full.notify_waiters();
+                    return Ok(payload);
+                }
+                if queue.senders == 0 {
+                    return Err(RequestError::recv_error());
+                }
+            }
+            not_empty.await;
+        }
+    }
+
+    /// Returns the number of requests waiting to be received
+    pub fn len(&self) -> usize {
+        self.shared.lock().payloads.len()
+    }
+
+    /// Returns `true` if there are no requests waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the capacity of the channel
+    pub fn capacity(&self) -> usize {
+        self.shared.capacity
+    }
+}
+
+fn map_payload_error<Req, Res>(err: TrySendError<Payload<Req, Res>>) -> TrySendError<Req> {
+    match err {
+        TrySendError::Full(payload) => TrySendError::Full(payload.0),
+        TrySendError::Closed(payload) => TrySendError::Closed(payload.0),
+        TrySendError::Rejected(payload) => TrySendError::Rejected(payload.0),
+    }
+}
+
+impl<Req, Res> Clone for RingRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+        RingRequestSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Req, Res> Drop for RingRequestSender<Req, Res> {
+    fn drop(&mut self) {
+        let mut queue = self.shared.lock();
+        queue.senders -= 1;
+        if queue.senders == 0 {
+            drop(queue);
+            self.shared.not_empty.notify_waiters();
+        }
+    }
+}
+
+impl<Req, Res> Drop for RingRequestReceiver<Req, Res> {
+    fn drop(&mut self) {
+        let payloads = {
+            let mut queue = self.shared.lock();
+            queue.receiver_alive = false;
+            std::mem::take(&mut queue.payloads)
+        };
+        drop(payloads);
+        self.shared.not_full.notify_waiters();
+    }
+}
+
+impl<Req, Res> fmt::Debug for RingRequestSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RingRequestSender")
+            .field("capacity", &self.shared.capacity)
+            .field("overflow", &self.shared.overflow)
+            .field("channel", &self.shared.channel)
+            .finish()
+    }
+}
+
+impl<Req, Res> fmt::Debug for RingRequestReceiver<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RingRequestReceiver")
+            .field("capacity", &self.shared.capacity)
+            .field("overflow", &self.shared.overflow)
+            .finish()
+    }
+}
+
+/// Creates a bounded request-response channel whose behavior when full is set
+/// by the `overflow` policy
+///
+/// # Panics
+///
+/// Panics if the capacity is 0
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::error::ReceiveError;
+/// use bmrng::ring::Overflow;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::ring::channel::<i32, i32>(1, Overflow::DropOldest);
+///     let stale = tx.send(1).await.unwrap();
+///     let fresh = tx.send(2).await.unwrap();
+///     assert_eq!(stale.await, Err(ReceiveError::Evicted));
+///     let (input, responder) = rx.recv().await.unwrap();
+///     assert_eq!(input, 2);
+///     let _ = responder.respond(input * 2);
+///     assert_eq!(fresh.await, Ok(4));
+/// }
+/// ```
+pub fn channel<Req, Res>(
+    capacity: usize,
+    overflow: Overflow,
+) -> (RingRequestSender<Req, Res>, RingRequestReceiver<Req, Res>) {
+    channel_evicting(capacity, overflow, ReceiveError::Evicted)
+}
+
+/// Creates a ring channel whose evicted requests fail with `evicted_as`
+pub(crate) fn channel_evicting<Req, Res>(
+    capacity: usize,
+    overflow: Overflow,
+    evicted_as: ReceiveError,
+) -> (RingRequestSender<Req, Res>, RingRequestReceiver<Req, Res>) {
+    assert!(capacity > 0, "ring channel requires capacity > 0");
+    let shared = Arc::new(Shared {
+        queue: Mutex::new(Queue {
+            payloads: VecDeque::with_capacity(capacity),
+            senders: 1,
+            receiver_alive: true,
+        }),
+        capacity,
+        overflow,
+        not_empty: Notify::new(),
+        not_full: Notify::new(),
+        evicted_as,
+        channel: Arc::new(ChannelState::new(None)),
+    });
+    (
+        RingRequestSender {
+            shared: shared.clone(),
+        },
+        RingRequestReceiver { shared },
+    )
+}
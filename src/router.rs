@@ -0,0 +1,127 @@
+use crate::bounded::{channel, RequestSender};
+use crate::error::RequestError;
+use crate::Request;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+type Routes = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+/// Registers one handler task per [`Request`] type, then builds the [`RouterSender`]
+/// that dispatches every request to the handler of its type
+///
+/// Every handler answers the requests of its own bounded channel, so a slow
+/// handler only applies backpressure to the requests of its type.
+pub struct Router {
+    buffer: usize,
+    routes: Routes,
+}
+
+/// Send requests of any registered [`Request`] type to their handler
+///
+/// Instances are created by calling [`Router::build()`]. The handler tasks stop
+/// once every clone of the sender is dropped.
+#[derive(Clone)]
+pub struct RouterSender {
+    routes: Arc<Routes>,
+}
+
+impl Router {
+    /// Creates a router whose handler channels have the given buffer capacity
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+    pub fn new(buffer: usize) -> Self {
+        assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+        Router {
+            buffer,
+            routes: HashMap::new(),
+        }
+    }
+
+    /// Spawns a task answering the requests of type `R` with the async `handler`
+    ///
+    /// Registering a second handler for the same type replaces the first one,
+    /// whose task stops once its channel is drained.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime
+    pub fn register<R, F, Fut>(&mut self, handler: F) -> &mut Self
+    where
+        R: Request + Send + 'static,
+        R::Response: Send + 'static,
+        F: FnMut(R) -> Fut + Send + 'static,
+        Fut: Future<Output = R::Response> + Send + 'static,
+    {
+        let (sender, receiver) = channel::<R, R::Response>(self.buffer);
+        tokio::spawn(receiver.serve(handler));
+        self.routes.insert(TypeId::of::<R>(), Box::new(sender));
+        self
+    }
+
+    /// Builds the sender dispatching the requests to the registered handlers
+    pub fn build(self) -> RouterSender {
+        RouterSender {
+            routes: Arc::new(self.routes),
+        }
+    }
+}
+
+impl RouterSender {
+    /// Sends a request to the handler of its type, waits for the response and returns it
+    ///
+    /// The response timeout is taken from [`Request::TIMEOUT`]. If no handler is
+    /// registered for `R`, the request is handed back in [`RequestError::SendError`].
+    pub async fn send_receive<R>(&self, request: R) -> Result<R::Response, RequestError<R>>
+    where
+        R: Request + Send + 'static,
+        R::Response: Send + 'static,
+    {
+        let sender = match self.route::<R>() {
+            Some(sender) => sender,
+            None => return Err(RequestError::SendError(request)),
+        };
+        let mut receiver = sender.send(request).await?;
+        if R::TIMEOUT.is_some() {
+            receiver.set_timeout(R::TIMEOUT);
+        }
+        Ok(receiver.recv().await?)
+    }
+
+    /// Returns `true` if a handler is registered for the request type `R`
+    pub fn handles<R: Request + 'static>(&self) -> bool {
+        self.routes.contains_key(&TypeId::of::<R>())
+    }
+
+    fn route<R>(&self) -> Option<&RequestSender<R, R::Response>>
+    where
+        R: Request + 'static,
+        R::Response: 'static,
+    {
+        self.routes
+            .get(&TypeId::of::<R>())
+            .and_then(|sender| sender.downcast_ref())
+    }
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Router")
+            .field("buffer", &self.buffer)
+            .field("routes", &self.routes.len())
+            .finish()
+    }
+}
+
+impl fmt::Debug for RouterSender {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RouterSender")
+            .field("routes", &self.routes.len())
+            .finish()
+    }
+}
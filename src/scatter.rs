@@ -0,0 +1,184 @@
+use crate::bounded::{channel, RequestReceiver, RequestSender};
+use crate::error::RequestError;
+
+use futures_util::stream::{FuturesUnordered, Stream, StreamExt};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Identifies a receiver registered with a [`ScatterSender`]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReceiverId(u64);
+
+impl fmt::Display for ReceiverId {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "receiver #{}", self.0)
+    }
+}
+
+/// Send every request to all the registered receivers and gather their responses
+///
+/// Every receiver gets its own bounded channel, so each one answers its copy of
+/// the request with its own responder. A receiver is unregistered once it is
+/// dropped. Clones of the sender share the same set of receivers.
+pub struct ScatterSender<Req, Res> {
+    shared: Arc<Shared<Req, Res>>,
+}
+
+struct Shared<Req, Res> {
+    buffer: usize,
+    registry: Mutex<Registry<Req, Res>>,
+}
+
+struct Registry<Req, Res> {
+    senders: Vec<(ReceiverId, RequestSender<Req, Res>)>,
+    next_id: u64,
+}
+
+impl<Req, Res> Shared<Req, Res> {
+    fn lock(&self) -> MutexGuard<'_, Registry<Req, Res>> {
+        self.registry
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<Req, Res> ScatterSender<Req, Res> {
+    /// Creates a sender without receivers, whose receiver channels have the given
+    /// buffer capacity
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+    pub fn new(buffer: usize) -> Self {
+        assert!(buffer > 0, "mpsc bounded channel requires buffer > 0");
+        ScatterSender {
+            shared: Arc::new(Shared {
+                buffer,
+                registry: Mutex::new(Registry {
+                    senders: Vec::new(),
+                    next_id: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Registers a new receiver, which gets a copy of every request sent from now on
+    pub fn register(&self) -> (ReceiverId, RequestReceiver<Req, Res>) {
+        let (sender, receiver) = channel(self.shared.buffer);
+        let mut registry = self.shared.lock();
+        let id = ReceiverId(registry.next_id);
+        registry.next_id += 1;
+        registry.senders.push((id, sender));
+        (id, receiver)
+    }
+
+    /// Returns the number of registered receivers that have not been dropped yet
+    pub fn receiver_count(&self) -> usize {
+        let mut registry = self.shared.lock();
+        registry.senders.retain(|(_, sender)| !sender.is_closed());
+        registry.senders.len()
+    }
+
+    /// Sends a clone of the request to every registered receiver and returns a
+    /// stream of their responses as they arrive
+    ///
+    /// Every response is paired with the id of the receiver that sent it. Dropping
+    /// the stream abandons the requests that were not answered yet.
+    pub fn scatter(
+        &self,
+        request: Req,
+    ) -> impl Stream<Item = (ReceiverId, Result<Res, RequestError<Req>>)>
+    where
+        Req: Clone,
+    {
+        self.dispatch(request)
+    }
+
+    /// Starts a request to every registered receiver
+    fn dispatch(
+        &self,
+        request: Req,
+    ) -> FuturesUnordered<impl Future<Output = (ReceiverId, Result<Res, RequestError<Req>>)>>
+    where
+        Req: Clone,
+    {
+        let senders = {
+            let mut registry = self.shared.lock();
+            registry.senders.retain(|(_, sender)| !sender.is_closed());
+            registry.senders.clone()
+        };
+        senders
+            .into_iter()
+            .map(|(id, sender)| {
+                let request = request.clone();
+                async move { (id, sender.send_receive(request).await) }
+            })
+            .collect::<FuturesUnordered<_>>()
+    }
+
+    /// Sends a clone of the request to every registered receiver, waits for all of
+    /// them and returns their results in registration order
+    pub async fn send_receive_all(
+        &self,
+        request: Req,
+    ) -> Vec<(ReceiverId, Result<Res, RequestError<Req>>)>
+    where
+        Req: Clone,
+    {
+        let mut results: Vec<_> = self.scatter(request).collect().await;
+        results.sort_by_key(|(id, _)| *id);
+        results
+    }
+
+    /// Sends a clone of the request to every registered receiver and returns as
+    /// soon as `quorum` of them responded successfully
+    ///
+    /// The responses are returned in the order they arrived, and the remaining
+    /// requests are abandoned. If the quorum cannot be reached anymore, the last
+    /// error is returned, or [`RequestError::SendError`] if there were not enough
+    /// receivers to begin with.
+    pub async fn send_receive_quorum(
+        &self,
+        request: Req,
+        quorum: usize,
+    ) -> Result<Vec<(ReceiverId, Res)>, RequestError<Req>>
+    where
+        Req: Clone,
+    {
+        let mut pending = self.dispatch(request.clone());
+        let mut responses = Vec::with_capacity(quorum);
+        let mut last_error = None;
+        while responses.len() < quorum && responses.len() + pending.len() >= quorum {
+            match pending.next().await {
+                Some((id, Ok(response))) => responses.push((id, response)),
+                Some((_, Err(err))) => last_error = Some(err),
+                None => break,
+            }
+        }
+        if responses.len() >= quorum {
+            Ok(responses)
+        } else {
+            Err(last_error.unwrap_or(RequestError::SendError(request)))
+        }
+    }
+}
+
+impl<Req, Res> Clone for ScatterSender<Req, Res> {
+    fn clone(&self) -> Self {
+        ScatterSender {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<Req, Res> fmt::Debug for ScatterSender<Req, Res> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let registry = self.shared.lock();
+        let ids: Vec<_> = registry.senders.iter().map(|(id, _)| *id).collect();
+        fmt.debug_struct("ScatterSender")
+            .field("buffer", &self.shared.buffer)
+            .field("receivers", &ids)
+            .finish()
+    }
+}
@@ -0,0 +1,47 @@
+use crate::bounded::{channel, RequestSender};
+
+use futures_util::future::join;
+use std::future::Future;
+
+/// Runs `body` with a sender whose requests are answered by `handler` within the same future
+///
+/// Nothing is spawned: the handler loop and `body` are polled together by the
+/// returned future, so requests and responses may borrow data that outlives the
+/// scope, without having to be `'static`. The scope completes once `body` has
+/// finished and every [`RequestSender`] clone has been dropped, at which point
+/// all requests have been answered or cancelled.
+///
+/// The requests are handled one at a time, and the channel is created with the
+/// given buffer capacity.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+///
+/// # Examples
+///
+/// ```rust
+/// #[tokio::main]
+/// async fn main() {
+///     let document = String::from("a large document that should not be cloned");
+///     let text = document.as_str();
+///     let words = bmrng::scope(
+///         4,
+///         |text: &str| async move { text.split_whitespace().count() },
+///         |tx| async move { tx.send_receive(&text[2..]).await },
+///     )
+///     .await;
+///     assert_eq!(words, Ok(7));
+/// }
+/// ```
+pub async fn scope<Req, Res, H, HFut, F, Fut>(buffer: usize, handler: H, body: F) -> Fut::Output
+where
+    H: FnMut(Req) -> HFut,
+    HFut: Future<Output = Res>,
+    F: FnOnce(RequestSender<Req, Res>) -> Fut,
+    Fut: Future,
+{
+    let (sender, receiver) = channel::<Req, Res>(buffer);
+    let (output, _) = join(body(sender), receiver.serve(handler)).await;
+    output
+}
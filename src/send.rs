@@ -0,0 +1,72 @@
+use crate::bounded::{RequestSender, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::unbounded::UnboundedRequestSender;
+use crate::Request;
+
+use futures_util::future::{BoxFuture, FutureExt};
+
+/// A sender of [`Request`]s, implemented by both [`RequestSender`] and
+/// [`UnboundedRequestSender`]
+///
+/// Use it as a trait object, like [`BoxRequestSender`], to accept either kind of
+/// sender without a generic parameter for the channel type.
+pub trait RequestSend<R: Request> {
+    /// Send a request over the channel, return the response receiver
+    ///
+    /// With a bounded channel, the returned future waits for capacity.
+    fn send(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ResponseReceiver<R::Response>, SendError<R>>>;
+
+    /// Send a request over the channel, wait for the response and return it
+    fn send_receive(&self, request: R) -> BoxFuture<'_, Result<R::Response, RequestError<R>>>;
+
+    /// Checks if the channel has been closed
+    fn is_closed(&self) -> bool;
+}
+
+/// A boxed sender of either a bounded or an unbounded channel
+pub type BoxRequestSender<R> = Box<dyn RequestSend<R> + Send + Sync>;
+
+impl<R> RequestSend<R> for RequestSender<R, R::Response>
+where
+    R: Request + Send,
+    R::Response: Send,
+{
+    fn send(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ResponseReceiver<R::Response>, SendError<R>>> {
+        RequestSender::send(self, request).boxed()
+    }
+
+    fn send_receive(&self, request: R) -> BoxFuture<'_, Result<R::Response, RequestError<R>>> {
+        RequestSender::send_receive(self, request).boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        RequestSender::is_closed(self)
+    }
+}
+
+impl<R> RequestSend<R> for UnboundedRequestSender<R, R::Response>
+where
+    R: Request + Send,
+    R::Response: Send,
+{
+    fn send(
+        &self,
+        request: R,
+    ) -> BoxFuture<'_, Result<ResponseReceiver<R::Response>, SendError<R>>> {
+        futures_util::future::ready(UnboundedRequestSender::send(self, request)).boxed()
+    }
+
+    fn send_receive(&self, request: R) -> BoxFuture<'_, Result<R::Response, RequestError<R>>> {
+        UnboundedRequestSender::send_receive(self, request).boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        UnboundedRequestSender::is_closed(self)
+    }
+}
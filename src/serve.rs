@@ -0,0 +1,74 @@
+use tokio::time::{Duration, Instant};
+
+/// Summary of the requests handled by a serve loop, returned once the loop terminates
+///
+/// Every received request is counted in exactly one of the other counters.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ServeReport {
+    /// The number of requests taken from the channel and passed to the handler
+    pub received: usize,
+    /// The number of responses delivered to the requesting side
+    pub responded: usize,
+    /// The number of responses that could not be delivered because the requesting
+    /// side had cancelled the request or dropped its receiver
+    pub unanswered: usize,
+    /// The number of responses that could not be delivered because the response
+    /// timeout of the request had elapsed
+    pub timed_out: usize,
+    /// The number of requests whose handler panicked
+    pub panicked: usize,
+    /// The number of error responses delivered by
+    /// [`serve_with_retry()`](crate::RequestReceiver::serve_with_retry()) once the
+    /// handler ran out of attempts or failed with an error not worth retrying
+    pub failed: usize,
+    /// The number of requests answered with [`RequestError::ShutDown`](crate::error::RequestError::ShutDown)
+    /// because the serve loop shut down before they were handled
+    pub shut_down: usize,
+    /// The time the serve loop was running for
+    pub elapsed: Duration,
+}
+
+/// How a request passed to the handler of a serve loop ended
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum Outcome {
+    Responded,
+    Unanswered,
+    TimedOut,
+    Panicked,
+    Failed,
+    ShutDown,
+}
+
+/// Collects the counters of a [`ServeReport`] while a serve loop is running
+#[derive(Debug)]
+pub(crate) struct ServeReporter {
+    report: ServeReport,
+    started: Instant,
+}
+
+impl ServeReporter {
+    pub(crate) fn start() -> Self {
+        ServeReporter {
+            report: ServeReport::default(),
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn record(&mut self, outcome: Outcome) {
+        self.report.received += 1;
+        match outcome {
+            Outcome::Responded => self.report.responded += 1,
+            Outcome::Unanswered => self.report.unanswered += 1,
+            Outcome::TimedOut => self.report.timed_out += 1,
+            Outcome::Panicked => self.report.panicked += 1,
+            Outcome::Failed => self.report.failed += 1,
+            Outcome::ShutDown => self.report.shut_down += 1,
+        }
+    }
+
+    pub(crate) fn finish(mut self) -> ServeReport {
+        self.report.elapsed = self.started.elapsed();
+        self.report
+    }
+}
@@ -0,0 +1,133 @@
+use crate::bounded::{typed_channel, RequestReceiver, RequestSender, ResponseReceiver};
+use crate::error::{RequestError, SendError};
+use crate::Request;
+
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+
+/// The receiver of a single shard
+pub type ShardReceiver<R> = RequestReceiver<R, <R as Request>::Response>;
+
+type KeyFn<R, K> = dyn Fn(&R) -> K + Send + Sync;
+
+/// Send requests to the shard their key is hashed to
+///
+/// Requests with the same key always go to the same shard, so they are received
+/// in the order they were sent, while requests with different keys can be
+/// handled in parallel.
+///
+/// Instances are created by the [`channel()`] function.
+pub struct ShardedRequestSender<R: Request, K> {
+    shards: Arc<[RequestSender<R, R::Response>]>,
+    key_fn: Arc<KeyFn<R, K>>,
+}
+
+impl<R: Request, K: Hash> ShardedRequestSender<R, K> {
+    /// Returns the index of the shard the request is routed to
+    pub fn shard_of(&self, request: &R) -> usize {
+        let mut hasher = DefaultHasher::new();
+        (self.key_fn)(request).hash(&mut hasher);
+        (hasher.finish() % self.shards.len() as u64) as usize
+    }
+
+    /// Send a request to its shard, open the response channel
+    ///
+    /// It fails with [`SendError`] if the receiver of the shard was dropped, even if
+    /// the other shards are still open.
+    pub async fn send(&self, request: R) -> Result<ResponseReceiver<R::Response>, SendError<R>> {
+        self.shards[self.shard_of(&request)].send(request).await
+    }
+
+    /// Send a request to its shard, wait for the response and return it
+    pub async fn send_receive(&self, request: R) -> Result<R::Response, RequestError<R>> {
+        self.shards[self.shard_of(&request)]
+            .send_receive(request)
+            .await
+    }
+}
+
+impl<R: Request, K> ShardedRequestSender<R, K> {
+    /// Returns the number of shards
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Checks if the receivers of all the shards have been dropped
+    pub fn is_closed(&self) -> bool {
+        self.shards.iter().all(RequestSender::is_closed)
+    }
+}
+
+impl<R: Request, K> Clone for ShardedRequestSender<R, K> {
+    fn clone(&self) -> Self {
+        ShardedRequestSender {
+            shards: self.shards.clone(),
+            key_fn: self.key_fn.clone(),
+        }
+    }
+}
+
+impl<R: Request, K> fmt::Debug for ShardedRequestSender<R, K> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("ShardedRequestSender")
+            .field("shards", &self.shards.len())
+            .finish()
+    }
+}
+
+/// Creates `shards` bounded request-response channels behind a single sender,
+/// which routes every request by hashing `key_fn(&request)`
+///
+/// The receivers are returned in shard order. Every shard has the given
+/// capacity, and the response timeout is taken from [`Request::TIMEOUT`].
+///
+/// # Panics
+///
+/// Panics if `shards` or the capacity is 0
+///
+/// # Examples
+///
+/// ```rust
+/// struct Deposit {
+///     account: u32,
+///     amount: u64,
+/// }
+///
+/// impl bmrng::Request for Deposit {
+///     type Response = ();
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, receivers) =
+///         bmrng::sharded::channel::<Deposit, u32>(4, 16, |deposit| deposit.account);
+///     for mut rx in receivers {
+///         tokio::spawn(async move {
+///             while let Ok((_deposit, responder)) = rx.recv().await {
+///                 let _ = responder.respond(());
+///             }
+///         });
+///     }
+///     let deposit = Deposit { account: 7, amount: 100 };
+///     assert!(tx.send_receive(deposit).await.is_ok());
+/// }
+/// ```
+pub fn channel<R, K>(
+    shards: usize,
+    capacity: usize,
+    key_fn: impl Fn(&R) -> K + Send + Sync + 'static,
+) -> (ShardedRequestSender<R, K>, Vec<ShardReceiver<R>>)
+where
+    R: Request,
+{
+    assert!(shards > 0, "sharded channel requires shards > 0");
+    let (senders, receivers): (Vec<_>, Vec<_>) =
+        (0..shards).map(|_| typed_channel::<R>(capacity)).unzip();
+    let sender = ShardedRequestSender {
+        shards: senders.into(),
+        key_fn: Arc::new(key_fn),
+    };
+    (sender, receivers)
+}
@@ -0,0 +1,90 @@
+use crate::bounded::{RequestSender, ResponseReceiver};
+use crate::error::SendError;
+use crate::poll::PollRequestSender;
+
+use futures_core::Stream;
+use futures_sink::Sink;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// A wrapper around [`RequestSender`] that implements [`Sink`]
+///
+/// The [`ResponseReceiver`] of every request sent into the sink is yielded by the
+/// companion [`ResponseReceiverStream`], in the order the requests were sent.
+/// Instances are created by calling [`RequestSender::into_sink()`].
+#[derive(Debug)]
+pub struct RequestSenderSink<Req, Res> {
+    sender: PollRequestSender<Req, Res>,
+    receivers: Option<mpsc::UnboundedSender<ResponseReceiver<Res>>>,
+}
+
+/// A [`Stream`] of the [`ResponseReceiver`]s of the requests sent into a [`RequestSenderSink`]
+///
+/// The stream ends once the sink is closed or dropped.
+#[derive(Debug)]
+pub struct ResponseReceiverStream<Res> {
+    receivers: mpsc::UnboundedReceiver<ResponseReceiver<Res>>,
+}
+
+impl<Req, Res> RequestSenderSink<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    pub(crate) fn new(sender: RequestSender<Req, Res>) -> (Self, ResponseReceiverStream<Res>) {
+        let (receivers_sender, receivers) = mpsc::unbounded_channel();
+        let sink = RequestSenderSink {
+            sender: PollRequestSender::new(sender),
+            receivers: Some(receivers_sender),
+        };
+        (sink, ResponseReceiverStream { receivers })
+    }
+}
+
+impl<Req, Res> Sink<Req> for RequestSenderSink<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    type Error = SendError<()>;
+
+    fn poll_ready(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sender.poll_reserve(cx).map_err(|_| SendError(()))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if [`Sink::poll_ready()`] did not return `Ready(Ok(()))` before
+    fn start_send(mut self: Pin<&mut Self>, request: Req) -> Result<(), Self::Error> {
+        let receiver = match self.sender.send_item(request) {
+            Ok(receiver) => receiver,
+            Err(..) => panic!("poll_ready must be called before start_send"),
+        };
+        if let Some(receivers) = &self.receivers {
+            let _ = receivers.send(receiver);
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(
+        mut self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Self::Error>> {
+        self.sender.close();
+        self.receivers = None;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<Res> Stream for ResponseReceiverStream<Res> {
+    type Item = ResponseReceiver<Res>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receivers.poll_recv(cx)
+    }
+}
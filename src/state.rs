@@ -0,0 +1,850 @@
+use crate::clock::{Clock, ClockSleep, TokioClock};
+use crate::error::ReceiveError;
+use crate::metrics::{ChannelMetrics, ChannelObserver};
+use crate::rate_limit::RateLimiter;
+
+use futures_util::future::{select, Either};
+use futures_util::task::AtomicWaker;
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+use std::fmt;
+use std::future::Future;
+use std::pin::pin;
+use std::sync::atomic::{AtomicBool, AtomicIsize, AtomicU64, AtomicU8, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, Weak};
+use std::task::{ready, Context, Poll, Waker};
+use tokio::sync::{watch, Notify, OwnedSemaphorePermit, Semaphore};
+use tokio::time::{Duration, Instant};
+#[cfg(feature = "tokio-util")]
+use tokio_util::sync::CancellationToken;
+
+/// The reason why the requesting side stopped waiting for a response
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CancelReason {
+    /// The request was cancelled with [`ResponseReceiver::cancel()`](crate::ResponseReceiver::cancel())
+    /// or [`RequestSender::cancel()`](crate::RequestSender::cancel())
+    Cancelled,
+    /// The response timeout elapsed before the response was sent
+    TimedOut,
+    /// The [`ResponseReceiver`](crate::ResponseReceiver) was dropped before the response was sent
+    Dropped,
+}
+
+const NOT_CANCELLED: u8 = 0;
+
+impl CancelReason {
+    fn to_u8(self) -> u8 {
+        match self {
+            CancelReason::Cancelled => 1,
+            CancelReason::TimedOut => 2,
+            CancelReason::Dropped => 3,
+        }
+    }
+
+    fn from_u8(value: u8) -> Option<Self> {
+        match value {
+            1 => Some(CancelReason::Cancelled),
+            2 => Some(CancelReason::TimedOut),
+            3 => Some(CancelReason::Dropped),
+            _ => None,
+        }
+    }
+}
+
+/// Identifies a request among the requests sent over the same channel
+///
+/// The ids are assigned in the order the requests are sent, starting at 1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct RequestId(u64);
+
+impl RequestId {
+    /// Returns the id as a number
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for RequestId {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(fmt, "request #{}", self.0)
+    }
+}
+
+/// Identifies the sender a request came from, see [`Responder::sender_id()`](crate::Responder::sender_id())
+///
+/// Every sender of a channel, and every clone of it, is assigned a new id, in
+/// the order they are created, starting at 1. A label can be attached with
+/// [`RequestSender::labeled()`](crate::RequestSender::labeled()), and the clones
+/// of a labeled sender keep its label.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SenderId {
+    id: u64,
+    pub(crate) label: Option<Arc<str>>,
+}
+
+impl SenderId {
+    /// Returns the id as a number
+    pub fn as_u64(&self) -> u64 {
+        self.id
+    }
+
+    /// Returns the label of the sender, if any
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Returns the same id with the given label
+    pub(crate) fn with_label(&self, label: String) -> Arc<SenderId> {
+        Arc::new(SenderId {
+            id: self.id,
+            label: Some(label.into()),
+        })
+    }
+}
+
+impl fmt::Display for SenderId {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.label {
+            Some(label) => write!(fmt, "{} (sender #{})", label, self.id),
+            None => write!(fmt, "sender #{}", self.id),
+        }
+    }
+}
+
+/// User-provided metadata carried along with a request, like correlation ids for logs
+///
+/// Attach it with [`RequestSender::send_with_context()`](crate::RequestSender::send_with_context()).
+pub type RequestContext = HashMap<String, String>;
+
+/// Load-shedding thresholds above which a bounded channel rejects new requests
+/// instead of applying backpressure
+///
+/// Set it with [`ChannelBuilder::admission()`](crate::ChannelBuilder::admission()).
+///
+/// # Examples
+///
+/// ```rust
+/// use tokio::time::Duration;
+///
+/// let admission = bmrng::Admission::new()
+///     .max_depth(64)
+///     .max_age(Duration::from_millis(250));
+/// let (tx, rx) = bmrng::builder::<i32, i32>()
+///     .capacity(128)
+///     .admission(admission)
+///     .build();
+/// ```
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct Admission {
+    max_depth: Option<usize>,
+    max_age: Option<Duration>,
+}
+
+impl Admission {
+    /// Creates an admission controller without thresholds, which admits every request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects new requests while more than `depth` requests are queued
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Rejects new requests while the oldest queued request was sent more than `age` ago
+    pub fn max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+}
+
+/// The [`ChannelMetrics`], the [`ChannelObserver`] or the [`Clock`] attached to a
+/// channel, or the [`TypeMetrics`](crate::metrics::TypeMetrics) of a dynamic channel
+pub(crate) struct Hook<T: ?Sized>(pub(crate) Arc<T>);
+
+impl<T: ?Sized> Clone for Hook<T> {
+    fn clone(&self) -> Self {
+        Hook(self.0.clone())
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for Hook<T> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.write_str("Hook")
+    }
+}
+
+/// The number of requests in the queue of a channel, published to its watchers
+#[derive(Debug)]
+struct QueueDepth {
+    /// Briefly negative when the receiver takes a request before its sender counts it
+    count: AtomicIsize,
+    watch: watch::Sender<usize>,
+}
+
+impl Default for QueueDepth {
+    fn default() -> Self {
+        QueueDepth {
+            count: AtomicIsize::new(0),
+            watch: watch::channel(0).0,
+        }
+    }
+}
+
+/// The state shared by all the senders and the receiver of a channel
+#[derive(Debug, Default)]
+pub(crate) struct ChannelState {
+    pub(crate) name: Option<String>,
+    pub(crate) timeout_duration: Option<Duration>,
+    /// Whether the response timeout starts when the request is dequeued
+    pub(crate) timeout_on_dequeue: bool,
+    pub(crate) send_timeout: Option<Duration>,
+    pub(crate) ttl: Option<Duration>,
+    pub(crate) admission: Admission,
+    /// Limits the number of requests waiting for a response, see
+    /// [`ChannelBuilder::max_outstanding()`](crate::ChannelBuilder::max_outstanding())
+    pub(crate) max_outstanding: Option<Arc<Semaphore>>,
+    /// Paces the requests sent over the channel, see
+    /// [`ChannelBuilder::rate_limit()`](crate::ChannelBuilder::rate_limit())
+    pub(crate) rate_limit: Option<RateLimiter>,
+    pub(crate) metrics: Option<Hook<dyn ChannelMetrics>>,
+    pub(crate) observer: Option<Hook<dyn ChannelObserver>>,
+    /// Measures the response timeouts and the time-to-live of the requests, see
+    /// [`ChannelBuilder::clock()`](crate::ChannelBuilder::clock())
+    pub(crate) clock: Option<Hook<dyn Clock>>,
+    in_flight: AtomicUsize,
+    idle: Notify,
+    /// The send instants of the queued requests by sequence number, only tracked
+    /// when the admission controller limits their age
+    queued: Mutex<BTreeMap<u64, Instant>>,
+    next_sequence: AtomicU64,
+    last_request_id: AtomicU64,
+    last_sender_id: AtomicU64,
+    /// Set once a sender closed the channel for all the senders
+    closing: AtomicBool,
+    /// Wakes the receiver when a sender closes the channel
+    receiver_waker: AtomicWaker,
+    /// The requests in flight by id, so the senders can cancel them, if set by
+    /// [`ChannelBuilder::cancellable()`](crate::ChannelBuilder::cancellable())
+    pub(crate) outstanding: Option<Mutex<HashMap<RequestId, Weak<RequestState>>>>,
+    depth: QueueDepth,
+}
+
+impl ChannelState {
+    pub(crate) fn new(timeout_duration: Option<Duration>) -> Self {
+        ChannelState {
+            timeout_duration,
+            ..Default::default()
+        }
+    }
+
+    /// Returns the current instant of the clock of the channel
+    pub(crate) fn now(&self) -> Instant {
+        match &self.clock {
+            Some(clock) => clock.0.now(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Returns a future completing once the clock of the channel reaches `deadline`
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> ClockSleep {
+        match &self.clock {
+            Some(clock) => clock.0.sleep_until(deadline),
+            None => TokioClock.sleep_until(deadline),
+        }
+    }
+
+    /// Runs `future` until the clock of the channel reaches `deadline`
+    ///
+    /// Returns `None` if the deadline comes first.
+    pub(crate) async fn timeout_at<F: Future>(
+        &self,
+        deadline: Instant,
+        future: F,
+    ) -> Option<F::Output> {
+        match select(pin!(future), self.sleep_until(deadline)).await {
+            Either::Left((output, _)) => Some(output),
+            Either::Right(..) => None,
+        }
+    }
+
+    /// Returns the number of requests that are waiting for a response
+    pub(crate) fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Acquire)
+    }
+
+    /// Waits until no request is waiting for a response
+    pub(crate) async fn idle(&self) {
+        loop {
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.in_flight() == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    /// Closes the channel for all the senders and wakes the receiver, so it
+    /// closes its queue
+    pub(crate) fn close(&self) {
+        self.closing.store(true, Ordering::Release);
+        self.receiver_waker.wake();
+    }
+
+    /// Returns `true` once a sender closed the channel
+    pub(crate) fn is_closing(&self) -> bool {
+        self.closing.load(Ordering::Acquire)
+    }
+
+    /// Returns `true` once a sender closed the channel, or registers the receiver
+    /// to be woken when one does
+    pub(crate) fn poll_closing(&self, cx: &mut Context<'_>) -> bool {
+        self.receiver_waker.register(cx.waker());
+        self.is_closing()
+    }
+
+    /// Waits until fewer requests than the limit of the channel are waiting for a
+    /// response, and returns the permit to hold until the new request is finished
+    pub(crate) async fn acquire_outstanding(&self) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.max_outstanding.clone()?;
+        semaphore.acquire_owned().await.ok()
+    }
+
+    /// Returns the permit to hold until a new request is finished without waiting,
+    /// or `Err` if the limit of the channel is reached
+    pub(crate) fn try_acquire_outstanding(&self) -> Result<Option<OwnedSemaphorePermit>, ()> {
+        match &self.max_outstanding {
+            Some(semaphore) => semaphore
+                .clone()
+                .try_acquire_owned()
+                .map(Some)
+                .map_err(drop),
+            None => Ok(None),
+        }
+    }
+
+    /// Adds `delta` to the number of queued requests, and notifies the watchers if
+    /// the published depth changed
+    pub(crate) fn add_depth(&self, delta: isize) {
+        self.depth.watch.send_if_modified(|depth| {
+            // The count is only updated under the lock of the watch channel, so the
+            // published depths are in order
+            let count = self.depth.count.fetch_add(delta, Ordering::Relaxed) + delta;
+            let count = usize::try_from(count).unwrap_or(0);
+            let changed = *depth != count;
+            *depth = count;
+            changed
+        });
+    }
+
+    /// Returns a watch receiver that sees the number of queued requests
+    pub(crate) fn watch_depth(&self) -> watch::Receiver<usize> {
+        self.depth.watch.subscribe()
+    }
+
+    /// Waits until the rate limit of the channel lets a new request through
+    ///
+    /// Returns `false` right away if it would not before the `deadline`.
+    pub(crate) async fn pace(&self, deadline: Option<Instant>) -> bool {
+        let Some(rate_limit) = &self.rate_limit else {
+            return true;
+        };
+        let now = self.now();
+        match rate_limit.reserve(now, deadline) {
+            Some(at) => {
+                if at > now {
+                    self.sleep_until(at).await;
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns `false` if the rate limit of the channel does not let a new request
+    /// through without waiting
+    pub(crate) fn try_pace(&self) -> bool {
+        self.rate_limit
+            .as_ref()
+            .is_none_or(|rate_limit| rate_limit.try_acquire(self.now()))
+    }
+
+    /// Blocks the current thread until the rate limit of the channel lets a new
+    /// request through
+    ///
+    /// Returns `false` right away if it would not before the `deadline`.
+    pub(crate) fn blocking_pace(&self, deadline: Option<Instant>) -> bool {
+        self.rate_limit
+            .as_ref()
+            .is_none_or(|rate_limit| rate_limit.blocking_acquire(self.now(), deadline))
+    }
+
+    /// Registers a new request, so it can be cancelled by id until it is finished
+    pub(crate) fn register(&self, state: &Arc<RequestState>) {
+        if let Some(mut outstanding) = self.lock_outstanding() {
+            outstanding.insert(state.id, Arc::downgrade(state));
+        }
+    }
+
+    /// Withdraws the request with the given id if it is still in flight
+    ///
+    /// Returns `false` if the request is unknown or already finished, or if the
+    /// requests are not tracked by id.
+    pub(crate) fn cancel(&self, id: RequestId) -> bool {
+        let state = self
+            .lock_outstanding()
+            .and_then(|outstanding| outstanding.get(&id).and_then(Weak::upgrade));
+        match state {
+            Some(state) => state.withdraw(),
+            None => false,
+        }
+    }
+
+    fn lock_outstanding(&self) -> Option<MutexGuard<'_, HashMap<RequestId, Weak<RequestState>>>> {
+        self.outstanding
+            .as_ref()
+            .map(|outstanding| outstanding.lock().unwrap_or_else(|err| err.into_inner()))
+    }
+
+    /// Returns `false` if a new request must be rejected because `depth` requests are
+    /// queued, or because the oldest queued request is too old
+    pub(crate) fn admits(&self, depth: usize) -> bool {
+        if self.admission.max_depth.is_some_and(|max| depth > max) {
+            return false;
+        }
+        match self.admission.max_age {
+            Some(max_age) => self
+                .lock_queued()
+                .values()
+                .next()
+                .is_none_or(|sent| self.now().saturating_duration_since(*sent) <= max_age),
+            None => true,
+        }
+    }
+
+    fn lock_queued(&self) -> MutexGuard<'_, BTreeMap<u64, Instant>> {
+        self.queued.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Assigns the id of a new request
+    pub(crate) fn next_request_id(&self) -> RequestId {
+        RequestId(self.last_request_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    /// Assigns the id of a new sender
+    pub(crate) fn next_sender_id(&self, label: Option<Arc<str>>) -> Arc<SenderId> {
+        Arc::new(SenderId {
+            id: self.last_sender_id.fetch_add(1, Ordering::Relaxed) + 1,
+            label,
+        })
+    }
+
+    fn metrics(&self) -> Option<&dyn ChannelMetrics> {
+        self.metrics.as_ref().map(|hook| &*hook.0)
+    }
+
+    fn observer(&self) -> Option<&dyn ChannelObserver> {
+        self.observer.as_ref().map(|hook| &*hook.0)
+    }
+
+    fn start_request(&self) -> Option<u64> {
+        self.in_flight.fetch_add(1, Ordering::AcqRel);
+        if let Some(metrics) = self.metrics() {
+            metrics.on_send();
+        }
+        self.admission.max_age?;
+        let sequence = self.next_sequence.fetch_add(1, Ordering::Relaxed);
+        self.lock_queued().insert(sequence, self.now());
+        Some(sequence)
+    }
+
+    fn dequeue(&self, sequence: u64) {
+        self.lock_queued().remove(&sequence);
+    }
+
+    fn finish_request(&self, id: RequestId) {
+        if let Some(mut outstanding) = self.lock_outstanding() {
+            outstanding.remove(&id);
+        }
+        if self.in_flight.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.idle.notify_waiters();
+        }
+    }
+}
+
+/// When the requesting side stops waiting for the response
+#[derive(Debug, Copy, Clone)]
+enum Deadline {
+    Never,
+    At(Instant),
+    /// The response timeout starts once the request is dequeued
+    OnDequeue(Duration),
+}
+
+/// The state of a single request shared between its responder and its [`ResponseReceiver`](crate::ResponseReceiver)
+#[derive(Debug)]
+pub(crate) struct RequestState {
+    pub(crate) id: RequestId,
+    pub(crate) context: Option<RequestContext>,
+    cancel_reason: AtomicU8,
+    deadline: Mutex<Deadline>,
+    expires_at: Option<Instant>,
+    channel: Option<Arc<ChannelState>>,
+    /// The number of the request in the queue age tracking of its channel
+    sequence: Option<u64>,
+    sent_at: Instant,
+    /// Whether the receiver took the request out of the queue
+    received: AtomicBool,
+    /// Whether the responder is gone, after responding or not
+    released: AtomicBool,
+    finished: AtomicBool,
+    /// The permits counted against the outstanding requests limit of the channel
+    /// and the quota of the sender
+    outstanding: Mutex<Vec<OwnedSemaphorePermit>>,
+    /// Whether the request was cancelled by id from the sending side
+    withdrawn: AtomicBool,
+    /// The tasks waiting for the request to be accepted, released or withdrawn
+    wakers: Mutex<Vec<Waker>>,
+    drop_error: Mutex<Option<ReceiveError>>,
+    #[cfg(feature = "tokio-util")]
+    token: CancellationToken,
+    /// The span that was current when the request was sent
+    #[cfg(feature = "tracing")]
+    pub(crate) span: tracing::Span,
+    /// The OpenTelemetry context that was current when the request was sent
+    #[cfg(feature = "otel")]
+    pub(crate) otel_context: opentelemetry::Context,
+}
+
+impl RequestState {
+    /// Creates the state of a request counted as in flight by `channel` until it is finished
+    pub(crate) fn new(
+        id: RequestId,
+        context: Option<RequestContext>,
+        deadline: Option<Instant>,
+        expires_at: Option<Instant>,
+        channel: Option<Arc<ChannelState>>,
+    ) -> Self {
+        let sequence = channel.as_ref().and_then(|channel| channel.start_request());
+        let sent_at = channel
+            .as_ref()
+            .map_or_else(Instant::now, |channel| channel.now());
+        RequestState {
+            id,
+            context,
+            cancel_reason: AtomicU8::new(NOT_CANCELLED),
+            deadline: Mutex::new(deadline.map_or(Deadline::Never, Deadline::At)),
+            expires_at,
+            channel,
+            sequence,
+            sent_at,
+            received: AtomicBool::new(false),
+            released: AtomicBool::new(false),
+            finished: AtomicBool::new(false),
+            outstanding: Mutex::new(Vec::new()),
+            withdrawn: AtomicBool::new(false),
+            wakers: Mutex::new(Vec::new()),
+            drop_error: Mutex::new(None),
+            #[cfg(feature = "tokio-util")]
+            token: CancellationToken::new(),
+            #[cfg(feature = "tracing")]
+            span: tracing::Span::current(),
+            #[cfg(feature = "otel")]
+            otel_context: opentelemetry::Context::current(),
+        }
+    }
+
+    /// Marks the request as no longer in flight, because it was responded to or
+    /// the requesting side gave up
+    pub(crate) fn finish(&self) {
+        if !self.finished.swap(true, Ordering::AcqRel) {
+            self.dequeued();
+            self.outstanding
+                .lock()
+                .unwrap_or_else(|err| err.into_inner())
+                .clear();
+            if let Some(channel) = &self.channel {
+                channel.finish_request(self.id);
+            }
+        }
+    }
+
+    /// Holds the permits of the outstanding requests limits until the request is finished
+    pub(crate) fn hold(&self, permits: impl IntoIterator<Item = OwnedSemaphorePermit>) {
+        self.outstanding
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .extend(permits);
+    }
+
+    /// Marks the request as taken out of the queue by the receiver
+    pub(crate) fn dequeued(&self) {
+        if let (Some(channel), Some(sequence)) = (&self.channel, self.sequence) {
+            channel.dequeue(sequence);
+        }
+    }
+
+    fn metrics(&self) -> Option<&dyn ChannelMetrics> {
+        self.channel.as_ref().and_then(|channel| channel.metrics())
+    }
+
+    fn observer(&self) -> Option<&dyn ChannelObserver> {
+        self.channel.as_ref().and_then(|channel| channel.observer())
+    }
+
+    /// Reports to the metrics of the channel that the receiver took the request, the
+    /// first time it does
+    pub(crate) fn received(&self) {
+        if !self.accept() {
+            return;
+        }
+        if let Some(metrics) = self.metrics() {
+            metrics.on_recv(self.age());
+        }
+    }
+
+    /// Marks the request as accepted by the receiver, starting the response timeout
+    /// if it waited for it, and wakes the tasks waiting for it. Returns `true` the
+    /// first time.
+    fn accept(&self) -> bool {
+        if self.received.swap(true, Ordering::AcqRel) {
+            return false;
+        }
+        {
+            let mut deadline = self.lock_deadline();
+            if let Deadline::OnDequeue(duration) = *deadline {
+                *deadline = Deadline::At(self.now() + duration);
+            }
+        }
+        self.wake();
+        true
+    }
+
+    /// Returns `Poll::Ready(true)` once the receiver accepted the request, or
+    /// `Poll::Ready(false)` if the responder is gone or the request was withdrawn before
+    pub(crate) fn poll_accepted(&self, cx: &mut Context<'_>) -> Poll<bool> {
+        self.poll_until(cx, || {
+            if self.received.load(Ordering::Acquire) {
+                Some(true)
+            } else if self.released.load(Ordering::Acquire) || self.is_withdrawn() {
+                Some(false)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Marks the responder as gone, waking the tasks waiting for the request to be accepted
+    pub(crate) fn released(&self) {
+        self.released.store(true, Ordering::Release);
+        self.wake();
+    }
+
+    /// Reports to the metrics of the channel that the response was delivered
+    pub(crate) fn responded(&self) {
+        self.accept();
+        if let Some(metrics) = self.metrics() {
+            metrics.on_respond(self.age());
+        }
+    }
+
+    /// Reports to the metrics and the observer of the channel that the responder
+    /// was dropped without responding
+    pub(crate) fn dropped(&self) {
+        if let Some(metrics) = self.metrics() {
+            metrics.on_drop();
+        }
+        if let Some(observer) = self.observer() {
+            if self.received.load(Ordering::Acquire) {
+                observer.on_responder_dropped(self.id);
+            } else {
+                observer.on_request_dropped(self.id);
+            }
+        }
+    }
+
+    /// Reports to the observer of the channel that a response could not be delivered
+    /// because the requesting side had stopped waiting
+    pub(crate) fn undelivered(&self) {
+        if self.cancel_reason() == Some(CancelReason::TimedOut) {
+            if let Some(observer) = self.observer() {
+                observer.on_late_response(self.id, self.age());
+            }
+        }
+    }
+
+    /// Records why the requesting side gave up, unless a reason was already recorded
+    pub(crate) fn cancel(&self, reason: CancelReason) {
+        let recorded = self
+            .cancel_reason
+            .compare_exchange(
+                NOT_CANCELLED,
+                reason.to_u8(),
+                Ordering::AcqRel,
+                Ordering::Acquire,
+            )
+            .is_ok();
+        if recorded && reason == CancelReason::TimedOut {
+            if let Some(metrics) = self.metrics() {
+                metrics.on_timeout();
+            }
+        }
+        #[cfg(feature = "tokio-util")]
+        self.token.cancel();
+        self.finish();
+    }
+
+    /// Cancels the request on behalf of a sender, waking the responder and the
+    /// response receiver waiting for it
+    ///
+    /// Returns `false` if the request was already finished.
+    pub(crate) fn withdraw(&self) -> bool {
+        if self.finished.load(Ordering::Acquire) {
+            return false;
+        }
+        self.withdrawn.store(true, Ordering::Release);
+        self.cancel(CancelReason::Cancelled);
+        self.wake();
+        true
+    }
+
+    /// Returns `true` if the request was cancelled by id from the sending side
+    pub(crate) fn is_withdrawn(&self) -> bool {
+        self.withdrawn.load(Ordering::Acquire)
+    }
+
+    /// Returns `Poll::Ready` once the request is cancelled by id from the sending side
+    pub(crate) fn poll_withdrawn(&self, cx: &mut Context<'_>) -> Poll<()> {
+        self.poll_until(cx, || self.is_withdrawn().then_some(()))
+    }
+
+    /// Returns `Poll::Ready` with the output of `ready` once it returns `Some`,
+    /// registering the task to be woken by [`wake()`](Self::wake()) until then
+    fn poll_until<T>(&self, cx: &mut Context<'_>, ready: impl Fn() -> Option<T>) -> Poll<T> {
+        if let Some(output) = ready() {
+            return Poll::Ready(output);
+        }
+        let mut wakers = self.lock_wakers();
+        if let Some(output) = ready() {
+            return Poll::Ready(output);
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    fn wake(&self) {
+        let wakers = std::mem::take(&mut *self.lock_wakers());
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+
+    fn lock_wakers(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    pub(crate) fn cancel_reason(&self) -> Option<CancelReason> {
+        CancelReason::from_u8(self.cancel_reason.load(Ordering::Acquire))
+    }
+
+    /// Records the error to report when the responder is dropped without responding
+    pub(crate) fn set_drop_error(&self, error: ReceiveError) {
+        *self
+            .drop_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner()) = Some(error);
+    }
+
+    pub(crate) fn drop_error(&self) -> Option<ReceiveError> {
+        *self
+            .drop_error
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Returns the instant the request was sent at
+    pub(crate) fn sent_at(&self) -> Instant {
+        self.sent_at
+    }
+
+    /// Returns the time since the request was sent, measured with the clock of the channel
+    fn age(&self) -> Duration {
+        self.now().saturating_duration_since(self.sent_at)
+    }
+
+    /// Returns `true` if the request has been queued for longer than its time-to-live
+    pub(crate) fn expired(&self) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| self.now() >= expires_at)
+    }
+
+    /// Returns the current instant of the clock of the channel
+    pub(crate) fn now(&self) -> Instant {
+        self.channel
+            .as_ref()
+            .map_or_else(Instant::now, |channel| channel.now())
+    }
+
+    /// Returns a future completing once the clock of the channel reaches `deadline`
+    pub(crate) fn sleep_until(&self, deadline: Instant) -> ClockSleep {
+        match &self.channel {
+            Some(channel) => channel.sleep_until(deadline),
+            None => TokioClock.sleep_until(deadline),
+        }
+    }
+
+    /// Returns the instant the requesting side stops waiting for the response at, if any
+    ///
+    /// It returns `None` until the request is dequeued if the response timeout
+    /// starts then.
+    pub(crate) fn deadline(&self) -> Option<Instant> {
+        match *self.lock_deadline() {
+            Deadline::At(deadline) => Some(deadline),
+            Deadline::Never | Deadline::OnDequeue(..) => None,
+        }
+    }
+
+    pub(crate) fn set_deadline(&self, deadline: Option<Instant>) {
+        *self.lock_deadline() = deadline.map_or(Deadline::Never, Deadline::At);
+    }
+
+    /// Starts the response timeout when the request is dequeued instead of now
+    pub(crate) fn set_timeout_on_dequeue(&self, duration: Duration) {
+        *self.lock_deadline() = Deadline::OnDequeue(duration);
+    }
+
+    /// Returns `Poll::Ready` with the deadline once it is known, that is right away
+    /// unless the response timeout waits for the request to be dequeued
+    pub(crate) fn poll_deadline(&self, cx: &mut Context<'_>) -> Poll<Option<Instant>> {
+        if matches!(*self.lock_deadline(), Deadline::OnDequeue(..)) {
+            ready!(self.poll_accepted(cx));
+        }
+        Poll::Ready(self.deadline())
+    }
+
+    fn lock_deadline(&self) -> MutexGuard<'_, Deadline> {
+        self.deadline.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Records an event in the span of the request
+    pub(crate) fn trace(&self, _message: &'static str) {
+        #[cfg(feature = "tracing")]
+        tracing::trace!(parent: &self.span, "{}", _message);
+    }
+
+    #[cfg(feature = "tokio-util")]
+    pub(crate) fn cancellation_token(&self) -> CancellationToken {
+        self.token.child_token()
+    }
+}
+
+impl Drop for RequestState {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
@@ -0,0 +1,213 @@
+use crate::bounded::{Payload, Responder};
+
+use futures_core::Stream;
+use futures_util::stream::StreamExt;
+use std::collections::VecDeque;
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll, Wake, Waker};
+
+/// Adapters for streams of [`Payload`]s, like [`RequestReceiverStream`](crate::RequestReceiverStream),
+/// that operate on the request while carrying its [`Responder`] through
+pub trait PayloadStreamExt<Req, Res>: Stream<Item = Payload<Req, Res>> + Sized {
+    /// Maps every request with `f`, keeping its responder attached
+    fn map_request<T, F>(self, mut f: F) -> impl Stream<Item = (T, Responder<Res>)>
+    where
+        F: FnMut(Req) -> T,
+    {
+        self.map(move |(request, responder)| (f(request), responder))
+    }
+
+    /// Maps every request with `f`, keeping its responder attached, and skips the
+    /// requests it returns `None` for
+    ///
+    /// The responders of the skipped requests are dropped, so their senders fail
+    /// with [`RequestError::RecvError`](crate::error::RequestError::RecvError).
+    fn filter_map_request<T, F>(self, mut f: F) -> impl Stream<Item = (T, Responder<Res>)>
+    where
+        F: FnMut(Req) -> Option<T>,
+    {
+        self.filter_map(move |(request, responder)| {
+            let mapped = f(request).map(|mapped| (mapped, responder));
+            async move { mapped }
+        })
+    }
+
+    /// Splits the stream in two: the payloads whose request matches the `predicate`,
+    /// and the others
+    ///
+    /// Both halves pull from the same stream, and a payload for the other half is
+    /// buffered until that half is polled. Drop a half you do not need, so its
+    /// payloads are dropped instead of buffered.
+    fn split_by<F>(self, predicate: F) -> (SplitPayloads<Self, F>, SplitPayloads<Self, F>)
+    where
+        F: FnMut(&Req) -> bool,
+    {
+        let wakers = Arc::new(HalfWakers::default());
+        let shared = Arc::new(Mutex::new(Split {
+            stream: Box::pin(self),
+            predicate,
+            halves: [Half::default(), Half::default()],
+            waker: Waker::from(wakers.clone()),
+            wakers,
+            done: false,
+        }));
+        (
+            SplitPayloads {
+                shared: shared.clone(),
+                side: MATCHING,
+            },
+            SplitPayloads {
+                shared,
+                side: OTHERS,
+            },
+        )
+    }
+}
+
+impl<S, Req, Res> PayloadStreamExt<Req, Res> for S where S: Stream<Item = Payload<Req, Res>> {}
+
+const MATCHING: usize = 0;
+const OTHERS: usize = 1;
+
+/// One half of a payload stream split by [`PayloadStreamExt::split_by()`]
+pub struct SplitPayloads<S: Stream, F> {
+    shared: Arc<Mutex<Split<S, F>>>,
+    side: usize,
+}
+
+struct Split<S: Stream, F> {
+    stream: Pin<Box<S>>,
+    predicate: F,
+    halves: [Half<S::Item>; 2],
+    /// Polls the inner stream on behalf of both halves, so it wakes both of them
+    waker: Waker,
+    wakers: Arc<HalfWakers>,
+    done: bool,
+}
+
+struct Half<T> {
+    buffered: VecDeque<T>,
+    alive: bool,
+}
+
+impl<T> Default for Half<T> {
+    fn default() -> Self {
+        Half {
+            buffered: VecDeque::new(),
+            alive: true,
+        }
+    }
+}
+
+/// The wakers of the tasks polling each half
+#[derive(Default)]
+struct HalfWakers(Mutex<[Option<Waker>; 2]>);
+
+impl HalfWakers {
+    fn lock(&self) -> MutexGuard<'_, [Option<Waker>; 2]> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn register(&self, side: usize, waker: &Waker) {
+        self.lock()[side] = Some(waker.clone());
+    }
+
+    fn wake_half(&self, side: usize) {
+        let waker = self.lock()[side].take();
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Wake for HalfWakers {
+    fn wake(self: Arc<Self>) {
+        self.wake_by_ref();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.wake_half(MATCHING);
+        self.wake_half(OTHERS);
+    }
+}
+
+impl<S: Stream, F> SplitPayloads<S, F> {
+    fn lock(&self) -> MutexGuard<'_, Split<S, F>> {
+        self.shared
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl<S, F, Req, Res> Stream for SplitPayloads<S, F>
+where
+    S: Stream<Item = Payload<Req, Res>>,
+    F: FnMut(&Req) -> bool,
+{
+    type Item = Payload<Req, Res>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let side = self.side;
+        let mut split = self.lock();
+        let split = &mut *split;
+        if let Some(payload) = split.halves[side].buffered.pop_front() {
+            return Poll::Ready(Some(payload));
+        }
+        split.wakers.register(side, cx.waker());
+        let mut inner_cx = Context::from_waker(&split.waker);
+        while !split.done {
+            match split.stream.as_mut().poll_next(&mut inner_cx) {
+                Poll::Ready(Some(payload)) => {
+                    let target = if (split.predicate)(&payload.0) {
+                        MATCHING
+                    } else {
+                        OTHERS
+                    };
+                    if target == side {
+                        return Poll::Ready(Some(payload));
+                    }
+                    let other = &mut split.halves[target];
+                    if other.alive {
+                        other.buffered.push_back(payload);
+                        split.wakers.wake_half(target);
+                    }
+                }
+                Poll::Ready(None) => {
+                    split.done = true;
+                    split.wakers.wake_half(1 - side);
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl<S: Stream, F> Drop for SplitPayloads<S, F> {
+    fn drop(&mut self) {
+        let side = self.side;
+        let buffered = {
+            let mut split = self.lock();
+            split.wakers.lock()[side] = None;
+            let half = &mut split.halves[side];
+            half.alive = false;
+            std::mem::take(&mut half.buffered)
+        };
+        drop(buffered);
+    }
+}
+
+impl<S: Stream, F> fmt::Debug for SplitPayloads<S, F> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let split = self.lock();
+        fmt.debug_struct("SplitPayloads")
+            .field("matching", &(self.side == MATCHING))
+            .field("buffered", &split.halves[self.side].buffered.len())
+            .field("done", &split.done)
+            .finish()
+    }
+}
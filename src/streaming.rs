@@ -0,0 +1,179 @@
+use crate::error::{RequestError, RespondError, SendError};
+
+use futures_core::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc;
+
+/// The internal data sent in the MPSC request channel, a tuple that contains the request and the responder of the response stream
+pub type Payload<Req, Item> = (Req, StreamingResponder<Item>);
+
+/// Send requests to the associated [`StreamingRequestReceiver`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct StreamingRequestSender<Req, Item> {
+    request_sender: mpsc::Sender<Payload<Req, Item>>,
+    buffer: usize,
+}
+
+/// Receive requests from the associated [`StreamingRequestSender`]
+///
+/// Instances are created by the [`channel`] function.
+#[derive(Debug)]
+pub struct StreamingRequestReceiver<Req, Item> {
+    request_receiver: mpsc::Receiver<Payload<Req, Item>>,
+}
+
+/// Send any number of response items back to the [`ResponseStream`] of a request
+///
+/// The response stream ends when the responder is dropped.
+#[derive(Debug)]
+pub struct StreamingResponder<Item> {
+    item_sender: mpsc::Sender<Item>,
+}
+
+/// Receive the response items sent by a [`StreamingResponder`]
+///
+/// Instances are created by calling [`StreamingRequestSender::send()`]
+#[derive(Debug)]
+pub struct ResponseStream<Item> {
+    item_receiver: mpsc::Receiver<Item>,
+}
+
+impl<Req, Item> StreamingRequestSender<Req, Item> {
+    /// Send a request over the MPSC channel, open the response stream
+    ///
+    /// This call waits if the request channel is full
+    pub async fn send(&self, request: Req) -> Result<ResponseStream<Item>, SendError<Req>> {
+        let (item_sender, item_receiver) = mpsc::channel(self.buffer);
+        self.request_sender
+            .send((request, StreamingResponder { item_sender }))
+            .await
+            .map_err(|payload| SendError(payload.0 .0))?;
+        Ok(ResponseStream { item_receiver })
+    }
+
+    /// Checks if the channel has been closed.
+    pub fn is_closed(&self) -> bool {
+        self.request_sender.is_closed()
+    }
+}
+
+impl<Req, Item> Clone for StreamingRequestSender<Req, Item> {
+    fn clone(&self) -> Self {
+        StreamingRequestSender {
+            request_sender: self.request_sender.clone(),
+            buffer: self.buffer,
+        }
+    }
+}
+
+impl<Req, Item> StreamingRequestReceiver<Req, Item> {
+    /// Receives the next value for this receiver.
+    pub async fn recv(&mut self) -> Result<Payload<Req, Item>, RequestError<Req>> {
+        match self.request_receiver.recv().await {
+            Some(payload) => Ok(payload),
+            None => Err(RequestError::recv_error()),
+        }
+    }
+
+    /// Closes the receiving half of a channel without dropping it.
+    pub fn close(&mut self) {
+        self.request_receiver.close()
+    }
+}
+
+impl<Item> StreamingResponder<Item> {
+    /// Sends the next item of the response stream
+    ///
+    /// This call waits if the response stream is full. It fails if the
+    /// [`ResponseStream`] has been dropped.
+    pub async fn send(&self, item: Item) -> Result<(), RespondError<Item>> {
+        self.item_sender
+            .send(item)
+            .await
+            .map_err(|err| RespondError::new(err.0))
+    }
+
+    /// Checks if the associated [`ResponseStream`] has been dropped.
+    pub fn is_closed(&self) -> bool {
+        self.item_sender.is_closed()
+    }
+
+    /// Waits until the associated [`ResponseStream`] is dropped
+    pub async fn closed(&self) {
+        self.item_sender.closed().await
+    }
+}
+
+impl<Item> ResponseStream<Item> {
+    /// Receives the next item of the response stream, or `None` once the
+    /// [`StreamingResponder`] has been dropped and all the items were received
+    pub async fn recv(&mut self) -> Option<Item> {
+        self.item_receiver.recv().await
+    }
+
+    /// Stops the response stream, letting the [`StreamingResponder`] know that
+    /// no more items are wanted
+    ///
+    /// The items that were already sent can still be received.
+    pub fn close(&mut self) {
+        self.item_receiver.close()
+    }
+}
+
+impl<Item> Stream for ResponseStream<Item> {
+    type Item = Item;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.item_receiver.poll_recv(cx)
+    }
+}
+
+/// Creates a bounded mpsc request channel where every request is answered with
+/// a stream of response items
+///
+/// `buffer` is the capacity of the request channel and of the response stream of
+/// every request.
+///
+/// # Panics
+///
+/// Panics if the buffer capacity is 0, just like the Tokio MPSC channel
+///
+/// # Examples
+///
+/// ```rust
+/// use futures_util::StreamExt;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mut rx) = bmrng::streaming::channel::<u32, u32>(8);
+///     tokio::spawn(async move {
+///         while let Ok((count, responder)) = rx.recv().await {
+///             for i in 0..count {
+///                 if responder.send(i).await.is_err() {
+///                     break;
+///                 }
+///             }
+///         }
+///     });
+///     let items: Vec<u32> = tx.send(3).await.unwrap().collect().await;
+///     assert_eq!(items, vec![0, 1, 2]);
+/// }
+/// ```
+pub fn channel<Req, Item>(
+    buffer: usize,
+) -> (
+    StreamingRequestSender<Req, Item>,
+    StreamingRequestReceiver<Req, Item>,
+) {
+    let (request_sender, request_receiver) = mpsc::channel(buffer);
+    (
+        StreamingRequestSender {
+            request_sender,
+            buffer,
+        },
+        StreamingRequestReceiver { request_receiver },
+    )
+}
@@ -0,0 +1,431 @@
+use crate::bounded::{Payload, RequestSender};
+use crate::error::RequestError;
+use crate::Request;
+
+use futures_core::Stream;
+use futures_util::future::join_all;
+use futures_util::stream::StreamExt;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep_until, Duration, Instant};
+
+/// The capacity of the channels created by [`MockResponder::auto()`] and
+/// [`MockResponder::scripted()`]
+const MOCK_CAPACITY: usize = 16;
+
+/// The requests received by a [`MockResponder`], in the order they arrived
+struct Log<Req> {
+    requests: Vec<Req>,
+    unanswered: usize,
+}
+
+/// A receiver task answering the requests sent to it from a closure or a script,
+/// and recording them to assert on later
+///
+/// The task runs until the channel closes, or until the mock is dropped.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::test::MockResponder;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, mock) = MockResponder::auto(|input: &i32| input * 2);
+///     assert_eq!(tx.send_receive(21).await, Ok(42));
+///     mock.assert_received(&21);
+///     mock.assert_all_answered();
+///     bmrng::test::assert_idle(&tx);
+/// }
+/// ```
+pub struct MockResponder<Req> {
+    log: Arc<Mutex<Log<Req>>>,
+    received: watch::Receiver<usize>,
+    task: JoinHandle<()>,
+}
+
+impl<Req: Send + 'static> MockResponder<Req> {
+    /// Creates a channel whose requests are answered with the response `handler`
+    /// returns for them
+    pub fn auto<Res, F>(mut handler: F) -> (RequestSender<Req, Res>, Self)
+    where
+        Res: Send + 'static,
+        F: FnMut(&Req) -> Res + Send + 'static,
+    {
+        let (sender, receiver) = crate::channel(MOCK_CAPACITY);
+        let mock = MockResponder::serve(receiver.into_stream(), move |request| {
+            Some(handler(request))
+        });
+        (sender, mock)
+    }
+
+    /// Creates a channel whose requests are answered with the `responses`, in order
+    ///
+    /// Once the script runs out, the responders of the further requests are
+    /// dropped, so their senders fail with
+    /// [`RequestError::RecvError`].
+    pub fn scripted<Res, I>(responses: I) -> (RequestSender<Req, Res>, Self)
+    where
+        Res: Send + 'static,
+        I: IntoIterator<Item = Res>,
+        I::IntoIter: Send + 'static,
+    {
+        let mut responses = responses.into_iter();
+        let (sender, receiver) = crate::channel(MOCK_CAPACITY);
+        let mock = MockResponder::serve(receiver.into_stream(), move |_| responses.next());
+        (sender, mock)
+    }
+
+    /// Answers the payloads of an existing receiver, like
+    /// [`UnboundedRequestReceiver::into_stream()`](crate::unbounded::UnboundedRequestReceiver::into_stream())
+    ///
+    /// The responder of a request `handler` returns `None` for is dropped without
+    /// a response, and the request counts as unanswered.
+    pub fn serve<Res, S, F>(requests: S, mut handler: F) -> Self
+    where
+        Res: Send + 'static,
+        S: Stream<Item = Payload<Req, Res>> + Send + 'static,
+        F: FnMut(&Req) -> Option<Res> + Send + 'static,
+    {
+        let log = Arc::new(Mutex::new(Log {
+            requests: Vec::new(),
+            unanswered: 0,
+        }));
+        let (count, received) = watch::channel(0);
+        let task = tokio::spawn({
+            let log = log.clone();
+            async move {
+                let mut requests = Box::pin(requests);
+                while let Some((request, responder)) = requests.next().await {
+                    let response = handler(&request);
+                    {
+                        let mut log = lock(&log);
+                        log.requests.push(request);
+                        log.unanswered += usize::from(response.is_none());
+                    }
+                    if let Some(response) = response {
+                        // The requesting side may have given up already
+                        let _ = responder.respond(response);
+                    }
+                    count.send_modify(|count| *count += 1);
+                }
+            }
+        });
+        MockResponder {
+            log,
+            received,
+            task,
+        }
+    }
+}
+
+impl<Req> MockResponder<Req> {
+    /// Returns the number of requests received so far
+    pub fn received(&self) -> usize {
+        lock(&self.log).requests.len()
+    }
+
+    /// Returns the number of requests received so far that were not answered
+    pub fn unanswered(&self) -> usize {
+        lock(&self.log).unanswered
+    }
+
+    /// Returns a copy of the requests received so far, in the order they arrived
+    pub fn requests(&self) -> Vec<Req>
+    where
+        Req: Clone,
+    {
+        lock(&self.log).requests.clone()
+    }
+
+    /// Waits until at least `count` requests have been received
+    ///
+    /// Returns right away if the channel closed before that many requests arrived.
+    pub async fn wait_for(&self, count: usize) {
+        let mut received = self.received.clone();
+        let _ = received.wait_for(|received| *received >= count).await;
+    }
+
+    /// Panics unless a request equal to `request` has been received
+    #[track_caller]
+    pub fn assert_received(&self, request: &Req)
+    where
+        Req: PartialEq + fmt::Debug,
+    {
+        let log = lock(&self.log);
+        assert!(
+            log.requests.contains(request),
+            "expected request {:?} to have been received, received {:?}",
+            request,
+            log.requests
+        );
+    }
+
+    /// Panics if any request received so far was not answered
+    #[track_caller]
+    pub fn assert_all_answered(&self) {
+        let unanswered = self.unanswered();
+        assert!(
+            unanswered == 0,
+            "expected every request to be answered, {} were not",
+            unanswered
+        );
+    }
+}
+
+impl<Req> Drop for MockResponder<Req> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl<Req: fmt::Debug> fmt::Debug for MockResponder<Req> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let log = lock(&self.log);
+        fmt.debug_struct("MockResponder")
+            .field("requests", &log.requests)
+            .field("unanswered", &log.unanswered)
+            .finish()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Panics if any request sent by `sender` or its clones is still queued or
+/// waiting for its response
+#[track_caller]
+pub fn assert_idle<Req, Res>(sender: &RequestSender<Req, Res>) {
+    let pending = sender.pending_responses();
+    assert!(
+        pending == 0,
+        "expected no unanswered requests, {} are still pending",
+        pending
+    );
+}
+
+/// A request and its response, captured by a [`RecordingSender`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange<R: Request> {
+    /// The request sent over the channel
+    pub request: R,
+    /// The response received for the request, or `None` if the request failed
+    pub response: Option<R::Response>,
+    /// When the request was sent, since the recording started
+    pub sent_at: Duration,
+    /// The time the request waited for its response
+    pub elapsed: Duration,
+}
+
+/// The requests and responses captured by a [`RecordingSender`], to replay them later
+pub struct Recording<R: Request> {
+    /// Ordered by the time the requests were sent
+    exchanges: Vec<Exchange<R>>,
+}
+
+impl<R> Clone for Recording<R>
+where
+    R: Request + Clone,
+    R::Response: Clone,
+{
+    fn clone(&self) -> Self {
+        Recording {
+            exchanges: self.exchanges.clone(),
+        }
+    }
+}
+
+impl<R> PartialEq for Recording<R>
+where
+    R: Request + PartialEq,
+    R::Response: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.exchanges == other.exchanges
+    }
+}
+
+impl<R> fmt::Debug for Recording<R>
+where
+    R: Request + fmt::Debug,
+    R::Response: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("Recording")
+            .field("exchanges", &self.exchanges)
+            .finish()
+    }
+}
+
+impl<R> Recording<R>
+where
+    R: Request + Clone + Send + 'static,
+    R::Response: Clone + Send + 'static,
+{
+    /// Creates a recording from previously captured exchanges
+    pub fn new(mut exchanges: Vec<Exchange<R>>) -> Self {
+        exchanges.sort_by_key(|exchange| exchange.sent_at);
+        Recording { exchanges }
+    }
+
+    /// Returns the captured exchanges, ordered by the time their requests were sent
+    pub fn exchanges(&self) -> &[Exchange<R>] {
+        &self.exchanges
+    }
+
+    /// Returns the number of captured exchanges
+    pub fn len(&self) -> usize {
+        self.exchanges.len()
+    }
+
+    /// Returns `true` if no exchanges were captured
+    pub fn is_empty(&self) -> bool {
+        self.exchanges.is_empty()
+    }
+
+    /// Sends the recorded requests again with the same relative timing, and
+    /// returns the results in the order of the recording
+    ///
+    /// Use this to feed a recorded session to the receiver under test, and compare
+    /// its responses to the recorded ones.
+    pub async fn replay_requests(
+        &self,
+        sender: &RequestSender<R, R::Response>,
+    ) -> Vec<Result<R::Response, RequestError<R>>> {
+        let start = Instant::now();
+        join_all(self.exchanges.iter().map(|exchange| async move {
+            sleep_until(start + exchange.sent_at).await;
+            sender.send_receive(exchange.request.clone()).await
+        }))
+        .await
+    }
+
+    /// Creates a channel whose requests are answered with the recorded responses, in order
+    ///
+    /// Use this to stand in for the receiver of a recorded session while testing
+    /// the sending side. The requests are not matched against the recorded ones;
+    /// assert on them with the returned [`MockResponder`]. The responders of the
+    /// requests that failed in the recording, and of the requests past its end,
+    /// are dropped.
+    pub fn replay_responses(&self) -> (RequestSender<R, R::Response>, MockResponder<R>) {
+        let responses: Vec<_> = self
+            .exchanges
+            .iter()
+            .map(|exchange| exchange.response.clone())
+            .collect();
+        let mut responses = responses.into_iter();
+        let (sender, receiver) = crate::channel(MOCK_CAPACITY);
+        let mock =
+            MockResponder::serve(receiver.into_stream(), move |_| responses.next().flatten());
+        (sender, mock)
+    }
+}
+
+/// A [`RequestSender`] that captures every request sent with
+/// [`send_receive()`](Self::send_receive()), along with its response and timing
+///
+/// Clones of the sender share the recording. Requests that could not be sent
+/// because the channel is closed are not captured.
+///
+/// # Examples
+///
+/// ```rust
+/// use bmrng::test::{MockResponder, RecordingSender};
+/// use bmrng::Request;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// struct Double(i32);
+///
+/// impl Request for Double {
+///     type Response = i32;
+/// }
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let (tx, _mock) = MockResponder::auto(|Double(input): &Double| input * 2);
+///     let tx = RecordingSender::new(tx);
+///     assert_eq!(tx.send_receive(Double(21)).await, Ok(42));
+///
+///     let recording = tx.recording();
+///     let (tx, mock) = recording.replay_responses();
+///     assert_eq!(tx.send_receive(Double(21)).await, Ok(42));
+///     mock.assert_received(&Double(21));
+/// }
+/// ```
+pub struct RecordingSender<R: Request> {
+    sender: RequestSender<R, R::Response>,
+    session: Arc<Mutex<Session<R>>>,
+}
+
+struct Session<R: Request> {
+    started: Instant,
+    exchanges: Vec<Exchange<R>>,
+}
+
+impl<R> RecordingSender<R>
+where
+    R: Request + Clone + Send + 'static,
+    R::Response: Clone + Send + 'static,
+{
+    /// Wraps the sender to capture the requests sent with it, starting the recording now
+    pub fn new(sender: RequestSender<R, R::Response>) -> Self {
+        RecordingSender {
+            sender,
+            session: Arc::new(Mutex::new(Session {
+                started: Instant::now(),
+                exchanges: Vec::new(),
+            })),
+        }
+    }
+
+    /// Send a request over the channel, wait for the response and return it,
+    /// capturing both
+    pub async fn send_receive(&self, request: R) -> Result<R::Response, RequestError<R>> {
+        let sent = Instant::now();
+        let captured = request.clone();
+        let result = self.sender.send_receive(request).await;
+        if !matches!(result, Err(RequestError::SendError(..))) {
+            let mut session = lock(&self.session);
+            let sent_at = sent.saturating_duration_since(session.started);
+            session.exchanges.push(Exchange {
+                request: captured,
+                response: result.as_ref().ok().cloned(),
+                sent_at,
+                elapsed: sent.elapsed(),
+            });
+        }
+        result
+    }
+
+    /// Returns the exchanges captured so far
+    pub fn recording(&self) -> Recording<R> {
+        Recording::new(lock(&self.session).exchanges.clone())
+    }
+
+    /// Returns the wrapped sender, to send requests that are not captured
+    pub fn inner(&self) -> &RequestSender<R, R::Response> {
+        &self.sender
+    }
+}
+
+impl<R: Request> Clone for RecordingSender<R> {
+    fn clone(&self) -> Self {
+        RecordingSender {
+            sender: self.sender.clone(),
+            session: self.session.clone(),
+        }
+    }
+}
+
+impl<R: Request> fmt::Debug for RecordingSender<R> {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("RecordingSender")
+            .field("captured", &lock(&self.session).exchanges.len())
+            .finish()
+    }
+}
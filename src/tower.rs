@@ -0,0 +1,99 @@
+use crate::bounded::{RequestSender, ResponseFuture};
+use crate::error::RequestError;
+use crate::poll::PollRequestSender;
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tower_service::Service;
+
+/// A [`Service`] that sends its requests over a bounded bmrng channel
+///
+/// [`Service::poll_ready()`] waits for capacity in the request channel, and
+/// [`Service::call()`] resolves to the response sent by the [`Responder`](crate::Responder).
+#[derive(Debug)]
+pub struct ChannelService<Req, Res> {
+    sender: PollRequestSender<Req, Res>,
+}
+
+/// Future that resolves to the response of a [`ChannelService`] call
+#[derive(Debug)]
+pub struct ChannelFuture<Res> {
+    response: ResponseFuture<Res>,
+}
+
+impl<Req, Res> ChannelService<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    /// Creates a service sending its requests with `sender`
+    pub fn new(sender: RequestSender<Req, Res>) -> Self {
+        ChannelService {
+            sender: PollRequestSender::new(sender),
+        }
+    }
+
+    /// Returns the inner [`RequestSender`]
+    pub fn into_inner(self) -> RequestSender<Req, Res> {
+        self.sender
+            .into_inner()
+            .expect("the service never closes its sender")
+    }
+}
+
+impl<Req, Res> Service<Req> for ChannelService<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    type Response = Res;
+    type Error = RequestError<()>;
+    type Future = ChannelFuture<Res>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.sender
+            .poll_reserve(cx)
+            .map_err(|_| RequestError::SendError(()))
+    }
+
+    /// # Panics
+    ///
+    /// Panics if [`Service::poll_ready()`] did not return `Ready(Ok(()))` before
+    fn call(&mut self, request: Req) -> Self::Future {
+        match self.sender.send_item(request) {
+            Ok(receiver) => ChannelFuture {
+                response: receiver.into_future(),
+            },
+            Err(..) => panic!("poll_ready must be called before call"),
+        }
+    }
+}
+
+impl<Req, Res> Clone for ChannelService<Req, Res> {
+    fn clone(&self) -> Self {
+        ChannelService {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<Req, Res> From<RequestSender<Req, Res>> for ChannelService<Req, Res>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+{
+    fn from(sender: RequestSender<Req, Res>) -> Self {
+        ChannelService::new(sender)
+    }
+}
+
+impl<Res> Future for ChannelFuture<Res> {
+    type Output = Result<Res, RequestError<()>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.response)
+            .poll(cx)
+            .map(|result| result.map_err(|err| err.into()))
+    }
+}
@@ -1,21 +1,60 @@
-use crate::error::{RequestError, RespondError, SendError};
+#[cfg(feature = "tracing-error")]
+use crate::error::Traced;
+use crate::error::{
+    ReceiveError, RecvTimeoutError, RequestError, RetainedError, SendError, TryRecvError,
+};
 
-use crate::bounded::ResponseReceiver;
-use tokio::sync::{mpsc, oneshot};
+use crate::blocking::block_on_timeout;
+use crate::bounded::{
+    dead_letter_drained, drain_handlers, in_request_span, in_request_span_sync, new_payload,
+    new_payload_with_context, new_payload_with_ttl, record_handler, spawn_abortable, unexpired,
+    GuardedResponder, ReceiverHooks, Responder, ResponseReceiver,
+};
+use crate::merge::Merge;
+use crate::pause::PauseState;
+use crate::retry::{retry, retry_if, RetryPolicy};
+use crate::serve::{Outcome, ServeReport, ServeReporter};
+use crate::state::{ChannelState, RequestContext, RequestId, SenderId};
+use crate::{PauseHandle, Request};
+use tokio::sync::{mpsc, watch, Mutex};
+use tokio::task::{self, JoinHandle, JoinSet};
 use tokio::time::Duration;
+#[cfg(feature = "tokio-util")]
+use tokio_util::sync::CancellationToken;
 
 use futures_core::Stream;
-use std::pin::Pin;
-use std::task::{Context, Poll};
+use futures_util::future::{select, Either};
+use futures_util::FutureExt;
+use std::collections::VecDeque;
+use std::future::{poll_fn, Future};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::pin::{pin, Pin};
+use std::sync::Arc;
+use std::task::{ready, Context, Poll};
+use std::thread;
 
 /// The internal data sent in the MPSC request channel, a tuple that contains the request and the oneshot response channel responder
-pub type Payload<Req, Res> = (Req, UnboundedResponder<Res>);
+pub type Payload<Req, Res> = (Req, Responder<Res>);
 
 /// Send values to the associated [`UnboundedRequestReceiver`].
 #[derive(Debug)]
 pub struct UnboundedRequestSender<Req, Res> {
     request_sender: mpsc::UnboundedSender<Payload<Req, Res>>,
-    timeout_duration: Option<Duration>,
+    channel: Arc<ChannelState>,
+    id: Arc<SenderId>,
+}
+
+/// A sender that does not keep the channel open
+///
+/// The [`UnboundedRequestReceiver`] sees the channel closed once all the [`UnboundedRequestSender`] instances are
+/// dropped, even if some `WeakUnboundedRequestSender` instances are still alive.
+///
+/// Instances are created by calling [`UnboundedRequestSender::downgrade()`]
+#[derive(Debug)]
+pub struct WeakUnboundedRequestSender<Req, Res> {
+    request_sender: mpsc::WeakUnboundedSender<Payload<Req, Res>>,
+    channel: Arc<ChannelState>,
+    id: Arc<SenderId>,
 }
 
 /// Receive requests values from the associated [`UnboundedRequestSender`]
@@ -24,49 +63,297 @@ pub struct UnboundedRequestSender<Req, Res> {
 #[derive(Debug)]
 pub struct UnboundedRequestReceiver<Req, Res> {
     request_receiver: mpsc::UnboundedReceiver<Payload<Req, Res>>,
+    request_sender: mpsc::WeakUnboundedSender<Payload<Req, Res>>,
+    requeued_front: VecDeque<Payload<Req, Res>>,
+    requeued_back: VecDeque<Payload<Req, Res>>,
+    peeked: Option<Payload<Req, Res>>,
+    pub(crate) hooks: ReceiverHooks<Req, Res>,
+    pause: Arc<PauseState>,
+    channel: Arc<ChannelState>,
 }
 
-/// Send values back to the [`UnboundedRequestSender`] or [`UnboundedRequestReceiver`]
-///
-/// Instances are created by calling [`UnboundedRequestSender::send_receive()`] or [`UnboundedRequestSender::send()`]
-#[derive(Debug)]
-pub struct UnboundedResponder<Res> {
-    response_sender: oneshot::Sender<Res>,
-}
+/// The responder of the unbounded channel, the same type as the bounded [`Responder`]
+pub type UnboundedResponder<Res> = Responder<Res>;
+
+/// The guarded responder of the unbounded channel, the same type as the bounded [`GuardedResponder`]
+pub type GuardedUnboundedResponder<Res> = GuardedResponder<Res>;
 
 impl<Req, Res> UnboundedRequestSender<Req, Res> {
     fn new(
         request_sender: mpsc::UnboundedSender<Payload<Req, Res>>,
-        timeout_duration: Option<Duration>,
+        channel: Arc<ChannelState>,
     ) -> Self {
         UnboundedRequestSender {
             request_sender,
-            timeout_duration,
+            id: channel.next_sender_id(None),
+            channel,
         }
     }
 
+    /// Marks the payload as sent by this sender
+    fn stamp(&self, payload: &mut Payload<Req, Res>) {
+        payload.1.sender = Some(self.id.clone());
+    }
+
     /// Send a request over the MPSC channel, open the response channel
     /// Return the [`ResponseReceiver`] which can be used to wait for a response
     pub fn send(&self, request: Req) -> Result<ResponseReceiver<Res>, SendError<Req>> {
-        let (response_sender, response_receiver) = oneshot::channel::<Res>();
-        let responder = UnboundedResponder::new(response_sender);
-        let payload = (request, responder);
+        self.send_payload(new_payload(request, &self.channel))
+    }
+
+    /// Send a request over the MPSC channel with its own time-to-live, open the response channel
+    ///
+    /// If the request is still queued once `ttl` elapses, the receiver skips it and
+    /// the response receiver fails with [`ReceiveError::Expired`]. This overrides the
+    /// [time-to-live](crate::ChannelBuilder::ttl()) of the channel.
+    pub fn send_with_ttl(
+        &self,
+        request: Req,
+        ttl: Duration,
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_payload(new_payload_with_ttl(request, &self.channel, Some(ttl)))
+    }
+
+    /// Send a request over the MPSC channel with a user-provided context, open the
+    /// response channel
+    ///
+    /// Also see [`RequestSender::send_with_context()`](crate::RequestSender::send_with_context())
+    pub fn send_with_context(
+        &self,
+        request: Req,
+        context: RequestContext,
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        self.send_payload(new_payload_with_context(request, &self.channel, context))
+    }
+
+    fn send_payload(
+        &self,
+        (mut payload, receiver): (Payload<Req, Res>, ResponseReceiver<Res>),
+    ) -> Result<ResponseReceiver<Res>, SendError<Req>> {
+        if self.channel.is_closing() {
+            return Err(SendError(payload.0));
+        }
+        self.stamp(&mut payload);
         self.request_sender
             .send(payload)
             .map_err(|payload| SendError(payload.0 .0))?;
-        let receiver = ResponseReceiver::new(response_receiver, self.timeout_duration);
+        self.channel.add_depth(1);
         Ok(receiver)
     }
 
+    /// Puts a payload taken from the receiver back at the end of the queue, with its
+    /// original responder
+    ///
+    /// Also see [`RequestSender::requeue()`](crate::RequestSender::requeue()).
+    /// The payload is handed back if the channel is closed.
+    pub fn requeue(
+        &self,
+        mut payload: Payload<Req, Res>,
+    ) -> Result<(), SendError<Payload<Req, Res>>> {
+        if self.channel.is_closing() {
+            return Err(SendError(payload));
+        }
+        payload.1.attempt += 1;
+        self.request_sender.send(payload).map_err(|err| {
+            let mut payload = err.0;
+            payload.1.attempt -= 1;
+            SendError(payload)
+        })?;
+        self.channel.add_depth(1);
+        Ok(())
+    }
+
+    /// Send a request over the MPSC channel without opening a response channel
+    ///
+    /// Use this for requests that never need a response. The [`UnboundedResponder`]
+    /// of the request discards the response, see [`UnboundedResponder::expects_response()`].
+    pub fn send_forget(&self, request: Req) -> Result<(), SendError<Req>> {
+        if self.channel.is_closing() {
+            return Err(SendError(request));
+        }
+        let mut payload = (request, Responder::forgotten());
+        self.stamp(&mut payload);
+        self.request_sender
+            .send(payload)
+            .map_err(|payload| SendError(payload.0 .0))?;
+        self.channel.add_depth(1);
+        Ok(())
+    }
+
     /// Send a request over the MPSC channel, wait for the response and return it
     pub async fn send_receive(&self, request: Req) -> Result<Res, RequestError<Req>> {
         let mut receiver = self.send(request)?;
         receiver.recv().await.map_err(|err| err.into())
     }
 
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// capturing a span trace with the error if it fails
+    ///
+    /// The span trace of a failed response is captured in the span of the request.
+    #[cfg(feature = "tracing-error")]
+    pub async fn send_receive_traced(
+        &self,
+        request: Req,
+    ) -> Result<Res, Traced<RequestError<Req>>> {
+        let mut receiver = self
+            .send(request)
+            .map_err(|err| Traced::new(RequestError::from(err)))?;
+        receiver
+            .recv_traced()
+            .await
+            .map_err(|err| err.map(RequestError::from))
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// handing the part of the request picked by `retain` back with
+    /// [`RequestError::RecvTimeoutError`] if the response timeout elapses
+    ///
+    /// Also see [`RequestSender::send_receive_retained()`](crate::RequestSender::send_receive_retained()).
+    pub async fn send_receive_retained<R, F>(
+        &self,
+        request: Req,
+        retain: F,
+    ) -> Result<Res, RetainedError<Req, R>>
+    where
+        F: FnOnce(&Req) -> R,
+    {
+        let retained = retain(&request);
+        self.send_receive(request)
+            .await
+            .map_err(|error| match error {
+                RequestError::RecvTimeoutError(..) => RetainedError {
+                    error,
+                    retained: Some(retained),
+                },
+                error => RetainedError {
+                    error,
+                    retained: None,
+                },
+            })
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// sending the request again as the [`RetryPolicy`] allows when no response comes
+    ///
+    /// Every attempt sends a clone of `request`.
+    pub async fn send_receive_with_retry(
+        &self,
+        request: Req,
+        policy: &RetryPolicy,
+    ) -> Result<Res, RequestError<Req>>
+    where
+        Req: Clone,
+    {
+        retry(request, policy, |request| self.send_receive(request)).await
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// using `duration` as the response timeout instead of the one of the channel
+    pub async fn send_receive_timeout(
+        &self,
+        request: Req,
+        duration: Duration,
+    ) -> Result<Res, RequestError<Req>> {
+        let mut receiver = self.send(request)?;
+        receiver.set_timeout(Some(duration));
+        receiver.recv().await.map_err(|err| err.into())
+    }
+
+    /// Send a request over the MPSC channel, wait for the response and return it,
+    /// giving up as soon as `token` is cancelled
+    ///
+    /// Also see [`RequestSender::send_receive_with_token()`](crate::RequestSender::send_receive_with_token()).
+    #[cfg(feature = "tokio-util")]
+    pub async fn send_receive_with_token(
+        &self,
+        request: Req,
+        token: &CancellationToken,
+    ) -> Result<Res, RequestError<Req>> {
+        if token.is_cancelled() {
+            return Err(RequestError::Cancelled);
+        }
+        let mut receiver = self.send(request)?;
+        receiver
+            .recv_with_token(token)
+            .await
+            .map_err(|err| err.into())
+    }
+
+    /// Returns the name given to the channel with [`ChannelBuilder::name()`](crate::ChannelBuilder::name())
+    pub fn name(&self) -> Option<&str> {
+        self.channel.name.as_deref()
+    }
+
     /// Checks if the channel has been closed.
     pub fn is_closed(&self) -> bool {
-        self.request_sender.is_closed()
+        self.channel.is_closing() || self.request_sender.is_closed()
+    }
+
+    /// Cancels the request with the given id, sent by this sender or its clones
+    ///
+    /// Also see [`RequestSender::cancel()`](crate::RequestSender::cancel()).
+    pub fn cancel(&self, id: RequestId) -> bool {
+        self.channel.cancel(id)
+    }
+
+    /// Returns a watch receiver that sees the number of queued requests change
+    ///
+    /// Also see [`RequestSender::watch_depth()`](crate::RequestSender::watch_depth()).
+    pub fn watch_depth(&self) -> watch::Receiver<usize> {
+        self.channel.watch_depth()
+    }
+
+    /// Closes the channel for this sender and all its clones, without dropping them
+    ///
+    /// Also see [`RequestSender::close_channel()`](crate::RequestSender::close_channel()).
+    pub fn close_channel(&self) {
+        self.channel.close();
+    }
+
+    /// Waits until the receiver is dropped or closed
+    ///
+    /// Also see [`RequestSender::closed()`](crate::RequestSender::closed()).
+    pub async fn closed(&self) {
+        self.request_sender.closed().await
+    }
+
+    /// Returns the number of requests sent by this sender or its clones that are
+    /// still waiting for a response
+    ///
+    /// A request stops being counted once it is responded to, or once the
+    /// requesting side times out, cancels or drops its [`ResponseReceiver`].
+    pub fn pending_responses(&self) -> usize {
+        self.channel.in_flight()
+    }
+
+    /// Waits until every request sent by this sender or its clones has been
+    /// responded to, timed out or dropped
+    pub async fn await_idle(&self) {
+        self.channel.idle().await
+    }
+
+    /// Creates a clone of the sender whose requests carry the given label in their
+    /// [`SenderId`]
+    ///
+    /// Also see [`RequestSender::labeled()`](crate::RequestSender::labeled()).
+    pub fn labeled(&self, label: impl Into<String>) -> Self {
+        let mut sender = self.clone();
+        sender.id = sender.id.with_label(label.into());
+        sender
+    }
+
+    /// Returns the id of this sender, carried by the requests it sends
+    pub fn id(&self) -> &SenderId {
+        &self.id
+    }
+
+    /// Converts the sender into a [`WeakUnboundedRequestSender`] that does not keep the channel open
+    pub fn downgrade(&self) -> WeakUnboundedRequestSender<Req, Res> {
+        WeakUnboundedRequestSender {
+            request_sender: self.request_sender.downgrade(),
+            channel: self.channel.clone(),
+            id: self.id.clone(),
+        }
     }
 }
 
@@ -74,23 +361,621 @@ impl<Req, Res> Clone for UnboundedRequestSender<Req, Res> {
     fn clone(&self) -> Self {
         UnboundedRequestSender {
             request_sender: self.request_sender.clone(),
-            timeout_duration: self.timeout_duration,
+            channel: self.channel.clone(),
+            id: self.channel.next_sender_id(self.id.label.clone()),
         }
     }
 }
 
+impl<Req, Res> WeakUnboundedRequestSender<Req, Res> {
+    /// Tries to convert back into a [`UnboundedRequestSender`]
+    ///
+    /// Returns `None` if all the [`UnboundedRequestSender`] instances have been dropped.
+    pub fn upgrade(&self) -> Option<UnboundedRequestSender<Req, Res>> {
+        self.request_sender
+            .upgrade()
+            .map(|request_sender| UnboundedRequestSender {
+                request_sender,
+                channel: self.channel.clone(),
+                id: self.id.clone(),
+            })
+    }
+}
+
+impl<Req, Res> Clone for WeakUnboundedRequestSender<Req, Res> {
+    fn clone(&self) -> Self {
+        WeakUnboundedRequestSender {
+            request_sender: self.request_sender.clone(),
+            channel: self.channel.clone(),
+            id: self.id.clone(),
+        }
+    }
+}
+
+// The requeued payloads are never pinned, so the receiver can move freely
+impl<Req, Res> Unpin for UnboundedRequestReceiver<Req, Res> {}
+
 impl<Req, Res> UnboundedRequestReceiver<Req, Res> {
-    fn new(receiver: mpsc::UnboundedReceiver<Payload<Req, Res>>) -> Self {
+    fn new(
+        receiver: mpsc::UnboundedReceiver<Payload<Req, Res>>,
+        sender: mpsc::WeakUnboundedSender<Payload<Req, Res>>,
+        channel: Arc<ChannelState>,
+    ) -> Self {
         UnboundedRequestReceiver {
             request_receiver: receiver,
+            request_sender: sender,
+            requeued_front: VecDeque::new(),
+            requeued_back: VecDeque::new(),
+            peeked: None,
+            hooks: ReceiverHooks::<Req, Res>::default(),
+            pause: Arc::default(),
+            channel,
         }
     }
 
     /// Receives the next value for this receiver.
     pub async fn recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
-        match self.request_receiver.recv().await {
-            Some(payload) => Ok(payload),
-            None => Err(RequestError::RecvError),
+        loop {
+            self.pause.resumed().await;
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => match poll_fn(|cx| self.poll_recv_queued(cx)).await {
+                    Some(payload) => payload,
+                    None => return Err(RequestError::recv_error()),
+                },
+            };
+            if self.pause.is_paused() {
+                self.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives up to `limit` requests into `buffer`, waiting until at least one is available
+    ///
+    /// Returns the number of requests appended to `buffer`. It returns `0` only if
+    /// `limit` is `0`, or if the channel is closed and no requests are left.
+    pub async fn recv_many(&mut self, buffer: &mut Vec<Payload<Req, Res>>, limit: usize) -> usize {
+        if limit == 0 {
+            return 0;
+        }
+        let start = buffer.len();
+        loop {
+            self.pause.resumed().await;
+            let mut received = 0;
+            while received < limit {
+                match self.next_requeued() {
+                    Some(payload) => {
+                        buffer.push(payload);
+                        received += 1;
+                    }
+                    None => break,
+                }
+            }
+            if received == 0
+                && poll_fn(|cx| self.poll_recv_many_queued(cx, buffer, limit)).await == 0
+            {
+                return 0;
+            }
+            let payloads = buffer.split_off(start);
+            if self.pause.is_paused() {
+                for payload in payloads.into_iter().rev() {
+                    self.requeued_front.push_front(payload);
+                }
+                continue;
+            }
+            buffer.extend(
+                payloads
+                    .into_iter()
+                    .filter_map(|payload| unexpired(payload, &self.hooks)),
+            );
+            if buffer.len() > start {
+                return buffer.len() - start;
+            }
+        }
+    }
+
+    /// Tries to receive the next value for this receiver without waiting.
+    ///
+    /// Fails with [`TryRecvError::Empty`] if no request is queued, or
+    /// [`TryRecvError::Disconnected`] if the channel is closed and drained.
+    pub fn try_recv(&mut self) -> Result<Payload<Req, Res>, TryRecvError> {
+        if self.pause.is_paused() {
+            return Err(TryRecvError::Empty);
+        }
+        self.close_if_closing();
+        loop {
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => {
+                    let payload = self.request_receiver.try_recv()?;
+                    self.channel.add_depth(-1);
+                    payload
+                }
+            };
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives the next value for this receiver, waiting at most `duration` for it
+    ///
+    /// Also see [`RequestReceiver::recv_timeout()`](crate::RequestReceiver::recv_timeout()).
+    pub async fn recv_timeout(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Payload<Req, Res>, RecvTimeoutError> {
+        let channel = self.channel.clone();
+        match channel
+            .timeout_at(channel.now() + duration, self.recv())
+            .await
+        {
+            Some(Ok(payload)) => Ok(payload),
+            Some(Err(..)) => Err(RecvTimeoutError::Disconnected),
+            None => Err(RecvTimeoutError::Timeout),
+        }
+    }
+
+    /// Waits for the next request and returns a reference to it, without taking it
+    /// out of the queue
+    ///
+    /// Also see [`RequestReceiver::peek()`](crate::RequestReceiver::peek()).
+    pub async fn peek(&mut self) -> Result<&Req, RequestError<Req>> {
+        while !self.discard_stale() {
+            self.pause.resumed().await;
+            if !poll_fn(|cx| self.poll_peek_queued(cx)).await {
+                return Err(RequestError::recv_error());
+            }
+        }
+        Ok(self.peeked_request())
+    }
+
+    /// Returns a reference to the next request without waiting and without taking
+    /// it out of the queue
+    ///
+    /// Also see [`RequestReceiver::try_peek()`](crate::RequestReceiver::try_peek()).
+    pub fn try_peek(&mut self) -> Result<&Req, TryRecvError> {
+        while !self.discard_stale() {
+            if self.pause.is_paused() {
+                return Err(TryRecvError::Empty);
+            }
+            self.close_if_closing();
+            if !self.peek_requeued_back() {
+                self.peeked = Some(self.request_receiver.try_recv()?);
+            }
+        }
+        Ok(self.peeked_request())
+    }
+
+    /// Blocking receive to call outside of asynchronous contexts.
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn blocking_recv(&mut self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        loop {
+            if self.pause.is_paused() {
+                block_on_timeout(self.pause.resumed(), None);
+            }
+            self.close_if_closing();
+            let payload = match self.next_requeued() {
+                Some(payload) => payload,
+                None => match self.request_receiver.blocking_recv() {
+                    Some(payload) => {
+                        self.channel.add_depth(-1);
+                        payload
+                    }
+                    None => return Err(RequestError::recv_error()),
+                },
+            };
+            if self.pause.is_paused() {
+                self.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.hooks) {
+                return Ok(payload);
+            }
+        }
+    }
+
+    /// Receives the next request, processes it in place with `handler` and
+    /// responds with the value it returns
+    ///
+    /// The handler borrows the request instead of taking ownership of it, and the
+    /// request is returned once the response is sent, so large buffers can be reused.
+    pub async fn recv_with<F>(&mut self, handler: F) -> Result<Req, RequestError<Res>>
+    where
+        F: FnOnce(&mut Req) -> Res,
+    {
+        match self.recv().await {
+            Ok((mut request, responder)) => {
+                let response = handler(&mut request);
+                responder.respond(response)?;
+                Ok(request)
+            }
+            Err(..) => Err(RequestError::recv_error()),
+        }
+    }
+
+    /// Answers every request with the response of the async `handler`, until the channel closes
+    ///
+    /// The requests are handled one at a time. The returned future resolves to a
+    /// [`ServeReport`] once all the senders have been dropped and the queue is drained.
+    pub async fn serve<F, Fut>(mut self, mut handler: F) -> ServeReport
+    where
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = in_request_span(&responder, handler(request)).await;
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve()`](Self::serve()), but the handler also receives a clone of
+    /// `state` with every request
+    ///
+    /// Use an `Arc` or another cheaply cloneable handle to share a database pool or
+    /// a configuration between the handler invocations.
+    pub async fn serve_with_state<S, F, Fut>(self, state: S, mut handler: F) -> ServeReport
+    where
+        S: Clone,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        self.serve(|request| handler(state.clone(), request)).await
+    }
+
+    /// Like [`serve()`](Self::serve()), but a panic of the handler is caught and
+    /// the loop keeps serving the next requests
+    ///
+    /// The requesting side of the request that made the handler panic receives
+    /// [`RequestError::HandlerPanicked`]. The request is counted as panicked in
+    /// the [`ServeReport`].
+    pub async fn serve_catch_unwind<F, Fut>(mut self, mut handler: F) -> ServeReport
+    where
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = match catch_unwind(AssertUnwindSafe(|| handler(request))) {
+                Ok(response) => {
+                    AssertUnwindSafe(in_request_span(&responder, response))
+                        .catch_unwind()
+                        .await
+                }
+                Err(panic) => Err(panic),
+            };
+            match response {
+                Ok(response) => reporter.record(responder.respond_outcome(response)),
+                Err(..) => {
+                    responder.drop_with(ReceiveError::HandlerPanicked);
+                    reporter.record(Outcome::Panicked);
+                }
+            }
+        }
+        reporter.finish()
+    }
+
+    /// Answers the requests with the async `handler`, running up to `limit` handlers
+    /// concurrently, until the channel closes
+    ///
+    /// Every handler is spawned as a task on the current Tokio runtime. No new
+    /// request is received while `limit` handlers are in flight. The returned future
+    /// resolves to a [`ServeReport`] once the channel is closed and every handler
+    /// has finished. A handler that panics is counted as panicked.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent<F, Fut>(mut self, limit: usize, mut handler: F) -> ServeReport
+    where
+        Res: Send + 'static,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        loop {
+            while handlers.len() >= limit {
+                record_handler(&mut reporter, handlers.join_next().await);
+            }
+            let (request, responder) = match self.recv().await {
+                Ok(payload) => payload,
+                Err(..) => break,
+            };
+            let response = in_request_span(&responder, handler(request));
+            handlers.spawn(async move { responder.respond_outcome(response.await) });
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
+    }
+
+    /// Answers the requests with the async `handler` like
+    /// [`serve_concurrent()`](Self::serve_concurrent()), until `shutdown` completes
+    /// or the channel closes
+    ///
+    /// Once `shutdown` completes, the channel is closed and the requests still
+    /// waiting in it are answered with [`RequestError::ShutDown`]. The handlers in
+    /// flight are given `drain` to finish; the ones still running once it elapses are
+    /// dropped and their requests answered with [`RequestError::ShutDown`] as well.
+    /// Both are counted as shut down in the returned [`ServeReport`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_until_shutdown<F, Fut, S>(
+        mut self,
+        limit: usize,
+        shutdown: S,
+        drain: Duration,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+        S: Future<Output = ()>,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        let (abort, aborted) = watch::channel(false);
+        let mut shutdown = pin!(shutdown);
+        let shut_down = loop {
+            if handlers.len() >= limit {
+                match select(pin!(handlers.join_next()), shutdown.as_mut()).await {
+                    Either::Left((result, _)) => record_handler(&mut reporter, result),
+                    Either::Right(..) => break true,
+                }
+                continue;
+            }
+            match select(pin!(self.recv()), shutdown.as_mut()).await {
+                Either::Left((Ok((request, responder)), _)) => {
+                    let response = in_request_span(&responder, handler(request));
+                    spawn_abortable(&mut handlers, &aborted, responder, response);
+                }
+                Either::Left((Err(..), _)) => break false,
+                Either::Right(..) => break true,
+            }
+        };
+        if shut_down {
+            for (_, responder) in self.close_and_take().await {
+                responder.drop_with(ReceiveError::ShutDown);
+                reporter.record(Outcome::ShutDown);
+            }
+            drain_handlers(&self.channel, &mut handlers, &mut reporter, abort, drain).await;
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve_concurrent()`](Self::serve_concurrent()), but every spawned
+    /// handler receives its own clone of `state` with its request
+    ///
+    /// The state is cloned on the serving task before the handler is spawned, so
+    /// the handler future owns its clone and needs no `Arc` around the closure.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_state<S, F, Fut>(
+        self,
+        limit: usize,
+        state: S,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        S: Clone,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+    {
+        self.serve_concurrent(limit, |request| handler(state.clone(), request))
+            .await
+    }
+
+    /// Like [`serve_until_shutdown()`](Self::serve_until_shutdown()), but every
+    /// spawned handler receives its own clone of `state` with its request
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_until_shutdown_with_state<St, F, Fut, S>(
+        self,
+        limit: usize,
+        shutdown: S,
+        drain: Duration,
+        state: St,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Res: Send + 'static,
+        St: Clone,
+        F: FnMut(St, Req) -> Fut,
+        Fut: Future<Output = Res> + Send + 'static,
+        S: Future<Output = ()>,
+    {
+        self.serve_until_shutdown(limit, shutdown, drain, |request| {
+            handler(state.clone(), request)
+        })
+        .await
+    }
+
+    /// Puts a payload back at the front of the queue, so it is the next one to be received
+    ///
+    /// The attempt counter of the responder is incremented.
+    pub fn push_front(&mut self, mut payload: Payload<Req, Res>) {
+        payload.1.attempt += 1;
+        self.requeued_front.push_front(payload);
+    }
+
+    /// Puts a payload back at the end of the queue, to be received again after the
+    /// requests that are already queued
+    ///
+    /// The attempt counter of the responder is incremented.
+    pub fn push_back(&mut self, mut payload: Payload<Req, Res>) {
+        payload.1.attempt += 1;
+        let payload = match self.request_sender.upgrade() {
+            Some(sender) => match sender.send(payload) {
+                Ok(()) => {
+                    self.channel.add_depth(1);
+                    return;
+                }
+                Err(err) => err.0,
+            },
+            None => payload,
+        };
+        self.requeued_back.push_back(payload);
+    }
+
+    /// Closes the queue once a sender closed the channel, so the remaining requests
+    /// are still delivered before the receiver sees the channel closed
+    fn close_if_closing(&mut self) {
+        if self.channel.is_closing() {
+            self.request_receiver.close();
+        }
+    }
+
+    /// Polls the queue for the next request, closing it once a sender closed the channel
+    fn poll_recv_queued(&mut self, cx: &mut Context<'_>) -> Poll<Option<Payload<Req, Res>>> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        let payload = ready!(self.request_receiver.poll_recv(cx));
+        if payload.is_some() {
+            self.channel.add_depth(-1);
+        }
+        Poll::Ready(payload)
+    }
+
+    /// Polls the queue for up to `limit` requests, see [`poll_recv_queued()`](Self::poll_recv_queued())
+    fn poll_recv_many_queued(
+        &mut self,
+        cx: &mut Context<'_>,
+        buffer: &mut Vec<Payload<Req, Res>>,
+        limit: usize,
+    ) -> Poll<usize> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        let received = ready!(self.request_receiver.poll_recv_many(cx, buffer, limit));
+        self.channel.add_depth(-(received as isize));
+        Poll::Ready(received)
+    }
+
+    /// Polls the queue for the request that [`peek()`](Self::peek()) returns,
+    /// returning `false` once the channel is closed and drained
+    ///
+    /// The request stays counted in the depth of the channel until it is received.
+    fn poll_peek_queued(&mut self, cx: &mut Context<'_>) -> Poll<bool> {
+        if self.channel.poll_closing(cx) {
+            self.request_receiver.close();
+        }
+        if self.peek_requeued_back() {
+            return Poll::Ready(true);
+        }
+        self.peeked = ready!(self.request_receiver.poll_recv(cx));
+        Poll::Ready(self.peeked.is_some())
+    }
+
+    /// Takes the next request out of the channel for [`peek()`](Self::peek()), or the
+    /// first payload put back with [`push_back()`](Self::push_back()) once the channel
+    /// is drained, returning `false` if no payload was put back
+    fn peek_requeued_back(&mut self) -> bool {
+        if self.requeued_back.is_empty() {
+            return false;
+        }
+        match self.request_receiver.try_recv() {
+            Ok(payload) => self.peeked = Some(payload),
+            Err(..) => self.requeued_front.extend(self.requeued_back.pop_front()),
+        }
+        true
+    }
+
+    /// Discards the cancelled and expired requests in front of the queue the way
+    /// receiving does, returning `true` if the next request is already taken out
+    /// of the channel
+    fn discard_stale(&mut self) -> bool {
+        loop {
+            match self.requeued_front.front().or(self.peeked.as_ref()) {
+                Some((_, responder)) if responder.is_stale() => {
+                    if let Some(payload) = self.next_requeued() {
+                        unexpired(payload, &self.hooks);
+                    }
+                }
+                Some(..) => return true,
+                None => return false,
+            }
+        }
+    }
+
+    /// Returns the request taken out of the channel by [`peek()`](Self::peek())
+    fn peeked_request(&self) -> &Req {
+        match self.requeued_front.front().or(self.peeked.as_ref()) {
+            Some((request, _)) => request,
+            None => unreachable!("a request is peeked"),
+        }
+    }
+
+    /// Returns the next requeued payload that is due before the requests in the channel
+    ///
+    /// Payloads put back while no sender was left to enqueue them are only due
+    /// once the channel is drained.
+    fn next_requeued(&mut self) -> Option<Payload<Req, Res>> {
+        if let Some(payload) = self.requeued_front.pop_front() {
+            return Some(payload);
+        }
+        if let Some(payload) = self.peeked.take() {
+            self.channel.add_depth(-1);
+            return Some(payload);
+        }
+        if self.requeued_back.is_empty() {
+            return None;
+        }
+        match self.request_receiver.try_recv() {
+            Ok(payload) => {
+                self.channel.add_depth(-1);
+                Some(payload)
+            }
+            Err(..) => self.requeued_back.pop_front(),
+        }
+    }
+
+    /// Stops delivering requests until [`resume()`](Self::resume()) is called,
+    /// without closing the channel
+    ///
+    /// Also see [`RequestReceiver::pause()`](crate::RequestReceiver::pause()).
+    pub fn pause(&self) {
+        self.pause.pause();
+    }
+
+    /// Delivers requests again after [`pause()`](Self::pause())
+    pub fn resume(&self) {
+        self.pause.resume();
+    }
+
+    /// Returns `true` if the receiver is paused
+    pub fn is_paused(&self) -> bool {
+        self.pause.is_paused()
+    }
+
+    /// Returns a handle to pause and resume the receiver from another task
+    pub fn pause_handle(&self) -> PauseHandle {
+        PauseHandle {
+            state: self.pause.clone(),
         }
     }
 
@@ -99,6 +984,63 @@ impl<Req, Res> UnboundedRequestReceiver<Req, Res> {
         self.request_receiver.close()
     }
 
+    /// Closes the channel and rejects every request that is still waiting in it
+    ///
+    /// The responders of the remaining requests are dropped, so the requesting
+    /// sides resolve with [`RequestError::RecvError`] right away instead of waiting.
+    /// The requests are moved to the [dead-letter queue](crate::ChannelBuilder::dead_letters())
+    /// of the channel, if any. Returns the number of rejected requests.
+    pub async fn close_and_drain(&mut self) -> usize {
+        let drained = self.close_and_take().await;
+        let count = drained.len();
+        dead_letter_drained(drained, &self.hooks);
+        count
+    }
+
+    /// Closes the channel and takes every request that is still waiting in it
+    async fn close_and_take(&mut self) -> Vec<Payload<Req, Res>> {
+        self.close();
+        let mut queued: Vec<_> = self.requeued_front.drain(..).collect();
+        if let Some(payload) = self.peeked.take() {
+            self.channel.add_depth(-1);
+            queued.push(payload);
+        }
+        queued.extend(self.requeued_back.drain(..));
+        while let Some(payload) = self.request_receiver.recv().await {
+            self.channel.add_depth(-1);
+            queued.push(payload);
+        }
+        queued
+    }
+
+    /// Returns the number of requests waiting to be received, including the ones
+    /// that were put back with [`push_front()`](Self::push_front()) or
+    /// [`push_back()`](Self::push_back())
+    pub fn len(&self) -> usize {
+        self.request_receiver.len()
+            + self.requeued_front.len()
+            + usize::from(self.peeked.is_some())
+            + self.requeued_back.len()
+    }
+
+    /// Returns `true` if there are no requests waiting to be received
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a watch receiver that sees the number of queued requests change
+    ///
+    /// Also see [`RequestSender::watch_depth()`](crate::RequestSender::watch_depth()).
+    pub fn watch_depth(&self) -> watch::Receiver<usize> {
+        self.channel.watch_depth()
+    }
+
+    /// Converts this receiver into a [`SharedUnboundedRequestReceiver`] that can be cloned and shared
+    /// between several worker tasks
+    pub fn into_shared(self) -> SharedUnboundedRequestReceiver<Req, Res> {
+        self.into()
+    }
+
     /// Converts this receiver into a stream
     pub fn into_stream(self) -> impl Stream<Item = Payload<Req, Res>> {
         let stream: UnboundedRequestReceiverStream<Req, Res> = self.into();
@@ -106,19 +1048,130 @@ impl<Req, Res> UnboundedRequestReceiver<Req, Res> {
     }
 }
 
-impl<Res> UnboundedResponder<Res> {
-    fn new(response_sender: oneshot::Sender<Res>) -> Self {
-        Self { response_sender }
+impl<Req, T, E> UnboundedRequestReceiver<Req, Result<T, E>> {
+    /// Like [`serve()`](Self::serve()), but the handler is called again with a clone
+    /// of the request when it fails with an error that `should_retry` accepts, as
+    /// the [`RetryPolicy`] allows
+    ///
+    /// The last error is sent back once the handler runs out of attempts or fails
+    /// with an error not worth retrying, and the request is counted as failed in
+    /// the [`ServeReport`].
+    pub async fn serve_with_retry<P, F, Fut>(
+        mut self,
+        policy: RetryPolicy,
+        should_retry: P,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone,
+        P: Fn(&E) -> bool,
+        F: FnMut(Req) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = self.recv().await {
+            let response = retry_if(request, &policy, &should_retry, &mut handler);
+            let response = in_request_span(&responder, response).await;
+            reporter.record(responder.respond_result_outcome(response));
+        }
+        reporter.finish()
+    }
+
+    /// Like [`serve_concurrent()`](Self::serve_concurrent()), but the handler is
+    /// retried like in [`serve_with_retry()`](Self::serve_with_retry())
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_retry<P, F, Fut>(
+        mut self,
+        limit: usize,
+        policy: RetryPolicy,
+        should_retry: P,
+        handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+        P: Fn(&E) -> bool + Send + Sync + 'static,
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        assert!(limit > 0, "the concurrency limit must be greater than 0");
+        let should_retry = Arc::new(should_retry);
+        let handler = Arc::new(handler);
+        let mut reporter = ServeReporter::start();
+        let mut handlers = JoinSet::new();
+        loop {
+            while handlers.len() >= limit {
+                record_handler(&mut reporter, handlers.join_next().await);
+            }
+            let (request, responder) = match self.recv().await {
+                Ok(payload) => payload,
+                Err(..) => break,
+            };
+            let (should_retry, handler) = (should_retry.clone(), handler.clone());
+            let response = in_request_span(&responder, async move {
+                retry_if(request, &policy, &*should_retry, |request| handler(request)).await
+            });
+            handlers.spawn(async move { responder.respond_result_outcome(response.await) });
+        }
+        while let Some(result) = handlers.join_next().await {
+            record_handler(&mut reporter, Some(result));
+        }
+        reporter.finish()
     }
 
-    /// Responds a request from the [`UnboundedRequestSender`] which finishes the request
-    pub fn respond(self, response: Res) -> Result<(), RespondError<Res>> {
-        self.response_sender.send(response).map_err(RespondError)
+    /// Like [`serve_with_retry()`](Self::serve_with_retry()), but every attempt of
+    /// the handler receives a clone of `state` with its request
+    pub async fn serve_with_retry_and_state<S, P, F, Fut>(
+        self,
+        state: S,
+        policy: RetryPolicy,
+        should_retry: P,
+        mut handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone,
+        S: Clone,
+        P: Fn(&E) -> bool,
+        F: FnMut(S, Req) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.serve_with_retry(policy, should_retry, |request| {
+            handler(state.clone(), request)
+        })
+        .await
     }
 
-    /// Checks if the associated receiver handle for the response listener has been dropped.
-    pub fn is_closed(&self) -> bool {
-        self.response_sender.is_closed()
+    /// Like [`serve_concurrent_with_retry()`](Self::serve_concurrent_with_retry()),
+    /// but every attempt of a spawned handler receives its own clone of `state`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` is 0, or if called outside of a Tokio runtime
+    pub async fn serve_concurrent_with_retry_and_state<S, P, F, Fut>(
+        self,
+        limit: usize,
+        state: S,
+        policy: RetryPolicy,
+        should_retry: P,
+        handler: F,
+    ) -> ServeReport
+    where
+        Req: Clone + Send + 'static,
+        T: Send + 'static,
+        E: Send + 'static,
+        S: Clone + Send + Sync + 'static,
+        P: Fn(&E) -> bool + Send + Sync + 'static,
+        F: Fn(S, Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+    {
+        self.serve_concurrent_with_retry(limit, policy, should_retry, move |request| {
+            handler(state.clone(), request)
+        })
+        .await
     }
 }
 
@@ -129,10 +1182,22 @@ impl<Res> UnboundedResponder<Res> {
 pub fn channel<Req, Res>() -> (
     UnboundedRequestSender<Req, Res>,
     UnboundedRequestReceiver<Req, Res>,
+) {
+    channel_with_state(ChannelState::new(None))
+}
+
+/// Creates an unbounded channel whose senders share the given channel state
+pub(crate) fn channel_with_state<Req, Res>(
+    channel: ChannelState,
+) -> (
+    UnboundedRequestSender<Req, Res>,
+    UnboundedRequestReceiver<Req, Res>,
 ) {
     let (sender, receiver) = mpsc::unbounded_channel::<Payload<Req, Res>>();
-    let request_sender = UnboundedRequestSender::new(sender, None);
-    let request_receiver = UnboundedRequestReceiver::new(receiver);
+    let channel = Arc::new(channel);
+    let request_receiver =
+        UnboundedRequestReceiver::new(receiver, sender.downgrade(), channel.clone());
+    let request_sender = UnboundedRequestSender::new(sender, channel);
     (request_sender, request_receiver)
 }
 
@@ -146,10 +1211,81 @@ pub fn channel_with_timeout<Req, Res>(
     UnboundedRequestSender<Req, Res>,
     UnboundedRequestReceiver<Req, Res>,
 ) {
-    let (sender, receiver) = mpsc::unbounded_channel::<Payload<Req, Res>>();
-    let request_sender = UnboundedRequestSender::new(sender, Some(timeout_duration));
-    let request_receiver = UnboundedRequestReceiver::new(receiver);
-    (request_sender, request_receiver)
+    channel_with_state(ChannelState::new(Some(timeout_duration)))
+}
+
+/// Creates an unbounded mpsc request-response channel for the [`Request`] type `R`
+///
+/// The response timeout is taken from [`Request::TIMEOUT`].
+pub fn typed_channel<R: Request>() -> (
+    UnboundedRequestSender<R, R::Response>,
+    UnboundedRequestReceiver<R, R::Response>,
+) {
+    channel_with_state(ChannelState::new(R::TIMEOUT))
+}
+
+/// Merges the requests of several unbounded receivers into one stream
+///
+/// Also see [`bmrng::merge()`](crate::merge())
+pub fn merge<Req, Res>(
+    receivers: impl IntoIterator<Item = UnboundedRequestReceiver<Req, Res>>,
+) -> Merge<UnboundedRequestReceiverStream<Req, Res>> {
+    Merge::new(
+        receivers
+            .into_iter()
+            .map(UnboundedRequestReceiverStream::new),
+    )
+}
+
+/// Answers the requests of the receiver with a synchronous handler running on
+/// Tokio's blocking thread pool, until the channel closes
+///
+/// Use this for CPU-heavy or FFI-bound handlers that must not block the async workers.
+/// The task resolves to a [`ServeReport`] once the channel is closed.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime
+pub fn spawn_blocking_handler<Req, Res, F>(
+    mut receiver: UnboundedRequestReceiver<Req, Res>,
+    mut handler: F,
+) -> JoinHandle<ServeReport>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+    F: FnMut(Req) -> Res + Send + 'static,
+{
+    task::spawn_blocking(move || {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = receiver.blocking_recv() {
+            let response = in_request_span_sync(&responder, || handler(request));
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    })
+}
+
+/// Answers the requests of the receiver with a synchronous handler running on
+/// a dedicated OS thread, until the channel closes
+///
+/// The thread returns a [`ServeReport`] once the channel is closed.
+pub fn spawn_thread_handler<Req, Res, F>(
+    mut receiver: UnboundedRequestReceiver<Req, Res>,
+    mut handler: F,
+) -> thread::JoinHandle<ServeReport>
+where
+    Req: Send + 'static,
+    Res: Send + 'static,
+    F: FnMut(Req) -> Res + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut reporter = ServeReporter::start();
+        while let Ok((request, responder)) = receiver.blocking_recv() {
+            let response = in_request_span_sync(&responder, || handler(request));
+            reporter.record(responder.respond_outcome(response));
+        }
+        reporter.finish()
+    })
 }
 
 /// A wrapper around [`UnboundedRequestReceiver`] that implements [`Stream`].
@@ -181,7 +1317,23 @@ impl<Req, Res> Stream for UnboundedRequestReceiverStream<Req, Res> {
     type Item = Payload<Req, Res>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        self.inner.request_receiver.poll_recv(cx)
+        loop {
+            ready!(self.inner.pause.poll_resumed(cx));
+            let payload = match self.inner.next_requeued() {
+                Some(payload) => payload,
+                None => match self.inner.poll_recv_queued(cx) {
+                    Poll::Ready(Some(payload)) => payload,
+                    poll => return poll,
+                },
+            };
+            if self.inner.pause.is_paused() {
+                self.inner.requeued_front.push_front(payload);
+                continue;
+            }
+            if let Some(payload) = unexpired(payload, &self.inner.hooks) {
+                return Poll::Ready(Some(payload));
+            }
+        }
     }
 }
 
@@ -210,3 +1362,50 @@ impl<Req, Res> From<UnboundedRequestReceiver<Req, Res>>
         UnboundedRequestReceiverStream::new(receiver)
     }
 }
+
+/// A wrapper around [`UnboundedRequestReceiver`] that can be cloned, so several worker tasks can
+/// take requests from the same queue
+///
+/// Every request is received by exactly one of the clones. Instances are created
+/// by calling [`UnboundedRequestReceiver::into_shared()`].
+#[derive(Debug)]
+pub struct SharedUnboundedRequestReceiver<Req, Res> {
+    inner: Arc<Mutex<UnboundedRequestReceiver<Req, Res>>>,
+}
+
+impl<Req, Res> SharedUnboundedRequestReceiver<Req, Res> {
+    /// Receives the next value for this receiver.
+    ///
+    /// The workers waiting on the clones of this receiver are served in turn.
+    pub async fn recv(&self) -> Result<Payload<Req, Res>, RequestError<Req>> {
+        self.inner.lock().await.recv().await
+    }
+
+    /// Tries to receive the next value for this receiver without waiting.
+    ///
+    /// Fails with [`TryRecvError::Empty`] while another clone is waiting for a request.
+    pub fn try_recv(&self) -> Result<Payload<Req, Res>, TryRecvError> {
+        match self.inner.try_lock() {
+            Ok(mut receiver) => receiver.try_recv(),
+            Err(..) => Err(TryRecvError::Empty),
+        }
+    }
+}
+
+impl<Req, Res> Clone for SharedUnboundedRequestReceiver<Req, Res> {
+    fn clone(&self) -> Self {
+        SharedUnboundedRequestReceiver {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<Req, Res> From<UnboundedRequestReceiver<Req, Res>>
+    for SharedUnboundedRequestReceiver<Req, Res>
+{
+    fn from(receiver: UnboundedRequestReceiver<Req, Res>) -> Self {
+        SharedUnboundedRequestReceiver {
+            inner: Arc::new(Mutex::new(receiver)),
+        }
+    }
+}
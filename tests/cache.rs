@@ -0,0 +1,73 @@
+use bmrng::cache::CachedRequestSender;
+use bmrng::error::RequestError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::time::{advance, pause, Duration};
+
+fn counting_sender(calls: Arc<AtomicUsize>) -> bmrng::RequestSender<i32, i32> {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(rx.serve(move |input| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        async move { input * 2 }
+    }));
+    tx
+}
+
+#[tokio::test]
+async fn cache_serves_repeated_requests() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tx = CachedRequestSender::new(counting_sender(calls.clone()), 8, Duration::from_secs(60));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(tx.clone().send_receive(1).await, Ok(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(tx.send_receive(2).await, Ok(4));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(tx.len(), 2);
+
+    assert!(tx.invalidate(&1));
+    assert!(!tx.invalidate(&1));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    tx.clear();
+    assert!(tx.is_empty());
+}
+
+#[tokio::test]
+async fn cache_expires_after_ttl() {
+    pause();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tx = CachedRequestSender::new(counting_sender(calls.clone()), 8, Duration::from_secs(1));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    advance(Duration::from_millis(500)).await;
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    advance(Duration::from_millis(600)).await;
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn cache_evicts_least_recently_used() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tx = CachedRequestSender::new(counting_sender(calls.clone()), 2, Duration::from_secs(60));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(tx.send_receive(2).await, Ok(4));
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(tx.send_receive(3).await, Ok(6));
+    assert_eq!(tx.len(), 2);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(tx.send_receive(2).await, Ok(4));
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn cache_does_not_keep_errors() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    drop(rx);
+    let tx = CachedRequestSender::new(tx, 2, Duration::from_secs(60));
+    assert_eq!(tx.send_receive(1).await, Err(RequestError::SendError(1)));
+    assert!(tx.is_empty());
+}
@@ -0,0 +1,118 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::{CircuitBreaker, CircuitBreakerSender, CircuitState, Request};
+use tokio::time::{advance, pause, resume, Duration};
+
+#[derive(Debug, PartialEq)]
+struct Call(i32);
+
+impl Request for Call {
+    type Response = i32;
+    const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn circuit_opens_and_recovers() {
+    pause();
+    let (tx, mut rx) = bmrng::typed_channel::<Call>(4);
+    let breaker = CircuitBreaker::new()
+        .window(4)
+        .failure_rate(0.5)
+        .cooldown(Duration::from_secs(1));
+    let tx = CircuitBreakerSender::new(tx, breaker);
+    tokio::spawn(async move {
+        while let Ok((Call(input), responder)) = rx.recv().await {
+            match input {
+                0 => drop(responder),
+                // Never responds, so the request times out
+                1 => std::mem::forget(responder),
+                _ => responder.respond(input).unwrap(),
+            }
+        }
+    });
+
+    assert_eq!(tx.send_receive(Call(2)).await, Ok(2));
+    assert_eq!(
+        tx.send_receive(Call(0)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(tx.send_receive(Call(3)).await, Ok(3));
+    assert_eq!(tx.state(), CircuitState::Closed);
+    assert_eq!(
+        tx.send_receive(Call(1)).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert_eq!(tx.state(), CircuitState::Open);
+    assert_eq!(
+        tx.send_receive(Call(4)).await,
+        Err(RequestError::CircuitOpen(Call(4)))
+    );
+    assert_eq!(
+        tx.clone().send_receive(Call(5)).await,
+        Err(RequestError::CircuitOpen(Call(5)))
+    );
+    assert_eq!(tx.inner().send_receive(Call(6)).await, Ok(6));
+
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(tx.state(), CircuitState::HalfOpen);
+    assert_eq!(
+        tx.send_receive(Call(0)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(tx.state(), CircuitState::Open);
+
+    advance(Duration::from_secs(1)).await;
+    assert_eq!(tx.send_receive(Call(7)).await, Ok(7));
+    assert_eq!(tx.state(), CircuitState::Closed);
+    assert_eq!(
+        tx.send_receive(Call(0)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(tx.state(), CircuitState::Closed);
+    resume();
+}
+
+#[tokio::test]
+async fn circuit_half_open_lets_one_trial_through() {
+    pause();
+    let (tx, mut rx) = bmrng::typed_channel::<Call>(4);
+    let breaker = CircuitBreaker::new()
+        .window(1)
+        .cooldown(Duration::from_millis(100));
+    let tx = CircuitBreakerSender::new(tx, breaker);
+    let failing = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Call(1)).await }
+    });
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(responder);
+    assert_eq!(
+        failing.await.unwrap(),
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    advance(Duration::from_millis(100)).await;
+
+    let trial = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Call(2)).await }
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(tx.state(), CircuitState::Open);
+    assert_eq!(
+        tx.send_receive(Call(3)).await,
+        Err(RequestError::CircuitOpen(Call(3)))
+    );
+    trial.abort();
+    let _ = trial.await;
+    assert_eq!(tx.state(), CircuitState::HalfOpen);
+
+    let trial = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Call(4)).await }
+    });
+    drop((input, responder));
+    let (Call(input), responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(trial.await.unwrap(), Ok(4));
+    assert_eq!(tx.state(), CircuitState::Closed);
+    resume();
+}
@@ -0,0 +1,90 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::{CoalescingSender, Request};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Lookup(u32);
+
+impl Request for Lookup {
+    type Response = String;
+}
+
+fn spawn_lookup(calls: Arc<AtomicUsize>, delay: Duration) -> CoalescingSender<Lookup> {
+    let (tx, rx) = bmrng::typed_channel::<Lookup>(4);
+    tokio::spawn(rx.serve(move |Lookup(id)| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        async move {
+            sleep(delay).await;
+            format!("user {}", id)
+        }
+    }));
+    CoalescingSender::new(tx)
+}
+
+#[tokio::test]
+async fn coalesce_identical_requests() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tx = spawn_lookup(calls.clone(), Duration::from_millis(50));
+    let tasks: Vec<_> = (0..5)
+        .map(|_| {
+            let tx = tx.clone();
+            tokio::spawn(async move { tx.send_receive(Lookup(7)).await })
+        })
+        .collect();
+    let other = tx.send_receive(Lookup(8));
+    assert_eq!(other.await, Ok("user 8".to_string()));
+    for task in tasks {
+        assert_eq!(task.await.unwrap(), Ok("user 7".to_string()));
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(tx.in_flight(), 0);
+
+    assert_eq!(tx.send_receive(Lookup(7)).await, Ok("user 7".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn coalesce_shares_errors() {
+    let (tx, mut rx) = bmrng::typed_channel::<Lookup>(4);
+    let tx = CoalescingSender::new(tx);
+    let first = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Lookup(1)).await }
+    });
+    let (_, responder) = rx.recv().await.unwrap();
+    let second = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Lookup(1)).await }
+    });
+    sleep(Duration::from_millis(10)).await;
+    drop(responder);
+    assert_eq!(
+        first.await.unwrap(),
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(
+        second.await.unwrap(),
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn coalesce_cancelled_leader() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let tx = spawn_lookup(calls.clone(), Duration::from_millis(20));
+    let leader = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Lookup(3)).await }
+    });
+    sleep(Duration::from_millis(5)).await;
+    let follower = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(Lookup(3)).await }
+    });
+    sleep(Duration::from_millis(5)).await;
+    leader.abort();
+    assert_eq!(follower.await.unwrap(), Ok("user 3".to_string()));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,129 @@
+use bmrng::dead_letter::DeadLetterReason;
+use bmrng::error::{ReceiveError, RequestError};
+use bmrng::Admission;
+use tokio::time::{advance, pause, resume, Duration, Instant};
+
+#[tokio::test]
+async fn dead_letter_expired_requests() {
+    pause();
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .ttl(Duration::from_millis(50))
+        .dead_letters(dead_letters)
+        .build();
+    let sent_at = Instant::now();
+    let stale = tx.send(1).await.unwrap();
+    advance(Duration::from_millis(100)).await;
+    let fresh = tx.send(2).await.unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    responder.respond(input).unwrap();
+    assert_eq!(stale.await, Err(ReceiveError::Expired));
+    assert_eq!(fresh.await, Ok(2));
+
+    let letter = janitor.try_recv().unwrap();
+    assert_eq!(letter.reason(), DeadLetterReason::Expired);
+    assert_eq!(letter.id().map(|id| id.as_u64()), Some(1));
+    assert_eq!(letter.sent_at(), sent_at);
+    assert_eq!(letter.dead_at(), sent_at + Duration::from_millis(100));
+    assert_eq!(letter.into_request(), Some(1));
+    assert!(janitor.is_empty());
+    resume();
+}
+
+#[tokio::test]
+async fn dead_letter_unanswered_requests() {
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .dead_letters(dead_letters)
+        .build_unbounded();
+    let dropped = tx.send(1).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(responder);
+    assert_eq!(dropped.await, Err(ReceiveError::RecvError));
+    let answered = tx.send(2).unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(answered.await, Ok(2));
+
+    assert_eq!(janitor.len(), 1);
+    let letter = janitor.recv().await.unwrap();
+    assert_eq!(letter.reason(), DeadLetterReason::Unanswered);
+    assert_eq!(letter.id().map(|id| id.as_u64()), Some(1));
+    assert_eq!(letter.request(), None);
+    drop((tx, rx));
+    assert!(janitor.recv().await.is_none());
+}
+
+#[tokio::test]
+async fn dead_letter_rejected_requests() {
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(Admission::new().max_depth(0))
+        .dead_letters(dead_letters)
+        .build();
+    let _queued = tx.send(1).await.unwrap();
+    let weak = tx.downgrade();
+    assert_eq!(tx.send_receive(2).await, Err(RequestError::Rejected(2)));
+    let upgraded = weak.upgrade().unwrap();
+    assert_eq!(
+        upgraded.send_receive(3).await,
+        Err(RequestError::Rejected(3))
+    );
+
+    let letter = janitor.try_recv().unwrap();
+    assert_eq!(letter.reason(), DeadLetterReason::Rejected);
+    assert_eq!(letter.id(), None);
+    assert_eq!(letter.into_request(), Some(2));
+    assert_eq!(janitor.try_recv().unwrap().into_request(), Some(3));
+    assert!(janitor.try_recv().is_none());
+}
+
+#[tokio::test]
+async fn dead_letter_rejected_by_every_send() {
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(Admission::new().max_depth(0))
+        .dead_letters(dead_letters)
+        .build();
+    let _queued = tx.send(1).await.unwrap();
+    assert!(tx.send(2).await.is_err());
+    assert!(tx
+        .send_with_context(3, bmrng::RequestContext::new())
+        .await
+        .is_err());
+    assert!(tx.send_with_ttl(4, Duration::from_secs(1)).await.is_err());
+    assert!(tx.send_timeout(5, Duration::from_secs(1)).await.is_err());
+    assert!(tx.try_send(6).is_err());
+    assert!(tx.send_forget(7).await.is_err());
+
+    let mut rejected = Vec::new();
+    while let Some(letter) = janitor.try_recv() {
+        assert_eq!(letter.reason(), DeadLetterReason::Rejected);
+        rejected.extend(letter.into_request());
+    }
+    assert_eq!(rejected, vec![2, 3, 4, 5, 6, 7]);
+}
+
+#[tokio::test]
+async fn dead_letter_drained_requests() {
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .dead_letters(dead_letters)
+        .build();
+    let first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert_eq!(rx.close_and_drain().await, 2);
+    assert_eq!(first.await, Err(ReceiveError::RecvError));
+
+    let letter = janitor.try_recv().unwrap();
+    assert_eq!(letter.reason(), DeadLetterReason::Drained);
+    assert_eq!(letter.id().map(|id| id.as_u64()), Some(1));
+    assert_eq!(letter.into_request(), Some(1));
+    assert_eq!(janitor.try_recv().unwrap().into_request(), Some(2));
+    assert!(janitor.try_recv().is_none());
+}
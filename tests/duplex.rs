@@ -0,0 +1,82 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::Request;
+use tokio::time::{advance, pause, resume, Duration};
+
+#[derive(Debug, PartialEq)]
+struct Question(u32);
+
+impl Request for Question {
+    type Response = u32;
+}
+
+#[derive(Debug, PartialEq)]
+struct Notice(&'static str);
+
+impl Request for Notice {
+    type Response = ();
+    const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn duplex_both_directions() {
+    let (mut left, mut right) = bmrng::duplex::<Question, Notice>(1);
+    let task = tokio::spawn(async move {
+        let (Question(n), responder) = right.recv().await.expect("Unexpected err");
+        let _ = responder.respond(n + 1);
+        right.send_receive(Notice("done")).await
+    });
+    assert_eq!(left.send_receive(Question(41)).await, Ok(42));
+    let (notice, responder) = left.recv().await.expect("Unexpected err");
+    assert_eq!(notice, Notice("done"));
+    let _ = responder.respond(());
+    assert_eq!(task.await.expect("Unexpected err"), Ok(()));
+}
+
+#[tokio::test]
+async fn duplex_timeouts_per_request_type() {
+    let (mut left, right) = bmrng::duplex::<Question, Notice>(1);
+    pause();
+    let task = tokio::spawn(async move { right.send_receive(Notice("late")).await });
+    let (_notice, _responder) = left.recv().await.expect("Unexpected err");
+    advance(Duration::from_millis(101)).await;
+    resume();
+    assert_eq!(
+        task.await.expect("Unexpected err"),
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn duplex_with_timeout_applies_to_both() {
+    let timeout = Duration::from_millis(10);
+    let (mut left, mut right) = bmrng::duplex_with_timeout::<Question, Notice>(1, timeout);
+    pause();
+    let left_sender = left.sender().clone();
+    let right_sender = right.sender().clone();
+    let question = tokio::spawn(async move { left_sender.send_receive(Question(1)).await });
+    let notice = tokio::spawn(async move { right_sender.send_receive(Notice("late")).await });
+    let (_question, _responder) = right.recv().await.expect("Unexpected err");
+    let (_notice, _responder) = left.recv().await.expect("Unexpected err");
+    advance(Duration::from_millis(11)).await;
+    resume();
+    assert_eq!(
+        question.await.expect("Unexpected err"),
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert_eq!(
+        notice.await.expect("Unexpected err"),
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn duplex_into_parts() {
+    let (left, right) = bmrng::duplex::<Question, Notice>(1);
+    let (left_sender, _left_receiver) = left.into_parts();
+    let (_right_sender, mut right_receiver) = right.into_parts();
+    tokio::spawn(async move {
+        let (Question(n), responder) = right_receiver.recv().await.expect("Unexpected err");
+        let _ = responder.respond(n * 2);
+    });
+    assert_eq!(left_sender.send_receive(Question(4)).await, Ok(8));
+}
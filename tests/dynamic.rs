@@ -0,0 +1,102 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::metrics::TypeStats;
+use bmrng::Request;
+use std::sync::Arc;
+use tokio::time::{pause, resume, sleep, Duration};
+
+#[derive(Debug, PartialEq)]
+struct Add(i32, i32);
+
+impl Request for Add {
+    type Response = i32;
+}
+
+#[derive(Debug, PartialEq)]
+struct Greet(&'static str);
+
+impl Request for Greet {
+    type Response = String;
+    const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn dynamic_dispatch() {
+    let (tx, mut rx) = bmrng::dynamic::channel(4);
+    tokio::spawn(async move {
+        while let Ok(payload) = rx.recv().await {
+            let payload = match payload.downcast::<Add>() {
+                Ok((Add(a, b), responder)) => {
+                    let _ = responder.respond(a + b);
+                    continue;
+                }
+                Err(payload) => payload,
+            };
+            assert!(payload.is::<Greet>());
+            assert!(payload.type_name().ends_with("Greet"));
+            if let Ok((Greet(name), responder)) = payload.downcast::<Greet>() {
+                let _ = responder.respond(format!("hello {}", name));
+            }
+        }
+    });
+    assert_eq!(tx.send_receive(Add(1, 2)).await, Ok(3));
+    assert_eq!(
+        tx.send_receive(Greet("bmrng")).await,
+        Ok("hello bmrng".to_string())
+    );
+}
+
+#[tokio::test]
+async fn dynamic_request_timeout_and_closed() {
+    pause();
+    let (tx, mut rx) = bmrng::dynamic::channel(4);
+    tokio::spawn(async move {
+        while let Ok(payload) = rx.recv().await {
+            let _responder = payload.into_responder();
+            sleep(Duration::from_millis(200)).await;
+        }
+    });
+    assert_eq!(
+        tx.send_receive(Greet("slow")).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    resume();
+
+    let (tx, rx) = bmrng::dynamic::channel(1);
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(
+        tx.send_receive(Add(1, 1)).await,
+        Err(RequestError::SendError(Add(1, 1)))
+    );
+}
+
+#[tokio::test]
+async fn dynamic_metrics_per_type() {
+    let stats = Arc::new(TypeStats::new());
+    let (tx, mut rx) = bmrng::dynamic::channel_with_metrics(4, stats.clone());
+    tokio::spawn(async move {
+        while let Ok(payload) = rx.recv().await {
+            match payload.downcast::<Add>() {
+                Ok((Add(a, b), responder)) => {
+                    let _ = responder.respond(a + b);
+                }
+                Err(payload) => drop(payload),
+            }
+        }
+    });
+    assert_eq!(tx.send_receive(Add(1, 2)).await, Ok(3));
+    assert_eq!(tx.send_receive(Add(2, 2)).await, Ok(4));
+    assert_eq!(
+        tx.send_receive(Greet("bmrng")).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+
+    let add = stats.of::<Add>().unwrap();
+    assert_eq!((add.sent(), add.received(), add.responded()), (2, 2, 2));
+    assert_eq!(add.latency().count(), 2);
+    let greet = stats.of::<Greet>().unwrap();
+    assert_eq!((greet.sent(), greet.received(), greet.dropped()), (1, 1, 1));
+    assert_eq!(greet.responded(), 0);
+    assert_eq!(stats.type_names().len(), 2);
+    assert!(stats.get(std::any::type_name::<Add>()).is_some());
+}
@@ -0,0 +1,84 @@
+use bmrng::error::{RequestError, SendError};
+use tokio::time::{timeout, Duration};
+
+#[tokio::test]
+async fn fair_round_robin() {
+    let (chatty, mut rx) = bmrng::fair::channel::<i32, i32>(8);
+    let quiet = chatty.clone();
+    let other = chatty.clone();
+    for input in 1..=4 {
+        let _response = chatty.send(input).await.unwrap();
+    }
+    let _response = quiet.send(10).await.unwrap();
+    let _response = other.send(20).await.unwrap();
+    let _response = other.send(21).await.unwrap();
+    assert_eq!(rx.len(), 7);
+    assert_eq!(chatty.len(), 4);
+
+    let mut order = Vec::new();
+    while !rx.is_empty() {
+        let (input, _responder) = rx.recv().await.unwrap();
+        order.push(input);
+    }
+    assert_eq!(order, vec![1, 10, 20, 2, 21, 3, 4]);
+}
+
+#[tokio::test]
+async fn fair_weights() {
+    let (tx, mut rx) = bmrng::fair::channel::<i32, i32>(8);
+    let heavy = tx.clone_with_weight(3);
+    assert_eq!(heavy.weight(), 3);
+    for input in 1..=3 {
+        let _response = tx.send(input).await.unwrap();
+    }
+    for input in 10..=15 {
+        let _response = heavy.send(input).await.unwrap();
+    }
+    let mut order = Vec::new();
+    while !rx.is_empty() {
+        let (input, _responder) = rx.recv().await.unwrap();
+        order.push(input);
+    }
+    assert_eq!(order, vec![1, 10, 11, 12, 2, 13, 14, 15, 3]);
+}
+
+#[tokio::test]
+async fn fair_lane_capacity() {
+    let (chatty, mut rx) = bmrng::fair::channel::<i32, i32>(1);
+    let quiet = chatty.clone();
+    let _response = chatty.send(1).await.unwrap();
+    assert!(timeout(Duration::from_millis(10), chatty.send(2))
+        .await
+        .is_err());
+    let _response = quiet.send(3).await.unwrap();
+
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 1);
+    let request = tokio::spawn(async move { chatty.send_receive(4).await });
+    responder.respond(input).unwrap();
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 3);
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 4);
+    responder.respond(input * 2).unwrap();
+    assert_eq!(request.await.unwrap(), Ok(8));
+}
+
+#[tokio::test]
+async fn fair_closed() {
+    let (tx, mut rx) = bmrng::fair::channel::<i32, i32>(4);
+    let other = tx.clone();
+    let _response = other.send(1).await.unwrap();
+    drop(other);
+    drop(tx);
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 1);
+    assert!(matches!(rx.recv().await, Err(RequestError::RecvError(..))));
+
+    let (tx, rx) = bmrng::fair::channel::<i32, i32>(4);
+    let mut response = tx.send(1).await.unwrap();
+    drop(rx);
+    assert!(tx.is_closed());
+    assert!(response.recv().await.is_err());
+    assert_eq!(tx.send(2).await.map(drop), Err(SendError(2)));
+}
@@ -0,0 +1,144 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::fanout;
+use bmrng::RequestSender;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+fn spawn_multiplier(factor: i32, delay: Duration) -> RequestSender<i32, i32> {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            sleep(delay).await;
+            let _ = responder.respond(input * factor);
+        }
+    });
+    tx
+}
+
+#[tokio::test]
+async fn fanout_all() {
+    let senders = vec![
+        spawn_multiplier(1, Duration::from_millis(20)),
+        spawn_multiplier(2, Duration::from_millis(10)),
+        spawn_multiplier(3, Duration::from_millis(0)),
+    ];
+    let responses = fanout::send_receive_all(&senders, 5).await;
+    assert_eq!(responses, vec![Ok(5), Ok(10), Ok(15)]);
+}
+
+#[tokio::test]
+async fn fanout_first() {
+    let (closed_tx, closed_rx) = bmrng::channel::<i32, i32>(1);
+    drop(closed_rx);
+    let senders = vec![
+        closed_tx,
+        spawn_multiplier(2, Duration::from_millis(50)),
+        spawn_multiplier(3, Duration::from_millis(0)),
+    ];
+    let response = fanout::send_receive_first(&senders, 5).await;
+    assert_eq!(response, Ok(15));
+}
+
+#[tokio::test]
+async fn fanout_quorum() {
+    let senders = vec![
+        spawn_multiplier(1, Duration::from_millis(0)),
+        spawn_multiplier(1, Duration::from_millis(10)),
+        spawn_multiplier(1, Duration::from_millis(500)),
+    ];
+    let responses = fanout::send_receive_quorum(&senders, 7, 2).await;
+    assert_eq!(responses, Ok(vec![7, 7]));
+}
+
+#[tokio::test]
+async fn fanout_quorum_unreachable() {
+    let (closed_tx, closed_rx) = bmrng::channel::<i32, i32>(1);
+    drop(closed_rx);
+    let senders = vec![closed_tx, spawn_multiplier(1, Duration::from_millis(0))];
+    let responses = fanout::send_receive_quorum(&senders, 7, 2).await;
+    assert_eq!(responses, Err(RequestError::SendError(7)));
+    let responses = fanout::send_receive_first::<i32, i32>(&[], 7).await;
+    assert_eq!(responses, Err(RequestError::SendError(7)));
+}
+
+#[tokio::test]
+async fn fanout_shared_request() {
+    #[derive(Debug)]
+    struct Body(Vec<u8>);
+
+    let original = Arc::new(Body(vec![0; 1024]));
+    let mut senders = Vec::new();
+    for _ in 0..3 {
+        let (tx, mut rx) = bmrng::channel::<Arc<Body>, bool>(1);
+        let original = original.clone();
+        tokio::spawn(async move {
+            let (body, responder) = rx.recv().await.expect("Unexpected err");
+            let _ = responder.respond(Arc::ptr_eq(&body, &original) && body.0.len() == 1024);
+        });
+        senders.push(tx);
+    }
+    let responses = fanout::send_receive_all(&senders, original).await;
+    assert!(responses
+        .into_iter()
+        .all(|response| matches!(response, Ok(true))));
+}
+
+#[tokio::test]
+async fn fanout_race_cancels_losers() {
+    let (slow_tx, mut slow_rx) = bmrng::channel::<i32, i32>(1);
+    let slow = tokio::spawn(async move {
+        let (_, mut responder) = slow_rx.recv().await.expect("Unexpected err");
+        responder.closed().await;
+        responder.cancel_reason()
+    });
+    let (closed_tx, closed_rx) = bmrng::channel::<i32, i32>(1);
+    drop(closed_rx);
+    let senders = vec![
+        closed_tx,
+        slow_tx,
+        spawn_multiplier(2, Duration::from_millis(10)),
+    ];
+    let response = fanout::race_send_receive(&senders, 5).await;
+    assert_eq!(response, Ok(10));
+    assert_eq!(
+        slow.await.expect("Unexpected err"),
+        Some(bmrng::CancelReason::Cancelled)
+    );
+}
+
+#[tokio::test]
+async fn fanout_race_all_fail() {
+    let (failing_tx, mut failing_rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        let (_, responder) = failing_rx.recv().await.expect("Unexpected err");
+        drop(responder);
+    });
+    let response = fanout::race_send_receive(&[failing_tx], 5).await;
+    assert_eq!(
+        response,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    let response = fanout::race_send_receive::<i32, i32>(&[], 5).await;
+    assert_eq!(response, Err(RequestError::SendError(5)));
+}
+
+#[tokio::test]
+async fn fanout_send_receive_all_shared() {
+    #[derive(Debug)]
+    struct Body(Vec<u8>);
+
+    let mut senders = Vec::new();
+    for _ in 0..3 {
+        let (tx, mut rx) = bmrng::channel::<Arc<Body>, usize>(1);
+        tokio::spawn(async move {
+            let (body, responder) = rx.recv().await.expect("Unexpected err");
+            let _ = responder.respond(Arc::strong_count(&body) + body.0.len());
+        });
+        senders.push(tx);
+    }
+    let responses = fanout::send_receive_all_shared(&senders, Body(vec![0; 1024])).await;
+    assert_eq!(responses.len(), 3);
+    assert!(responses
+        .into_iter()
+        .all(|response| matches!(response, Ok(count) if count >= 1024 + 2)));
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "tonic")]
+
+use bmrng::grpc;
+use tokio::time::{advance, pause, resume, sleep, Duration};
+use tonic::{Code, Request, Response, Status};
+
+#[tokio::test]
+async fn grpc_forward() {
+    let (tx, mut rx) = bmrng::channel::<i32, Result<i32, Status>>(1);
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            let response = if input < 0 {
+                Err(Status::invalid_argument("negative"))
+            } else {
+                Ok(input * input)
+            };
+            let _ = responder.respond(response);
+        }
+    });
+    let response = grpc::forward(&tx, Request::new(8)).await;
+    assert_eq!(response.map(Response::into_inner).ok(), Some(64));
+    let response = grpc::forward(&tx, Request::new(-1)).await;
+    assert_eq!(
+        response.map_err(|status| status.code()).err(),
+        Some(Code::InvalidArgument)
+    );
+}
+
+#[tokio::test]
+async fn grpc_forward_errors() {
+    let (tx, rx) =
+        bmrng::channel_with_timeout::<i32, Result<i32, Status>>(1, Duration::from_millis(100));
+    let task = tokio::spawn(async move {
+        let mut rx = rx;
+        let (_input, _responder) = rx.recv().await.expect("Unexpected err");
+        advance(Duration::from_millis(200)).await;
+        sleep(Duration::from_micros(1)).await;
+        resume();
+    });
+    pause();
+    let response = grpc::forward(&tx, Request::new(1)).await;
+    assert_eq!(
+        response.map_err(|status| status.code()).err(),
+        Some(Code::DeadlineExceeded)
+    );
+    assert!(tokio::join!(task).0.is_ok());
+    let response = grpc::forward(&tx, Request::new(1)).await;
+    assert_eq!(
+        response.map_err(|status| status.code()).err(),
+        Some(Code::Unavailable)
+    );
+}
+
+#[tokio::test]
+async fn grpc_serve_client() {
+    let (tx, rx) = bmrng::channel::<String, Result<usize, Status>>(1);
+    tokio::spawn(grpc::serve_client(
+        rx,
+        |request: Request<String>| async move { Ok(Response::new(request.into_inner().len())) },
+    ));
+    let response = tx.send_receive("hello".to_string()).await;
+    assert!(matches!(response, Ok(Ok(5))));
+}
@@ -0,0 +1,37 @@
+use bmrng::error::{ReceiveError, RequestError, SendError};
+
+#[tokio::test]
+async fn latest_supersedes_pending_request() {
+    let (tx, mut rx) = bmrng::latest::channel::<i32, i32>();
+    let first = tx.send(1).unwrap();
+    let second = tx.send(2).unwrap();
+    let third = tx.clone().send(3).unwrap();
+    assert!(rx.has_pending());
+    assert_eq!(first.await, Err(ReceiveError::Superseded));
+    assert_eq!(second.await, Err(ReceiveError::Superseded));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(!rx.has_pending());
+    assert_eq!(input, 3);
+    let _ = responder.respond(input * 2);
+    assert_eq!(third.await, Ok(6));
+}
+
+#[tokio::test]
+async fn latest_send_receive() {
+    let (tx, mut rx) = bmrng::latest::channel::<i32, i32>();
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            let _ = responder.respond(input + 1);
+        }
+    });
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+}
+
+#[tokio::test]
+async fn latest_closed() {
+    let (tx, rx) = bmrng::latest::channel::<i32, i32>();
+    drop(rx);
+    assert!(tx.is_closed());
+    assert!(matches!(tx.send(1), Err(SendError(1))));
+    assert_eq!(tx.send_receive(2).await, Err(RequestError::SendError(2)));
+}
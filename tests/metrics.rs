@@ -0,0 +1,184 @@
+use bmrng::error::{ErrorTrace, ReceiveError, RequestError};
+use bmrng::metrics::{ChannelMetrics, ChannelObserver, ChannelStats};
+use bmrng::RequestId;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::time::{advance, pause, resume, Duration};
+
+#[tokio::test]
+async fn metrics_count_requests_and_latency() {
+    pause();
+    let stats = Arc::new(ChannelStats::with_buckets(vec![
+        Duration::from_millis(100),
+        Duration::from_millis(10),
+    ]));
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .metrics(stats.clone())
+        .build();
+    let fast = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(1).await }
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    advance(Duration::from_millis(5)).await;
+    responder.respond(input * 2).unwrap();
+    assert_eq!(fast.await.unwrap(), Ok(2));
+
+    let slow = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(2).await }
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    advance(Duration::from_millis(50)).await;
+    responder.respond(input * 2).unwrap();
+    assert_eq!(slow.await.unwrap(), Ok(4));
+
+    let dropped = tokio::spawn(async move { tx.send_receive(3).await });
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(responder);
+    assert_eq!(
+        dropped.await.unwrap(),
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    resume();
+
+    assert_eq!(stats.sent(), 3);
+    assert_eq!(stats.received(), 3);
+    assert_eq!(stats.responded(), 2);
+    assert_eq!(stats.dropped(), 1);
+    assert_eq!(stats.timed_out(), 0);
+    let latency = stats.latency();
+    assert_eq!(
+        latency.buckets(),
+        &[
+            (Some(Duration::from_millis(10)), 1),
+            (Some(Duration::from_millis(100)), 2),
+            (None, 2),
+        ]
+    );
+    assert_eq!(latency.count(), 2);
+    assert_eq!(latency.sum(), Duration::from_millis(55));
+}
+
+#[tokio::test]
+async fn metrics_count_timeouts() {
+    pause();
+    let stats = Arc::new(ChannelStats::new());
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .response_timeout(Duration::from_millis(100))
+        .metrics(stats.clone())
+        .build_unbounded();
+    let request = tokio::spawn(async move { tx.send_receive(1).await });
+    let (_, responder) = rx.recv().await.unwrap();
+    advance(Duration::from_millis(150)).await;
+    assert_eq!(
+        request.await.unwrap(),
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert!(responder.respond(2).is_err());
+    resume();
+
+    assert_eq!(stats.sent(), 1);
+    assert_eq!(stats.received(), 1);
+    assert_eq!(stats.timed_out(), 1);
+    assert_eq!(stats.responded(), 0);
+    assert_eq!(stats.dropped(), 0);
+}
+
+#[derive(Default)]
+struct Queued(AtomicUsize);
+
+impl ChannelMetrics for Queued {
+    fn on_recv(&self, queued: Duration) {
+        self.0
+            .fetch_add(queued.as_millis() as usize, Ordering::SeqCst);
+    }
+}
+
+#[tokio::test]
+async fn metrics_custom_hooks() {
+    pause();
+    let queued = Arc::new(Queued::default());
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .metrics(queued.clone())
+        .build();
+    let _response = tx.send(1).await.unwrap();
+    advance(Duration::from_millis(30)).await;
+    let (_, responder) = rx.recv().await.unwrap();
+    resume();
+    assert_eq!(queued.0.load(Ordering::SeqCst), 30);
+    drop(responder);
+}
+
+/// Records the lost requests by id
+#[derive(Default)]
+struct Lost(Mutex<Vec<(&'static str, u64)>>);
+
+impl Lost {
+    fn record(&self, event: &'static str, id: RequestId) {
+        self.0.lock().unwrap().push((event, id.as_u64()));
+    }
+
+    fn take(&self) -> Vec<(&'static str, u64)> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+impl ChannelObserver for Lost {
+    fn on_responder_dropped(&self, id: RequestId) {
+        self.record("responder dropped", id);
+    }
+
+    fn on_request_dropped(&self, id: RequestId) {
+        self.record("request dropped", id);
+    }
+
+    fn on_late_response(&self, id: RequestId, elapsed: Duration) {
+        assert_eq!(elapsed, Duration::from_millis(150));
+        self.record("late response", id);
+    }
+}
+
+#[tokio::test]
+async fn observer_reports_lost_requests() {
+    pause();
+    let lost = Arc::new(Lost::default());
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .response_timeout(Duration::from_millis(100))
+        .observer(lost.clone())
+        .build();
+
+    let mut first = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(responder);
+    assert_eq!(first.recv().await, Err(ReceiveError::RecvError));
+    assert_eq!(lost.take(), vec![("responder dropped", 1)]);
+
+    let second = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(2).await }
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    advance(Duration::from_millis(150)).await;
+    assert_eq!(
+        second.await.unwrap(),
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert!(responder.respond(input).is_err());
+    assert_eq!(lost.take(), vec![("late response", 2)]);
+
+    let mut third = tx.send(3).await.unwrap();
+    let fourth = tx.send(4).await.unwrap();
+    drop(fourth);
+    drop(rx);
+    assert_eq!(third.recv().await, Err(ReceiveError::RecvError));
+    assert_eq!(
+        lost.take(),
+        vec![("request dropped", 3), ("request dropped", 4)]
+    );
+    resume();
+}
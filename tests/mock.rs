@@ -0,0 +1,109 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::test::{assert_idle, MockResponder, RecordingSender};
+use bmrng::Request;
+use tokio::time::{pause, resume, sleep, Duration, Instant};
+
+#[tokio::test]
+async fn mock_auto() {
+    let (tx, mock) = MockResponder::auto(|input: &i32| input * 2);
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(tx.clone().send_receive(4).await, Ok(8));
+    assert_eq!(mock.received(), 2);
+    assert_eq!(mock.requests(), vec![1, 4]);
+    mock.assert_received(&4);
+    mock.assert_all_answered();
+    assert_idle(&tx);
+}
+
+#[tokio::test]
+async fn mock_scripted() {
+    let (tx, mock) = MockResponder::<&str>::scripted(vec![1, 2]);
+    assert_eq!(tx.send_receive("a").await, Ok(1));
+    assert_eq!(tx.send_receive("b").await, Ok(2));
+    assert_eq!(
+        tx.send_receive("c").await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(mock.unanswered(), 1);
+    assert_eq!(mock.requests(), vec!["a", "b", "c"]);
+}
+
+#[tokio::test]
+async fn mock_serve_unbounded() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mock = MockResponder::serve(rx.into_stream(), |input| (*input > 0).then_some(*input));
+    let response = tx.send(5).unwrap();
+    tx.send_forget(-1).unwrap();
+    mock.wait_for(2).await;
+    assert_eq!(response.await, Ok(5));
+    mock.assert_received(&-1);
+    assert_eq!(mock.unanswered(), 1);
+}
+
+#[tokio::test]
+#[should_panic(expected = "expected request 3 to have been received")]
+async fn mock_assert_received_panics() {
+    let (tx, mock) = MockResponder::auto(|input: &i32| *input);
+    assert_eq!(tx.send_receive(1).await, Ok(1));
+    mock.assert_received(&3);
+}
+
+#[tokio::test]
+#[should_panic(expected = "expected no unanswered requests, 1 are still pending")]
+async fn mock_assert_idle_panics() {
+    let (tx, _rx) = bmrng::channel::<i32, i32>(1);
+    let _response = tx.send(1).await.unwrap();
+    assert_idle(&tx);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Echo(u32);
+
+impl Request for Echo {
+    type Response = u32;
+}
+
+#[tokio::test]
+async fn recording_replay() {
+    pause();
+    let (tx, mut rx) = bmrng::typed_channel::<Echo>(4);
+    tokio::spawn(async move {
+        while let Ok((Echo(input), responder)) = rx.recv().await {
+            sleep(Duration::from_millis(10)).await;
+            if input > 0 {
+                responder.respond(input).unwrap();
+            }
+        }
+    });
+    let tx = RecordingSender::new(tx);
+    assert_eq!(tx.send_receive(Echo(1)).await, Ok(1));
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(
+        tx.clone().send_receive(Echo(0)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(tx.inner().send_receive(Echo(2)).await, Ok(2));
+
+    let recording = tx.recording();
+    assert_eq!(recording.len(), 2);
+    let exchanges = recording.exchanges();
+    assert_eq!(exchanges[0].request, Echo(1));
+    assert_eq!(exchanges[0].response, Some(1));
+    assert!(exchanges[0].elapsed >= Duration::from_millis(10));
+    assert_eq!(exchanges[1].response, None);
+    assert!(exchanges[1].sent_at >= Duration::from_millis(110));
+
+    let (replay_tx, mock) = recording.replay_responses();
+    assert_eq!(replay_tx.send_receive(Echo(7)).await, Ok(1));
+    assert_eq!(
+        replay_tx.send_receive(Echo(8)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(mock.requests(), vec![Echo(7), Echo(8)]);
+
+    let (tx, _mock) = MockResponder::auto(|Echo(input): &Echo| input + 1);
+    let start = Instant::now();
+    assert_eq!(recording.replay_requests(&tx).await, vec![Ok(2), Ok(1)]);
+    assert!(start.elapsed() >= exchanges[1].sent_at);
+    resume();
+}
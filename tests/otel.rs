@@ -0,0 +1,64 @@
+#![cfg(feature = "otel")]
+
+use opentelemetry::Context;
+
+/// A value carried in the OpenTelemetry context, standing in for a span context
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct TraceMarker(u32);
+
+fn current_marker() -> Option<u32> {
+    Context::current()
+        .get::<TraceMarker>()
+        .map(|marker| marker.0)
+}
+
+#[tokio::test]
+async fn otel_handler_runs_in_request_context() {
+    let (tx, rx) = bmrng::channel::<u32, Option<u32>>(1);
+    let server = tokio::spawn(rx.serve(|_| async move { current_marker() }));
+
+    let mut receiver = {
+        let _context = Context::current_with_value(TraceMarker(7)).attach();
+        tx.send(0).await.unwrap()
+    };
+    assert_eq!(receiver.recv().await, Ok(Some(7)));
+    assert_eq!(tx.send_receive(0).await, Ok(None));
+    drop(tx);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn otel_thread_handler_runs_in_request_context() {
+    let (tx, rx) = bmrng::channel::<u32, Option<u32>>(1);
+    let handler = bmrng::spawn_thread_handler(rx, |_| current_marker());
+
+    let mut receiver = {
+        let _context = Context::current_with_value(TraceMarker(3)).attach();
+        tx.send(0).await.unwrap()
+    };
+    assert_eq!(receiver.recv().await, Ok(Some(3)));
+    drop(tx);
+    handler.join().unwrap();
+}
+
+#[tokio::test]
+async fn otel_responder_context() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<u32, u32>();
+    let receiver = {
+        let _context = Context::current_with_value(TraceMarker(1)).attach();
+        tx.send(1).unwrap()
+    };
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(
+        responder.otel_context().get::<TraceMarker>(),
+        Some(&TraceMarker(1))
+    );
+    assert_eq!(
+        receiver.otel_context().get::<TraceMarker>(),
+        Some(&TraceMarker(1))
+    );
+
+    tx.send_forget(2).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.otel_context().get::<TraceMarker>(), None);
+}
@@ -0,0 +1,64 @@
+use bmrng::error::{ErrorTrace, RequestError, SendError};
+use tokio::time::{advance, pause, resume, timeout, Duration};
+
+#[tokio::test]
+async fn priority_order() {
+    let (tx, mut rx) = bmrng::priority::channel::<i32, i32>(8);
+    let _low = tx.send(1).await.unwrap();
+    let _high = tx.send_with_priority(2, 5).await.unwrap();
+    let _mid = tx.send_with_priority(3, 3).await.unwrap();
+    let _also_low = tx.send(4).await.unwrap();
+    let mut order = Vec::new();
+    while !rx.is_empty() {
+        let (input, _responder) = rx.recv().await.unwrap();
+        order.push(input);
+    }
+    assert_eq!(order, vec![2, 3, 1, 4]);
+}
+
+#[tokio::test]
+async fn priority_aging() {
+    pause();
+    let (tx, mut rx) = bmrng::priority::channel_with_aging::<i32, i32>(8, Duration::from_secs(1));
+    let _old = tx.send(1).await.unwrap();
+    advance(Duration::from_secs(3)).await;
+    let _urgent = tx.send_with_priority(2, 2).await.unwrap();
+    let _more_urgent = tx.send_with_priority(3, 4).await.unwrap();
+    let mut order = Vec::new();
+    while !rx.is_empty() {
+        let (input, _responder) = rx.recv().await.unwrap();
+        order.push(input);
+    }
+    assert_eq!(order, vec![3, 1, 2]);
+    resume();
+}
+
+#[tokio::test]
+async fn priority_send_receive_timeout() {
+    pause();
+    let (tx, mut rx) =
+        bmrng::priority::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let _first = tx.send(1).await.unwrap();
+    assert!(timeout(Duration::from_millis(50), tx.send(2))
+        .await
+        .is_err());
+    let (_input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(
+        tx.send_receive_with_priority(3, 1).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn priority_closed() {
+    let (tx, mut rx) = bmrng::priority::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.unwrap();
+        let _ = responder.respond(input);
+    });
+    assert_eq!(tx.send_receive(7).await, Ok(7));
+    tokio::task::yield_now().await;
+    assert!(tx.is_closed());
+    assert!(matches!(tx.send(1).await, Err(SendError(1))));
+}
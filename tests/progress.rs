@@ -0,0 +1,45 @@
+use bmrng::error::ReceiveError;
+use futures_util::stream::StreamExt;
+
+#[tokio::test]
+async fn progress_then_respond() {
+    let (tx, mut rx) = bmrng::progress::channel::<u32, u32, u32>(8);
+    tokio::spawn(async move {
+        while let Ok((steps, responder)) = rx.recv().await {
+            for step in 1..=steps {
+                assert!(responder.progress(step).is_ok());
+            }
+            let _ = responder.respond(steps * 100);
+        }
+    });
+    let mut receiver = tx.send(3).await.unwrap();
+    let updates: Vec<u32> = receiver.by_ref().collect().await;
+    assert_eq!(updates, vec![1, 2, 3]);
+    assert_eq!(receiver.recv().await, Ok(300));
+}
+
+#[tokio::test]
+async fn progress_skipped_when_full() {
+    let (tx, mut rx) = bmrng::progress::channel::<u32, u32, u32>(1);
+    let mut receiver = tx.send(0).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert!(responder.progress(1).is_ok());
+    assert!(responder.progress(2).is_ok());
+    assert!(responder.respond(7).is_ok());
+    assert_eq!(receiver.recv().await, Ok(7));
+    assert_eq!(receiver.progress().await, Some(1));
+    assert_eq!(receiver.progress().await, None);
+}
+
+#[tokio::test]
+async fn progress_receiver_dropped() {
+    let (tx, mut rx) = bmrng::progress::channel::<u32, u32, u32>(1);
+    let receiver = tx.send(0).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(receiver);
+    assert!(responder.is_closed());
+    assert_eq!(responder.progress(1).map_err(|err| err.0), Err(1));
+    let mut receiver = tx.send(0).await.unwrap();
+    drop(rx);
+    assert_eq!(receiver.recv().await, Err(ReceiveError::RecvError));
+}
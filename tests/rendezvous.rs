@@ -0,0 +1,54 @@
+use bmrng::error::{RequestError, SendError};
+use tokio::time::{pause, resume, sleep, timeout, Duration};
+
+#[tokio::test]
+async fn rendezvous_hand_off() {
+    let (tx, mut rx) = bmrng::rendezvous_channel::<i32, i32>();
+    let server = tokio::spawn(async move {
+        sleep(Duration::from_millis(10)).await;
+        while let Ok((input, responder)) = rx.recv().await {
+            let _ = responder.respond(input * 2);
+        }
+    });
+    let tx2 = tx.clone();
+    let other = tokio::spawn(async move { tx2.send_receive(2).await });
+    assert_eq!(tx.send_receive(1).await, Ok(2));
+    assert_eq!(other.await.unwrap(), Ok(4));
+    drop(tx);
+    server.await.unwrap();
+}
+
+#[tokio::test]
+async fn rendezvous_send_waits_for_receiver() {
+    pause();
+    let (tx, mut rx) = bmrng::rendezvous_channel::<i32, i32>();
+    let send = timeout(Duration::from_millis(100), tx.send(1)).await;
+    assert!(send.is_err());
+    let receiving = tokio::spawn(async move {
+        let (input, _responder) = rx.recv().await.unwrap();
+        input
+    });
+    let response_receiver = tx.send(2).await;
+    assert!(response_receiver.is_ok());
+    assert_eq!(receiving.await.unwrap(), 2);
+    assert!(tx.is_closed());
+    assert!(matches!(tx.send(3).await, Err(SendError(3))));
+    resume();
+}
+
+#[tokio::test]
+async fn rendezvous_receiver_dropped_during_send() {
+    let (tx, rx) = bmrng::rendezvous_channel::<i32, i32>();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(10)).await;
+        drop(rx);
+    });
+    assert_eq!(tx.send_receive(1).await, Err(RequestError::SendError(1)));
+}
+
+#[tokio::test]
+async fn rendezvous_senders_dropped() {
+    let (tx, mut rx) = bmrng::rendezvous_channel::<i32, i32>();
+    drop(tx);
+    assert!(matches!(rx.recv().await, Err(RequestError::RecvError(..))));
+}
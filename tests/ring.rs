@@ -0,0 +1,60 @@
+use bmrng::error::{ReceiveError, RequestError, TrySendError};
+use bmrng::ring::Overflow;
+use tokio::time::{pause, resume, timeout, Duration};
+
+#[tokio::test]
+async fn ring_drop_oldest() {
+    let (tx, mut rx) = bmrng::ring::channel::<i32, i32>(2, Overflow::DropOldest);
+    let first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    let _third = tx.send(3).await.unwrap();
+    assert_eq!(rx.len(), 2);
+    assert_eq!(first.await, Err(ReceiveError::Evicted));
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+}
+
+#[tokio::test]
+async fn ring_drop_newest() {
+    let (tx, mut rx) = bmrng::ring::channel::<i32, i32>(1, Overflow::DropNewest);
+    let _first = tx.send(1).await.unwrap();
+    assert!(matches!(tx.send(2).await, Err(TrySendError::Full(2))));
+    assert_eq!(tx.send_receive(3).await, Err(RequestError::Evicted));
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 1);
+    assert!(rx.is_empty());
+}
+
+#[tokio::test]
+async fn ring_block() {
+    pause();
+    let (tx, mut rx) = bmrng::ring::channel::<i32, i32>(1, Overflow::Block);
+    let _first = tx.send(1).await.unwrap();
+    assert!(timeout(Duration::from_millis(100), tx.send(2))
+        .await
+        .is_err());
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            let _ = responder.respond(input * 2);
+        }
+    });
+    assert_eq!(tx.send_receive(3).await, Ok(6));
+    resume();
+}
+
+#[tokio::test]
+async fn ring_closed() {
+    let (tx, rx) = bmrng::ring::channel::<i32, i32>(1, Overflow::Block);
+    let queued = tx.send(1).await.unwrap();
+    drop(rx);
+    assert!(tx.is_closed());
+    assert_eq!(queued.await, Err(ReceiveError::RecvError));
+    assert_eq!(tx.send_receive(2).await, Err(RequestError::SendError(2)));
+}
+
+#[tokio::test]
+async fn ring_try_send() {
+    let (tx, _rx) = bmrng::ring::channel::<i32, i32>(1, Overflow::Block);
+    let _first = tx.try_send(1).unwrap();
+    assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+}
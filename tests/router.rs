@@ -0,0 +1,62 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::router::Router;
+use bmrng::Request;
+use tokio::time::{pause, resume, sleep, Duration};
+
+#[derive(Debug, PartialEq)]
+struct GetUser(u32);
+
+impl Request for GetUser {
+    type Response = String;
+}
+
+#[derive(Debug, PartialEq)]
+struct CountUsers;
+
+impl Request for CountUsers {
+    type Response = usize;
+    const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+}
+
+#[derive(Debug, PartialEq)]
+struct DeleteUser(u32);
+
+impl Request for DeleteUser {
+    type Response = bool;
+}
+
+#[tokio::test]
+async fn router_dispatch() {
+    let mut router = Router::new(4);
+    router
+        .register(|GetUser(id)| async move { format!("user {}", id) })
+        .register(|CountUsers| async move { 3 });
+    let sender = router.build();
+    assert!(sender.handles::<GetUser>());
+    assert!(!sender.handles::<DeleteUser>());
+    assert_eq!(
+        sender.send_receive(GetUser(1)).await,
+        Ok("user 1".to_string())
+    );
+    assert_eq!(sender.clone().send_receive(CountUsers).await, Ok(3));
+    assert_eq!(
+        sender.send_receive(DeleteUser(1)).await,
+        Err(RequestError::SendError(DeleteUser(1)))
+    );
+}
+
+#[tokio::test]
+async fn router_request_timeout() {
+    pause();
+    let mut router = Router::new(1);
+    router.register(|CountUsers| async move {
+        sleep(Duration::from_millis(200)).await;
+        3
+    });
+    let sender = router.build();
+    assert_eq!(
+        sender.send_receive(CountUsers).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    resume();
+}
@@ -0,0 +1,94 @@
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::scatter::{ReceiverId, ScatterSender};
+use bmrng::RequestReceiver;
+use futures_util::StreamExt;
+use tokio::time::{sleep, Duration};
+
+fn spawn_multiplier(mut rx: RequestReceiver<i32, i32>, factor: i32, delay: Duration) {
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            sleep(delay).await;
+            let _ = responder.respond(input * factor);
+        }
+    });
+}
+
+fn register_multipliers(tx: &ScatterSender<i32, i32>, delays: &[u64]) -> Vec<ReceiverId> {
+    delays
+        .iter()
+        .enumerate()
+        .map(|(index, delay)| {
+            let (id, rx) = tx.register();
+            spawn_multiplier(rx, index as i32 + 1, Duration::from_millis(*delay));
+            id
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn scatter_all() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    let ids = register_multipliers(&tx, &[20, 10, 0]);
+    let responses = tx.send_receive_all(5).await;
+    assert_eq!(
+        responses,
+        vec![(ids[0], Ok(5)), (ids[1], Ok(10)), (ids[2], Ok(15))]
+    );
+}
+
+#[tokio::test]
+async fn scatter_stream_in_arrival_order() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    let ids = register_multipliers(&tx, &[40, 0, 20]);
+    let responses: Vec<_> = tx.scatter(2).collect().await;
+    assert_eq!(
+        responses,
+        vec![(ids[1], Ok(4)), (ids[2], Ok(6)), (ids[0], Ok(2))]
+    );
+}
+
+#[tokio::test]
+async fn scatter_quorum() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    let ids = register_multipliers(&tx, &[1000, 0, 10]);
+    let responses = tx.send_receive_quorum(3, 2).await;
+    assert_eq!(responses, Ok(vec![(ids[1], 6), (ids[2], 9)]));
+}
+
+#[tokio::test]
+async fn scatter_quorum_unreachable() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    register_multipliers(&tx, &[0]);
+    let (_, rx) = tx.register();
+    tokio::spawn(async move {
+        let mut rx = rx;
+        let (_, responder) = rx.recv().await.unwrap();
+        drop(responder);
+    });
+    let responses = tx.send_receive_quorum(3, 2).await;
+    assert_eq!(
+        responses,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn scatter_without_receivers() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    assert_eq!(tx.send_receive_all(1).await, vec![]);
+    assert_eq!(
+        tx.send_receive_quorum(1, 1).await,
+        Err(RequestError::SendError(1))
+    );
+}
+
+#[tokio::test]
+async fn scatter_unregisters_dropped_receivers() {
+    let tx = ScatterSender::<i32, i32>::new(1);
+    let ids = register_multipliers(&tx, &[0]);
+    let (_, dropped) = tx.register();
+    assert_eq!(tx.receiver_count(), 2);
+    drop(dropped);
+    assert_eq!(tx.receiver_count(), 1);
+    assert_eq!(tx.send_receive_all(7).await, vec![(ids[0], Ok(7))]);
+}
@@ -0,0 +1,85 @@
+use bmrng::error::RequestError;
+use bmrng::Request;
+use tokio::time::{sleep, Duration};
+
+#[derive(Debug, PartialEq)]
+struct Append {
+    key: &'static str,
+    value: u32,
+}
+
+impl Request for Append {
+    type Response = (usize, Vec<u32>);
+}
+
+fn spawn_shards(receivers: Vec<bmrng::sharded::ShardReceiver<Append>>) {
+    for (shard, mut rx) in receivers.into_iter().enumerate() {
+        tokio::spawn(async move {
+            let mut seen = std::collections::HashMap::<&str, Vec<u32>>::new();
+            while let Ok((append, responder)) = rx.recv().await {
+                sleep(Duration::from_millis(1)).await;
+                let values = seen.entry(append.key).or_default();
+                values.push(append.value);
+                let _ = responder.respond((shard, values.clone()));
+            }
+        });
+    }
+}
+
+#[tokio::test]
+async fn sharded_same_key_same_shard() {
+    let (tx, receivers) = bmrng::sharded::channel::<Append, &str>(4, 8, |append| append.key);
+    assert_eq!(tx.shard_count(), 4);
+    assert_eq!(receivers.len(), 4);
+    spawn_shards(receivers);
+    let mut shards = Vec::new();
+    for value in 0..5 {
+        let (shard, values) = tx
+            .send_receive(Append { key: "a", value })
+            .await
+            .expect("Unexpected err");
+        shards.push(shard);
+        assert_eq!(values, (0..=value).collect::<Vec<_>>());
+    }
+    let expected = tx.shard_of(&Append { key: "a", value: 0 });
+    assert!(shards.iter().all(|shard| *shard == expected));
+}
+
+#[tokio::test]
+async fn sharded_keeps_order_per_key() {
+    let (tx, receivers) = bmrng::sharded::channel::<Append, &str>(3, 16, |append| append.key);
+    spawn_shards(receivers);
+    let mut pending = Vec::new();
+    for value in 0..10 {
+        for key in ["a", "b", "c", "d"].iter().copied() {
+            pending.push((key, tx.send(Append { key, value }).await.unwrap()));
+        }
+    }
+    for (key, receiver) in pending {
+        let (_, values) = receiver.await.expect("Unexpected err");
+        let last = *values.last().unwrap();
+        assert_eq!(values, (0..=last).collect::<Vec<_>>(), "key {}", key);
+    }
+}
+
+#[tokio::test]
+async fn sharded_closed_shard() {
+    let (tx, receivers) = bmrng::sharded::channel::<Append, &str>(2, 1, |append| append.key);
+    let request = Append { key: "x", value: 1 };
+    let shard = tx.shard_of(&request);
+    let mut receivers: Vec<_> = receivers.into_iter().map(Some).collect();
+    drop(receivers[shard].take());
+    assert!(!tx.is_closed());
+    assert_eq!(
+        tx.send_receive(request).await,
+        Err(RequestError::SendError(Append { key: "x", value: 1 }))
+    );
+    drop(receivers);
+    assert!(tx.is_closed());
+}
+
+#[test]
+#[should_panic(expected = "sharded channel requires shards > 0")]
+fn sharded_zero_shards() {
+    let _ = bmrng::sharded::channel::<Append, &str>(0, 1, |append| append.key);
+}
@@ -0,0 +1,45 @@
+use bmrng::error::RequestError;
+use futures_util::stream::StreamExt;
+
+#[tokio::test]
+async fn streaming_responses() {
+    let (tx, mut rx) = bmrng::streaming::channel::<u32, u32>(2);
+    tokio::spawn(async move {
+        while let Ok((count, responder)) = rx.recv().await {
+            for i in 0..count {
+                if responder.send(i * 10).await.is_err() {
+                    break;
+                }
+            }
+        }
+    });
+    let items: Vec<u32> = tx.send(4).await.unwrap().collect().await;
+    assert_eq!(items, vec![0, 10, 20, 30]);
+    let mut stream = tx.send(0).await.unwrap();
+    assert_eq!(stream.recv().await, None);
+}
+
+#[tokio::test]
+async fn streaming_close_response_stream() {
+    let (tx, mut rx) = bmrng::streaming::channel::<u32, u32>(1);
+    let mut stream = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert!(responder.send(1).await.is_ok());
+    stream.close();
+    responder.closed().await;
+    assert!(responder.is_closed());
+    assert_eq!(responder.send(2).await.map_err(|err| err.0), Err(2));
+    assert_eq!(stream.recv().await, Some(1));
+    assert_eq!(stream.recv().await, None);
+}
+
+#[tokio::test]
+async fn streaming_receiver_dropped() {
+    let (tx, rx) = bmrng::streaming::channel::<u32, u32>(1);
+    drop(rx);
+    assert!(tx.is_closed());
+    assert!(matches!(tx.send(1).await, Err(bmrng::error::SendError(1))));
+    let (tx, mut rx) = bmrng::streaming::channel::<u32, u32>(1);
+    drop(tx);
+    assert!(matches!(rx.recv().await, Err(RequestError::RecvError(..))));
+}
@@ -1,7 +1,7 @@
 use bmrng::unbounded::UnboundedRequestReceiverStream;
-use bmrng::{error::*, RequestReceiverStream};
+use bmrng::{error::*, CancelReason, RequestReceiverStream};
 use futures_util::stream::StreamExt;
-use tokio::time::{advance, pause, resume, sleep, Duration};
+use tokio::time::{advance, pause, resume, sleep, timeout, Duration};
 
 #[tokio::test]
 async fn unbounded_send_receive() {
@@ -84,7 +84,10 @@ async fn unbounded_drop_while_waiting_for_response() {
     });
     let response = tx.send_receive(8).await;
     assert!(tokio::join!(task).0.is_ok());
-    assert_eq!(response, Err(RequestError::RecvError));
+    assert_eq!(
+        response,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
 }
 
 #[tokio::test]
@@ -105,7 +108,7 @@ async fn unbounded_drop_sender_while_sending_response() {
     let task = tokio::spawn(async move {
         let (_, responder) = rx.recv().await.expect("Received err");
         let respond_result = responder.respond(42);
-        assert_eq!(respond_result, Err(RespondError(42)));
+        assert_eq!(respond_result, Err(RespondError(42, ErrorTrace::capture())));
     });
     let response_receiver = tx.send(21);
     drop(response_receiver);
@@ -121,7 +124,10 @@ async fn bounded_drop_while_waiting_for_response() {
     });
     let response = tx.send_receive(8).await;
     assert!(tokio::join!(task).0.is_ok());
-    assert_eq!(response, Err(RequestError::RecvError));
+    assert_eq!(
+        response,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
 }
 
 #[tokio::test]
@@ -140,7 +146,7 @@ async fn bounded_drop_sender_while_sending_response() {
     let task = tokio::spawn(async move {
         let (_, responder) = rx.recv().await.expect("Unexpected err");
         let respond_result = responder.respond(42);
-        assert_eq!(respond_result, Err(RespondError(42)));
+        assert_eq!(respond_result, Err(RespondError(42, ErrorTrace::capture())));
     });
     let response_receiver = tx.send(21).await;
     drop(response_receiver);
@@ -193,7 +199,10 @@ async fn bounded_timeout() {
     });
     assert!(!tx.is_closed());
     let response = tx.send_receive(8).await;
-    assert_eq!(response, Err(RequestError::<i32>::RecvTimeoutError));
+    assert_eq!(
+        response,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
 }
 
 #[tokio::test]
@@ -211,7 +220,10 @@ async fn unbounded_timeout() {
     });
     assert!(!tx.is_closed());
     let response = tx.send_receive(8).await;
-    assert_eq!(response, Err(RequestError::<i32>::RecvTimeoutError));
+    assert_eq!(
+        response,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
 }
 
 #[tokio::test]
@@ -287,3 +299,2821 @@ async fn req_unbounded_receiver_into_inner() {
     assert!(tx.is_closed());
     assert_eq!(response, Ok(64));
 }
+
+#[tokio::test]
+async fn oneshot_send_receive() {
+    let (pending, slot) = bmrng::oneshot::request::<i32, i32>();
+    tokio::spawn(async move {
+        let (input, responder) = slot.recv().await.expect("Unexpected err");
+        assert!(!responder.is_closed());
+        let res = responder.respond(input * input);
+        assert!(res.is_ok());
+    });
+    assert!(!pending.is_closed());
+    let response = pending.send_receive(8).await;
+    assert_eq!(response, Ok(64));
+}
+
+#[tokio::test]
+async fn oneshot_drop_slot() {
+    let (pending, slot) = bmrng::oneshot::request::<i32, i32>();
+    drop(slot);
+    assert!(pending.is_closed());
+    assert_eq!(pending.send(3).map(|_| ()), Err(SendError(3)));
+}
+
+#[tokio::test]
+async fn oneshot_timeout() {
+    let (pending, slot) =
+        bmrng::oneshot::request_with_timeout::<i32, i32>(Duration::from_millis(100));
+    pause();
+    tokio::spawn(async move {
+        let (_input, _responder) = slot.recv().await.expect("Unexpected err");
+        advance(Duration::from_millis(200)).await;
+        sleep(Duration::from_micros(1)).await;
+        resume();
+    });
+    let response = pending.send_receive(8).await;
+    assert_eq!(
+        response,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+}
+
+#[derive(Debug)]
+struct Lookup(i32);
+
+impl bmrng::Request for Lookup {
+    type Response = i32;
+}
+
+#[derive(Debug)]
+struct SlowReport;
+
+impl bmrng::Request for SlowReport {
+    type Response = String;
+    const TIMEOUT: Option<Duration> = Some(Duration::from_millis(100));
+}
+
+#[tokio::test]
+async fn bounded_typed_channel() {
+    let (tx, mut rx) = bmrng::typed_channel::<Lookup>(1);
+    tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.expect("Unexpected err");
+        let res = responder.respond(input.0 * 2);
+        assert!(res.is_ok());
+    });
+    let response = tx.send_receive(Lookup(21)).await;
+    assert_eq!(response.ok(), Some(42));
+}
+
+#[tokio::test]
+async fn unbounded_typed_channel_timeout() {
+    let (tx, mut rx) = bmrng::unbounded_typed_channel::<SlowReport>();
+    pause();
+    tokio::spawn(async move {
+        let (_input, _responder) = rx.recv().await.expect("Unexpected err");
+        advance(Duration::from_millis(200)).await;
+        sleep(Duration::from_micros(1)).await;
+        resume();
+    });
+    let response = tx.send_receive(SlowReport).await;
+    assert!(matches!(response, Err(RequestError::RecvTimeoutError(..))));
+}
+
+#[tokio::test]
+async fn bounded_recv_with() {
+    let (tx, mut rx) = bmrng::channel::<Vec<u8>, usize>(1);
+    let task = tokio::spawn(async move {
+        let request = rx
+            .recv_with(|buf| {
+                buf.push(4);
+                buf.len()
+            })
+            .await;
+        assert_eq!(request, Ok(vec![1, 2, 3, 4]));
+        assert_eq!(
+            rx.recv_with(|buf| buf.len()).await,
+            Err(RequestError::RecvError(ErrorTrace::capture()))
+        );
+    });
+    assert_eq!(tx.send_receive(vec![1, 2, 3]).await, Ok(4));
+    drop(tx);
+    assert!(tokio::join!(task).0.is_ok());
+}
+
+#[tokio::test]
+async fn unbounded_recv_with_dropped_response_receiver() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let response_receiver = tx.send(21);
+    drop(response_receiver);
+    let result = rx.recv_with(|input| *input * 2).await;
+    assert_eq!(result, Err(RequestError::SendError(42)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bounded_spawn_blocking_handler() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let (gate_tx, gate_rx) = std::sync::mpsc::channel::<()>();
+    let handle = bmrng::spawn_blocking_handler(rx, move |input| {
+        if input == 4 {
+            gate_rx.recv().expect("gate closed");
+        }
+        input * input
+    });
+    assert_eq!(tx.send_receive(8).await, Ok(64));
+    assert_eq!(tx.send_receive(3).await, Ok(9));
+    drop(tx.send(4).await);
+    gate_tx.send(()).expect("handler gone");
+    drop(tx);
+    let report = handle.await.expect("handler panicked");
+    assert_eq!(report.received, 3);
+    assert_eq!(report.responded, 2);
+    assert_eq!(report.unanswered, 1);
+}
+
+#[tokio::test]
+async fn unbounded_spawn_thread_handler() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let handle = bmrng::unbounded::spawn_thread_handler(rx, |input| input * 2);
+    assert_eq!(tx.send_receive(21).await, Ok(42));
+    drop(tx);
+    let report = handle.join().expect("handler panicked");
+    assert_eq!(report.received, 1);
+    assert_eq!(report.responded, 1);
+}
+
+#[tokio::test]
+async fn bounded_cancel_reason() {
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(4, Duration::from_millis(100));
+    pause();
+    let response_receiver = tx.send(1).await.unwrap();
+    let (_, cancelled) = rx.recv().await.unwrap();
+    assert_eq!(cancelled.cancel_reason(), None);
+    response_receiver.cancel();
+    assert_eq!(cancelled.cancel_reason(), Some(CancelReason::Cancelled));
+
+    let response_receiver = tx.send(2).await.unwrap();
+    let (_, dropped) = rx.recv().await.unwrap();
+    drop(response_receiver);
+    assert_eq!(dropped.cancel_reason(), Some(CancelReason::Dropped));
+
+    let task = tokio::spawn(async move {
+        let (_, timed_out) = rx.recv().await.unwrap();
+        advance(Duration::from_millis(200)).await;
+        sleep(Duration::from_micros(1)).await;
+        resume();
+        assert_eq!(timed_out.cancel_reason(), Some(CancelReason::TimedOut));
+    });
+    assert_eq!(
+        tx.send_receive(3).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert!(tokio::join!(task).0.is_ok());
+}
+
+#[tokio::test]
+async fn unbounded_cancel_reason() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let response_receiver = tx.send(1).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.cancel_reason(), None);
+    response_receiver.cancel();
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::Cancelled));
+    assert_eq!(
+        responder.respond(1),
+        Err(RespondError(1, ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn bounded_channel_const() {
+    let (tx, mut rx) = bmrng::channel_const::<i32, i32, 2>();
+    let mut first = tx.send(1).await.unwrap();
+    let mut second = tx.send(2).await.unwrap();
+    for _ in 0..2 {
+        let (input, responder) = rx.recv().await.unwrap();
+        assert!(responder.respond(input * 10).is_ok());
+    }
+    assert_eq!(tokio::join!(first.recv(), second.recv()), (Ok(10), Ok(20)));
+}
+
+#[tokio::test]
+async fn scope_borrowed_requests() {
+    let buffers = vec![vec![1u8; 16], vec![2u8; 32], vec![3u8; 64]];
+    let borrowed = &buffers;
+    let total = bmrng::scope(
+        1,
+        |buf: &[u8]| async move { buf.iter().map(|b| *b as usize).sum::<usize>() },
+        |tx| async move {
+            let mut total = 0;
+            for buf in borrowed {
+                total += tx.send_receive(buf.as_slice()).await.unwrap();
+            }
+            total
+        },
+    )
+    .await;
+    assert_eq!(total, 16 + 64 + 192);
+    assert_eq!(buffers.len(), 3);
+}
+
+#[cfg(feature = "tokio-util")]
+#[tokio::test]
+async fn bounded_cancellation_token() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let response_receiver = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    let token = responder.cancellation_token();
+    assert!(!token.is_cancelled());
+    let task = tokio::spawn(async move { token.cancelled().await });
+    drop(response_receiver);
+    assert!(tokio::join!(task).0.is_ok());
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::Dropped));
+}
+
+#[cfg(feature = "tokio-util")]
+#[tokio::test]
+async fn unbounded_cancellation_token_timeout() {
+    let (tx, mut rx) = bmrng::unbounded_channel_with_timeout::<i32, i32>(Duration::from_millis(10));
+    let task = tokio::spawn(async move {
+        let (_, responder) = rx.recv().await.unwrap();
+        responder.cancellation_token().cancelled().await;
+        assert_eq!(responder.cancel_reason(), Some(CancelReason::TimedOut));
+    });
+    assert_eq!(
+        tx.send_receive(1).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert!(tokio::join!(task).0.is_ok());
+}
+
+#[cfg(feature = "tokio-util")]
+#[tokio::test]
+async fn bounded_send_receive_with_token() {
+    use tokio_util::sync::CancellationToken;
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let token = CancellationToken::new();
+    let queued = tokio::spawn({
+        let tx = tx.clone();
+        let token = token.clone();
+        async move { tx.send_receive_with_token(1, &token).await }
+    });
+    let full = tokio::spawn({
+        let tx = tx.clone();
+        let token = token.clone();
+        async move { tx.send_receive_with_token(2, &token).await }
+    });
+    while tx.capacity() > 0 {
+        tokio::task::yield_now().await;
+    }
+    token.cancel();
+    assert_eq!(queued.await.unwrap(), Err(RequestError::Cancelled));
+    assert_eq!(full.await.unwrap(), Err(RequestError::Cancelled));
+    assert_eq!(rx.try_recv().map(|_| ()), Err(TryRecvError::Empty));
+}
+
+#[cfg(feature = "tokio-util")]
+#[tokio::test]
+async fn unbounded_send_receive_with_token() {
+    use tokio_util::sync::CancellationToken;
+
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let token = CancellationToken::new();
+    let request = tokio::spawn({
+        let token = token.clone();
+        async move { tx.send_receive_with_token(1, &token).await }
+    });
+    let (_, mut responder) = rx.recv().await.unwrap();
+    token.cancel();
+    responder.closed().await;
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::Cancelled));
+    assert_eq!(request.await.unwrap(), Err(RequestError::Cancelled));
+}
+
+#[tokio::test]
+async fn bounded_drop_pending_recv() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let mut response_receiver = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    let pending = tokio::time::timeout(Duration::from_millis(1), response_receiver.recv()).await;
+    assert!(pending.is_err());
+    assert_eq!(responder.cancel_reason(), None);
+    assert!(responder.respond(2).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(2));
+}
+
+#[tokio::test]
+async fn bounded_push_front_and_back() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let mut receivers = Vec::new();
+    for i in 1..=3 {
+        receivers.push(tx.send(i).await.unwrap());
+    }
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(payload.1.attempt(), 1);
+    rx.push_back(payload).expect("queue full");
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(payload.0, 2);
+    rx.push_front(payload);
+    let mut order = Vec::new();
+    for _ in 0..3 {
+        let (input, responder) = rx.recv().await.unwrap();
+        order.push((input, responder.attempt()));
+        assert!(responder.respond(input).is_ok());
+    }
+    assert_eq!(order, vec![(2, 2), (3, 1), (1, 2)]);
+    for (i, mut receiver) in receivers.into_iter().enumerate() {
+        assert_eq!(receiver.recv().await, Ok(i as i32 + 1));
+    }
+}
+
+#[tokio::test]
+async fn bounded_push_back_full() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let _first = tx.send(1).await.unwrap();
+    let payload = rx.recv().await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    let payload = rx.push_back(payload).expect_err("queue should be full");
+    assert_eq!(payload.1.attempt(), 1);
+}
+
+#[tokio::test]
+async fn unbounded_push_back_after_senders_dropped() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let _first = tx.send(1).unwrap();
+    let _second = tx.send(2).unwrap();
+    drop(tx);
+    let payload = rx.recv().await.unwrap();
+    rx.push_back(payload);
+    let (input, _) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!((input, responder.attempt()), (1, 2));
+    assert!(rx.recv().await.is_err());
+}
+
+#[tokio::test]
+async fn bounded_sender_requeue() {
+    let (tx, rx) = bmrng::channel::<i32, usize>(2);
+    let requeue = tx.clone();
+    let server = tokio::spawn(RequestReceiverStream::new(rx).for_each_concurrent(
+        None,
+        move |(input, responder)| {
+            let requeue = requeue.clone();
+            async move {
+                let attempt = responder.attempt();
+                if attempt < 3 {
+                    requeue.requeue((input, responder)).expect("queue full");
+                } else {
+                    assert!(responder.respond(attempt).is_ok());
+                }
+            }
+        },
+    ));
+    assert_eq!(tx.send_receive(1).await, Ok(3));
+    server.abort();
+}
+
+#[tokio::test]
+async fn bounded_sender_requeue_full() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let _first = tx.send(1).await.unwrap();
+    let payload = rx.recv().await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    let err = tx.requeue(payload).expect_err("queue should be full");
+    let TrySendError::Full(payload) = err else {
+        panic!("queue should be full");
+    };
+    assert_eq!(payload.1.attempt(), 1);
+}
+
+#[tokio::test]
+async fn unbounded_sender_requeue() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut response = tx.send(1).unwrap();
+    let _second = tx.send(2).unwrap();
+    let payload = rx.recv().await.unwrap();
+    tx.requeue(payload).unwrap();
+    let (input, _) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!((input, responder.attempt()), (1, 2));
+    assert!(responder.respond(10).is_ok());
+    assert_eq!(response.recv().await, Ok(10));
+}
+
+#[tokio::test]
+async fn bounded_peek() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    assert!(matches!(rx.try_peek(), Err(TryRecvError::Empty)));
+    let mut first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert_eq!(rx.peek().await, Ok(&1));
+    assert_eq!(rx.try_peek(), Ok(&1));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!((input, responder.attempt()), (1, 1));
+    assert!(responder.respond(10).is_ok());
+    assert_eq!(first.recv().await, Ok(10));
+    assert_eq!(rx.try_peek(), Ok(&2));
+    let (input, _) = rx.try_recv().unwrap();
+    assert_eq!(input, 2);
+    drop(tx);
+    assert_eq!(
+        rx.peek().await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn unbounded_peek() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let peeker = tokio::spawn(async move {
+        let peeked = *rx.peek().await.unwrap();
+        let (input, _) = rx.recv().await.unwrap();
+        (peeked, input)
+    });
+    let _response = tx.send(7).unwrap();
+    assert_eq!(peeker.await.unwrap(), (7, 7));
+}
+
+#[tokio::test]
+async fn bounded_peek_does_not_receive() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let depth = rx.watch_depth();
+    let mut response = tx.send(1).await.unwrap();
+    assert_eq!(rx.peek().await, Ok(&1));
+    assert_eq!(rx.try_peek(), Ok(&1));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), response.accepted())
+            .await
+            .is_err()
+    );
+    assert_eq!((*depth.borrow(), rx.len()), (1, 1));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(response.accepted().await, Ok(()));
+    assert_eq!((*depth.borrow(), rx.len()), (0, 0));
+    responder.respond(input * 2).unwrap();
+    assert_eq!(response.recv().await, Ok(2));
+}
+
+#[tokio::test]
+async fn unbounded_peek_skips_cancelled() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>().cancellable().build_unbounded();
+    let cancelled = tx.send(1).unwrap();
+    let response = tx.send(2).unwrap();
+    assert!(tx.cancel(cancelled.request_id()));
+    assert_eq!(rx.try_peek(), Ok(&2));
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), response.accepted())
+            .await
+            .is_err()
+    );
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    assert_eq!(response.accepted().await, Ok(()));
+}
+
+#[tokio::test]
+async fn bounded_recv_timeout() {
+    pause();
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(100))
+            .await
+            .map(|_| ()),
+        Err(RecvTimeoutError::Timeout)
+    );
+    let _response = tx.send(1).await.unwrap();
+    let (input, _) = rx.recv_timeout(Duration::from_millis(100)).await.unwrap();
+    assert_eq!(input, 1);
+    drop(tx);
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(100))
+            .await
+            .map(|_| ()),
+        Err(RecvTimeoutError::Disconnected)
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_recv_timeout() {
+    pause();
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let sender = tokio::spawn(async move {
+        sleep(Duration::from_millis(50)).await;
+        tx.send_receive(2).await
+    });
+    let (input, responder) = rx.recv_timeout(Duration::from_millis(100)).await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(sender.await.unwrap(), Ok(4));
+    assert_eq!(
+        rx.recv_timeout(Duration::from_millis(100))
+            .await
+            .map(|_| ()),
+        Err(RecvTimeoutError::Disconnected)
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_pause_resume() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(2);
+    rx.pause();
+    assert!(rx.is_paused());
+    let _first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert_eq!(tx.try_send(3).map(|_| ()), Err(TrySendError::Full(3)));
+    assert_eq!(rx.try_recv().map(|_| ()), Err(TryRecvError::Empty));
+
+    let handle = rx.pause_handle();
+    let worker = tokio::spawn(async move {
+        let (input, _) = rx.recv().await.unwrap();
+        (input, rx)
+    });
+    tokio::task::yield_now().await;
+    assert!(!worker.is_finished());
+    handle.resume();
+    let (input, rx) = worker.await.unwrap();
+    assert_eq!(input, 1);
+    assert!(!rx.is_paused());
+}
+
+#[tokio::test]
+async fn unbounded_pause_resume() {
+    pause();
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let handle = rx.pause_handle();
+    let mut stream = UnboundedRequestReceiverStream::new(rx);
+    let _first = tx.send(1).unwrap();
+    let (input, _) = stream.next().await.unwrap();
+    assert_eq!(input, 1);
+
+    handle.pause();
+    let _second = tx.send(2).unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(100), stream.next())
+            .await
+            .is_err()
+    );
+    handle.resume();
+    let (input, _) = stream.next().await.unwrap();
+    assert_eq!(input, 2);
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_close_channel() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(2);
+    let other = tx.clone();
+    let queued = tx.send(1).await.unwrap();
+    other.close_channel();
+    assert!(tx.is_closed());
+    assert_eq!(tx.send(2).await.map(|_| ()), Err(SendError(2)));
+    assert_eq!(tx.try_send(3).map(|_| ()), Err(TrySendError::Closed(3)));
+
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 1);
+    responder.respond(input * 2).unwrap();
+    assert_eq!(queued.await, Ok(2));
+    assert_eq!(
+        rx.recv().await.map(|_| ()),
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    tx.closed().await;
+}
+
+#[tokio::test]
+async fn unbounded_close_channel() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut stream = UnboundedRequestReceiverStream::new(rx);
+    let closer = tx.clone();
+    let waiting = tokio::spawn(async move { stream.next().await.is_none() });
+    tokio::task::yield_now().await;
+    closer.close_channel();
+    assert!(waiting.await.unwrap());
+    assert_eq!(tx.send(1).map(|_| ()), Err(SendError(1)));
+    tx.closed().await;
+}
+
+#[tokio::test]
+async fn bounded_cancel_by_id() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .cancellable()
+        .build();
+    let queued = tx.send(1).await.unwrap();
+    let processing = tx.send(2).await.unwrap();
+    assert!(tx.cancel(queued.request_id()));
+    assert!(!tx.cancel(queued.request_id()));
+    assert_eq!(queued.await, Err(ReceiveError::Cancelled));
+
+    let (input, mut responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    assert!(!responder.is_closed());
+    let id = processing.request_id();
+    let waiting = tokio::spawn(async move { processing.await });
+    let closed = tokio::spawn(async move {
+        responder.closed().await;
+        responder
+    });
+    tokio::task::yield_now().await;
+    assert!(tx.cancel(id));
+    let responder = closed.await.unwrap();
+    assert!(responder.is_closed());
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::Cancelled));
+    assert!(matches!(responder.respond(4), Err(RespondError(4, ..))));
+    assert_eq!(waiting.await.unwrap(), Err(ReceiveError::Cancelled));
+    assert_eq!(tx.pending_responses(), 0);
+}
+
+#[tokio::test]
+async fn bounded_cancel_by_id_not_cancellable() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let response = tx.send(1).await.unwrap();
+    assert!(!tx.cancel(response.request_id()));
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(response.await, Ok(1));
+}
+
+#[tokio::test]
+async fn unbounded_cancel_by_id() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>().cancellable().build_unbounded();
+    let cancelled = tx.send(1).unwrap();
+    let answered = tx.send(2).unwrap();
+    assert!(tx.cancel(cancelled.request_id()));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    let id = answered.request_id();
+    responder.respond(input).unwrap();
+    assert_eq!(answered.await, Ok(2));
+    assert!(!tx.cancel(id));
+    assert_eq!(rx.try_recv().map(|_| ()), Err(TryRecvError::Empty));
+}
+
+#[tokio::test]
+async fn bounded_accepted() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(2);
+    let mut response = tx.send(1).await.unwrap();
+    assert!(
+        tokio::time::timeout(Duration::from_millis(10), response.accepted())
+            .await
+            .is_err()
+    );
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(response.accepted().await, Ok(()));
+    responder.respond(input * 2).unwrap();
+    assert_eq!(response.recv().await, Ok(2));
+
+    let dropped = tx.send(2).await.unwrap();
+    drop(rx);
+    assert_eq!(dropped.accepted().await, Err(ReceiveError::RecvError));
+}
+
+#[tokio::test]
+async fn unbounded_accepted() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>().cancellable().build_unbounded();
+    let response = tx.send(1).unwrap();
+    let accepted = tokio::spawn(async move {
+        response.accepted().await.unwrap();
+        response.await
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(accepted.await.unwrap(), Ok(1));
+
+    let cancelled = tx.send(2).unwrap();
+    assert!(tx.cancel(cancelled.request_id()));
+    assert_eq!(cancelled.accepted().await, Err(ReceiveError::Cancelled));
+}
+
+#[tokio::test]
+async fn bounded_watch_depth() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let mut depth = tx.watch_depth();
+    assert_eq!(*depth.borrow_and_update(), 0);
+    let _first = tx.send(1).await.unwrap();
+    let _second = tx.try_send(2).unwrap();
+    tx.send_forget(3).await.unwrap();
+    assert!(depth.has_changed().unwrap());
+    assert_eq!(*depth.borrow_and_update(), 3);
+    assert_eq!(*rx.watch_depth().borrow(), 3);
+
+    let (_, first) = rx.recv().await.unwrap();
+    assert_eq!(*depth.borrow_and_update(), 2);
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 4).await, 2);
+    assert_eq!(*depth.borrow_and_update(), 0);
+    rx.push_back((1, first)).unwrap();
+    assert_eq!(*depth.borrow_and_update(), 1);
+    assert!(rx.try_recv().is_ok());
+    assert_eq!(*depth.borrow_and_update(), 0);
+    assert!(!depth.has_changed().unwrap());
+}
+
+#[tokio::test]
+async fn unbounded_watch_depth() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut depth = rx.watch_depth();
+    let _first = tx.send(1).unwrap();
+    tx.send_forget(2).unwrap();
+    depth.changed().await.unwrap();
+    assert_eq!(*depth.borrow_and_update(), 2);
+    assert_eq!(*tx.watch_depth().borrow(), 2);
+
+    let (_, first) = rx.recv().await.unwrap();
+    assert_eq!(*depth.borrow_and_update(), 1);
+    tx.requeue((1, first)).unwrap();
+    assert_eq!(*depth.borrow_and_update(), 2);
+    assert_eq!(rx.close_and_drain().await, 2);
+    assert_eq!(*depth.borrow(), 0);
+}
+
+#[tokio::test]
+async fn bounded_sender_id() {
+    use bmrng::PayloadExt;
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(8);
+    let clone = tx.clone();
+    let tenant = tx.labeled("tenant-a");
+    let tenant_clone = tenant.clone();
+    assert_eq!(tx.id().as_u64(), 1);
+    assert_eq!(clone.id().as_u64(), 2);
+    assert_eq!(tenant.id().as_u64(), 3);
+    assert_eq!(tenant_clone.id().label(), Some("tenant-a"));
+    assert_eq!(tenant_clone.id().to_string(), "tenant-a (sender #4)");
+    assert_eq!(tx.downgrade().upgrade().unwrap().id(), tx.id());
+
+    let _first = tx.send(1).await.unwrap();
+    clone.send_forget(2).await.unwrap();
+    let _third = tenant.try_send(3).unwrap();
+    let _fourth = tenant_clone.reserve().await.unwrap().send(4);
+    let mut senders = Vec::new();
+    for _ in 0..4 {
+        let payload = rx.recv().await.unwrap();
+        senders.push(payload.sender_id().unwrap().to_string());
+    }
+    assert_eq!(
+        senders,
+        vec![
+            "sender #1",
+            "sender #2",
+            "tenant-a (sender #3)",
+            "tenant-a (sender #4)"
+        ]
+    );
+}
+
+#[tokio::test]
+async fn unbounded_sender_id() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let tenant = tx.labeled("tenant-b");
+    let _first = tenant.send(1).unwrap();
+    tx.send_forget(2).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.sender_id(), Some(tenant.id()));
+    assert_eq!(tenant.id().label(), Some("tenant-b"));
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.sender_id().map(|id| id.as_u64()), Some(1));
+    assert_eq!(responder.sender_id().unwrap().label(), None);
+}
+
+#[tokio::test]
+async fn bounded_try_send() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let mut response_receiver = tx.try_send(1).expect("channel should have capacity");
+    assert_eq!(tx.try_send(2).map(|_| ()), Err(TrySendError::Full(2)));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(2));
+    drop(rx);
+    assert_eq!(tx.try_send(3).map(|_| ()), Err(TrySendError::Closed(3)));
+}
+
+#[tokio::test]
+async fn bounded_send_timeout() {
+    pause();
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let mut response_receiver = tx
+        .send_timeout(1, Duration::from_millis(100))
+        .await
+        .expect("channel should have capacity");
+    let result = tx.send_timeout(2, Duration::from_millis(100)).await;
+    assert_eq!(result.map(|_| ()), Err(SendTimeoutError::Timeout(2)));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(2));
+    drop(rx);
+    let result = tx.send_timeout(3, Duration::from_millis(100)).await;
+    assert_eq!(result.map(|_| ()), Err(SendTimeoutError::Closed(3)));
+    resume();
+}
+
+#[test]
+fn bounded_blocking_send_receive() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let handle = bmrng::spawn_thread_handler(rx, |input| input * 2);
+    assert_eq!(tx.blocking_send_receive(21), Ok(42));
+    let mut response_receiver = tx.blocking_send(4).unwrap();
+    assert_eq!(response_receiver.blocking_recv(), Ok(8));
+    assert_eq!(
+        response_receiver.blocking_recv(),
+        Err(ReceiveError::RecvError)
+    );
+    drop(tx);
+    let report = handle.join().expect("handler panicked");
+    assert_eq!(report.responded, 2);
+}
+
+#[test]
+fn bounded_blocking_recv_timeout() {
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(10));
+    let mut response_receiver = tx.blocking_send(1).unwrap();
+    let (_, responder) = rx.blocking_recv().unwrap();
+    assert_eq!(
+        response_receiver.blocking_recv(),
+        Err(ReceiveError::TimeoutError)
+    );
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::TimedOut));
+    drop(rx);
+    assert_eq!(tx.blocking_send_receive(2), Err(RequestError::SendError(2)));
+}
+
+#[test]
+fn bounded_blocking_send_timeout() {
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .send_timeout(Duration::from_millis(20))
+        .build();
+    let _first = tx.blocking_send(1).unwrap();
+    assert_eq!(tx.blocking_send(2).map(|_| ()), Err(SendError(2)));
+    assert_eq!(
+        tx.blocking_send_receive(3),
+        Err(RequestError::SendTimeoutError(3))
+    );
+
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .max_outstanding(1)
+        .send_timeout(Duration::from_millis(20))
+        .build();
+    let _first = tx.blocking_send(1).unwrap();
+    assert_eq!(
+        tx.blocking_send_receive(2),
+        Err(RequestError::SendTimeoutError(2))
+    );
+
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .rate_limit(1, 1)
+        .send_timeout(Duration::from_millis(20))
+        .build();
+    let _first = tx.blocking_send(1).unwrap();
+    let started = std::time::Instant::now();
+    assert_eq!(tx.blocking_send(2).map(|_| ()), Err(SendError(2)));
+    assert!(started.elapsed() < Duration::from_millis(500));
+}
+
+#[tokio::test]
+async fn bounded_reserve() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let permit = tx.reserve().await.unwrap();
+    assert!(matches!(tx.try_send(1), Err(TrySendError::Full(1))));
+    let mut response_receiver = permit.send(2);
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(4));
+
+    let permit = tx.reserve_owned().await.unwrap();
+    let task = tokio::spawn(async move { permit.send(3).recv().await });
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(task.await.unwrap(), Ok(6));
+
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    drop(rx);
+    assert!(tx.reserve().await.is_err());
+}
+
+#[tokio::test]
+async fn bounded_weak_sender() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let weak = tx.downgrade();
+    let upgraded = weak.upgrade().expect("the channel is still open");
+    drop(upgraded);
+    drop(tx);
+    assert!(weak.upgrade().is_none());
+    assert!(rx.recv().await.is_err());
+}
+
+#[tokio::test]
+async fn unbounded_weak_sender() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let weak = tx.downgrade().clone();
+    let upgraded = weak.upgrade().expect("the channel is still open");
+    drop(tx);
+    tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.unwrap();
+        let _ = responder.respond(input * 2);
+        assert!(rx.recv().await.is_err());
+    });
+    assert_eq!(upgraded.send_receive(2).await, Ok(4));
+    drop(upgraded);
+    assert!(weak.upgrade().is_none());
+}
+
+#[tokio::test]
+async fn bounded_len_and_capacity() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    assert!(tx.is_empty() && rx.is_empty());
+    assert_eq!((tx.capacity(), tx.max_capacity()), (4, 4));
+    let _first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert_eq!((tx.len(), tx.capacity()), (2, 2));
+    assert_eq!((rx.len(), rx.capacity(), rx.max_capacity()), (2, 2, 4));
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(tx.len(), 1);
+    rx.push_front(payload);
+    assert_eq!(rx.len(), 2);
+}
+
+#[tokio::test]
+async fn unbounded_len() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    assert!(rx.is_empty());
+    let _first = tx.send(1).unwrap();
+    let _second = tx.send(2).unwrap();
+    assert_eq!(rx.len(), 2);
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(rx.len(), 1);
+    rx.push_front(payload);
+    assert_eq!(rx.len(), 2);
+}
+
+#[tokio::test]
+async fn bounded_try_recv() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    let _response_receiver = tx.send(1).await.unwrap();
+    let payload = rx.try_recv().expect("a request is queued");
+    assert_eq!(payload.0, 1);
+    rx.push_front(payload);
+    drop(tx);
+    assert!(rx.try_recv().is_ok());
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+}
+
+#[tokio::test]
+async fn unbounded_try_recv() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Empty)));
+    let _response_receiver = tx.send(1).unwrap();
+    assert!(rx.try_recv().is_ok());
+    drop(tx);
+    assert!(matches!(rx.try_recv(), Err(TryRecvError::Disconnected)));
+}
+
+#[tokio::test]
+async fn bounded_recv_many() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(8);
+    let mut receivers = Vec::new();
+    for i in 0..5 {
+        receivers.push(tx.send(i).await.unwrap());
+    }
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 0).await, 0);
+    assert_eq!(rx.recv_many(&mut buffer, 3).await, 3);
+    assert_eq!(rx.recv_many(&mut buffer, 3).await, 2);
+    let requests: Vec<i32> = buffer.iter().map(|payload| payload.0).collect();
+    assert_eq!(requests, vec![0, 1, 2, 3, 4]);
+    let payload = buffer.pop().unwrap();
+    rx.push_front(payload);
+    drop(tx);
+    assert_eq!(rx.recv_many(&mut buffer, 3).await, 1);
+    assert_eq!(rx.recv_many(&mut buffer, 3).await, 0);
+}
+
+#[tokio::test]
+async fn unbounded_recv_many() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut receivers = Vec::new();
+    for i in 0..3 {
+        receivers.push(tx.send(i).unwrap());
+    }
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 8).await, 3);
+    drop(tx);
+    assert_eq!(rx.recv_many(&mut buffer, 8).await, 0);
+}
+
+#[tokio::test]
+async fn bounded_shared_receiver() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(4);
+    let rx = rx.into_shared();
+    let workers: Vec<_> = (0..3)
+        .map(|_| {
+            let rx = rx.clone();
+            tokio::spawn(async move {
+                let mut handled = 0;
+                while let Ok((input, responder)) = rx.recv().await {
+                    let _ = responder.respond(input * 2);
+                    handled += 1;
+                }
+                handled
+            })
+        })
+        .collect();
+    drop(rx);
+    for i in 0..10 {
+        assert_eq!(tx.send_receive(i).await, Ok(i * 2));
+    }
+    drop(tx);
+    let mut handled = 0;
+    for worker in workers {
+        handled += worker.await.unwrap();
+    }
+    assert_eq!(handled, 10);
+}
+
+#[tokio::test]
+async fn unbounded_shared_receiver() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let rx = rx.into_shared();
+    let other = rx.clone();
+    assert!(matches!(other.try_recv(), Err(TryRecvError::Empty)));
+    let mut response_receiver = tx.send(1).unwrap();
+    let (input, responder) = other.recv().await.unwrap();
+    assert!(responder.respond(input + 1).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(2));
+    drop(tx);
+    assert!(rx.recv().await.is_err());
+}
+
+#[tokio::test]
+async fn bounded_await_response_receiver() {
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let task = tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.unwrap();
+        let _ = responder.respond(input * 2);
+        let (_, responder) = rx.recv().await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        assert_eq!(responder.cancel_reason(), Some(CancelReason::TimedOut));
+    });
+    assert_eq!(tx.send(21).await.unwrap().await, Ok(42));
+    pause();
+    assert_eq!(
+        tx.send(1).await.unwrap().await,
+        Err(ReceiveError::TimeoutError)
+    );
+    task.await.unwrap();
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_await_response_receiver_set_timeout() {
+    use std::future::IntoFuture;
+
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let mut response = tx.send(1).await.unwrap().into_future();
+    tokio::select! {
+        _ = &mut response => panic!("the response should still be pending"),
+        _ = sleep(Duration::from_millis(50)) => {}
+    }
+    response.set_timeout(Some(Duration::from_millis(500)));
+    let (input, responder) = rx.recv().await.unwrap();
+    tokio::spawn(async move {
+        sleep(Duration::from_millis(300)).await;
+        responder.respond(input).unwrap();
+    });
+    assert_eq!(response.await, Ok(1));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_receive_timeout() {
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let task = tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            sleep(Duration::from_millis(200)).await;
+            let _ = responder.respond(input);
+        }
+    });
+    assert_eq!(
+        tx.send_receive_timeout(1, Duration::from_millis(300)).await,
+        Ok(1)
+    );
+    assert_eq!(
+        tx.send_receive_timeout(2, Duration::from_millis(50)).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    let mut response_receiver = tx.send(3).await.unwrap();
+    response_receiver.set_timeout(None);
+    assert_eq!(response_receiver.recv().await, Ok(3));
+    drop(tx);
+    task.await.unwrap();
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_send_receive_timeout() {
+    pause();
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let task = tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.unwrap();
+        sleep(Duration::from_millis(200)).await;
+        let _ = responder.respond(input);
+    });
+    assert_eq!(
+        tx.send_receive_timeout(1, Duration::from_millis(100)).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    task.await.unwrap();
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_forget() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    tx.send_forget(1).await.unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 1);
+    assert!(!responder.expects_response());
+    assert!(!responder.is_closed());
+    assert_eq!(responder.cancel_reason(), None);
+    assert!(responder.respond(2).is_ok());
+    drop(rx);
+    assert_eq!(tx.send_forget(3).await, Err(SendError(3)));
+}
+
+#[tokio::test]
+async fn bounded_send_forget_outstanding() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .max_outstanding(1)
+        .build();
+    tx.send_forget(1).await.unwrap();
+    assert!(timeout(Duration::from_millis(10), tx.send_forget(2))
+        .await
+        .is_err());
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(responder);
+    tx.send_forget(3).await.unwrap();
+    let tenant = tx.with_quota(1);
+    drop(rx.recv().await.unwrap());
+    tenant.send_forget(4).await.unwrap();
+    assert!(timeout(Duration::from_millis(10), tenant.send_forget(5))
+        .await
+        .is_err());
+
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(bmrng::Admission::new().max_depth(1))
+        .build();
+    tx.send_forget(1).await.unwrap();
+    tx.send_forget(2).await.unwrap();
+    assert_eq!(tx.send_forget(3).await, Err(SendError(3)));
+}
+
+#[tokio::test]
+async fn unbounded_send_forget() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    tx.send_forget(1).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert!(!responder.expects_response());
+    assert!(responder.respond(2).is_ok());
+}
+
+#[tokio::test]
+async fn bounded_guarded_responder() {
+    let (tx, mut rx) = bmrng::channel::<i32, Result<i32, String>>(1);
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            let responder = responder.or_else_on_drop(Err("handler gave up".to_string()));
+            if input < 0 {
+                continue;
+            }
+            if input == 0 {
+                let _ = tokio::spawn(async move {
+                    let _responder = responder;
+                    panic!("handler panicked");
+                })
+                .await;
+                continue;
+            }
+            let _ = responder.respond(Ok(input * 2));
+        }
+    });
+    assert_eq!(tx.send_receive(2).await, Ok(Ok(4)));
+    assert_eq!(
+        tx.send_receive(-1).await,
+        Ok(Err("handler gave up".to_string()))
+    );
+    assert_eq!(
+        tx.send_receive(0).await,
+        Ok(Err("handler gave up".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn unbounded_guarded_responder() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut response_receiver = tx.send(1).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    let guarded = responder.or_else_on_drop(-1);
+    assert!(!guarded.is_closed());
+    drop(guarded);
+    assert_eq!(response_receiver.recv().await, Ok(-1));
+
+    let mut response_receiver = tx.send(2).unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    let responder = responder.or_else_on_drop(-1).into_inner();
+    assert!(responder.respond(input).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(2));
+}
+
+#[tokio::test]
+async fn bounded_responder_closed() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let response_receiver = tx.send(1).await.unwrap();
+    let (_, mut responder) = rx.recv().await.unwrap();
+    let task = tokio::spawn(async move {
+        tokio::select! {
+            _ = responder.closed() => responder.cancel_reason(),
+            _ = sleep(Duration::from_secs(60)) => None,
+        }
+    });
+    response_receiver.cancel();
+    assert_eq!(task.await.unwrap(), Some(CancelReason::Cancelled));
+}
+
+#[tokio::test]
+async fn unbounded_responder_closed() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let response_receiver = tx.send(1).unwrap();
+    let (_, mut responder) = rx.recv().await.unwrap();
+    drop(response_receiver);
+    responder.closed().await;
+    assert!(responder.is_closed());
+}
+
+#[tokio::test]
+async fn bounded_responder_deadline() {
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let mut response_receiver = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.time_remaining(), Some(Duration::from_millis(100)));
+    advance(Duration::from_millis(40)).await;
+    assert_eq!(responder.time_remaining(), Some(Duration::from_millis(60)));
+    response_receiver.set_timeout(Some(Duration::from_millis(200)));
+    assert_eq!(responder.time_remaining(), Some(Duration::from_millis(200)));
+    response_receiver.set_timeout(None);
+    assert_eq!(responder.deadline(), None);
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let _response_receiver = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.time_remaining(), None);
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_responder_deadline() {
+    pause();
+    let (tx, mut rx) =
+        bmrng::unbounded_channel_with_timeout::<i32, i32>(Duration::from_millis(100));
+    let _response_receiver = tx.send(1).unwrap();
+    advance(Duration::from_millis(150)).await;
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.time_remaining(), Some(Duration::ZERO));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_serve() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let server = tokio::spawn(rx.serve(|input| async move { input * 2 }));
+    assert_eq!(tx.send_receive(21).await, Ok(42));
+    let response_receiver = tx.send(1).await.unwrap();
+    drop(response_receiver);
+    drop(tx);
+    let report = server.await.unwrap();
+    assert_eq!(report.received, 2);
+    assert_eq!(report.responded + report.unanswered, 2);
+}
+
+#[tokio::test]
+async fn bounded_serve_report_timed_out() {
+    pause();
+    let (tx, rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    let server = tokio::spawn(rx.serve(|input| async move {
+        sleep(Duration::from_millis(input as u64)).await;
+        input
+    }));
+    assert_eq!(tx.send_receive(10).await, Ok(10));
+    assert_eq!(
+        tx.send_receive(200).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    drop(tx);
+    let report = server.await.unwrap();
+    resume();
+    assert_eq!(
+        (report.received, report.responded, report.timed_out),
+        (2, 1, 1)
+    );
+    assert_eq!((report.unanswered, report.panicked), (0, 0));
+}
+
+#[tokio::test]
+async fn bounded_serve_until_shutdown() {
+    pause();
+    let (tx, rx) = bmrng::channel::<u64, u64>(1);
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(rx.serve_until_shutdown(
+        2,
+        async move {
+            let _ = stopped.await;
+        },
+        Duration::from_millis(100),
+        |input| async move {
+            sleep(Duration::from_millis(input)).await;
+            input
+        },
+    ));
+    let mut requests = Vec::new();
+    for input in [10, 1000, 5000] {
+        let tx = tx.clone();
+        requests.push(tokio::spawn(async move { tx.send_receive(input).await }));
+        sleep(Duration::from_millis(1)).await;
+    }
+    sleep(Duration::from_millis(50)).await;
+    stop.send(()).unwrap();
+    let report = server.await.unwrap();
+    resume();
+    let mut responses = Vec::new();
+    for request in requests {
+        responses.push(request.await.unwrap());
+    }
+    assert_eq!(
+        responses,
+        vec![
+            Ok(10),
+            Err(RequestError::ShutDown),
+            Err(RequestError::ShutDown)
+        ]
+    );
+    assert_eq!(
+        (report.received, report.responded, report.shut_down),
+        (3, 1, 2)
+    );
+    assert!(tx.is_closed());
+}
+
+#[tokio::test]
+async fn unbounded_serve_until_shutdown_drains() {
+    pause();
+    let (tx, rx) = bmrng::unbounded_channel::<u64, u64>();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(rx.serve_until_shutdown(
+        4,
+        async move {
+            let _ = stopped.await;
+        },
+        Duration::from_secs(1),
+        |input| async move {
+            sleep(Duration::from_millis(input)).await;
+            input
+        },
+    ));
+    let request = {
+        let tx = tx.clone();
+        tokio::spawn(async move { tx.send_receive(200).await })
+    };
+    sleep(Duration::from_millis(10)).await;
+    stop.send(()).unwrap();
+    assert_eq!(request.await.unwrap(), Ok(200));
+    let report = server.await.unwrap();
+    resume();
+    assert_eq!((report.received, report.responded), (1, 1));
+    assert_eq!(report.shut_down, 0);
+    assert!(tx.send_receive(1).await.is_err());
+}
+
+#[tokio::test]
+async fn bounded_serve_with_retry() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let (tx, rx) = bmrng::channel::<i32, Result<i32, String>>(1);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = tokio::spawn({
+        let calls = calls.clone();
+        rx.serve_with_retry(
+            bmrng::RetryPolicy::new(3),
+            |err: &String| err.as_str() == "transient",
+            move |input| {
+                let attempt = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                async move {
+                    match input {
+                        0 if attempt < 3 => Err("transient".to_string()),
+                        0 => Ok(attempt as i32),
+                        1 => Err("transient".to_string()),
+                        _ => Err("fatal".to_string()),
+                    }
+                }
+            },
+        )
+    });
+    assert_eq!(tx.send_receive(0).await, Ok(Ok(3)));
+    assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+    assert_eq!(tx.send_receive(1).await, Ok(Err("transient".to_string())));
+    assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+    assert_eq!(tx.send_receive(2).await, Ok(Err("fatal".to_string())));
+    assert_eq!(calls.swap(0, Ordering::SeqCst), 1);
+    drop(tx);
+    let report = server.await.unwrap();
+    assert_eq!(
+        (report.received, report.responded, report.failed),
+        (3, 1, 2)
+    );
+}
+
+#[tokio::test]
+async fn unbounded_serve_concurrent_with_retry() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    pause();
+    let (tx, rx) = bmrng::unbounded_channel::<i32, Result<i32, i32>>();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = tokio::spawn({
+        let calls = calls.clone();
+        rx.serve_concurrent_with_retry(
+            2,
+            bmrng::RetryPolicy::new(2).backoff(bmrng::Backoff::Fixed(Duration::from_millis(50))),
+            |_: &i32| true,
+            move |input| {
+                let first = calls.fetch_add(1, Ordering::SeqCst) < 2;
+                async move {
+                    if first {
+                        Err(input)
+                    } else {
+                        Ok(input * 10)
+                    }
+                }
+            },
+        )
+    });
+    let started = tokio::time::Instant::now();
+    let (first, second) = tokio::join!(tx.send_receive(1), tx.send_receive(2));
+    assert_eq!((first, second), (Ok(Ok(10)), Ok(Ok(20))));
+    assert!(started.elapsed() < Duration::from_millis(60));
+    drop(tx);
+    let report = server.await.unwrap();
+    resume();
+    assert_eq!(
+        (report.received, report.responded, report.failed),
+        (2, 2, 0)
+    );
+}
+
+#[tokio::test]
+async fn unbounded_serve_with_state() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let offset = std::sync::Arc::new(100);
+    let server =
+        tokio::spawn(rx.serve_with_state(offset, |offset, input| async move { *offset + input }));
+    assert_eq!(tx.send_receive(1).await, Ok(101));
+    assert_eq!(tx.send_receive(2).await, Ok(102));
+    drop(tx);
+    assert_eq!(server.await.unwrap().responded, 2);
+}
+
+#[tokio::test]
+async fn bounded_serve_concurrent_with_state() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    pause();
+    let (tx, rx) = bmrng::channel::<u64, usize>(4);
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let server = tokio::spawn(rx.serve_concurrent_with_state(
+        2,
+        in_flight.clone(),
+        |in_flight, input| async move {
+            let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            sleep(Duration::from_millis(input)).await;
+            in_flight.fetch_sub(1, Ordering::SeqCst);
+            current
+        },
+    ));
+    let (first, second) = tokio::join!(tx.send_receive(100), tx.send_receive(100));
+    resume();
+    let mut seen = vec![first.unwrap(), second.unwrap()];
+    seen.sort_unstable();
+    assert_eq!(seen, vec![1, 2]);
+    drop(tx);
+    assert_eq!(server.await.unwrap().responded, 2);
+    assert_eq!(Arc::strong_count(&in_flight), 1);
+}
+
+#[tokio::test]
+async fn unbounded_serve_until_shutdown_with_state() {
+    pause();
+    let (tx, rx) = bmrng::unbounded_channel::<u64, u64>();
+    let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(rx.serve_until_shutdown_with_state(
+        2,
+        async move {
+            let _ = stopped.await;
+        },
+        Duration::from_secs(1),
+        std::sync::Arc::new(1000),
+        |offset, input| async move {
+            sleep(Duration::from_millis(input)).await;
+            *offset + input
+        },
+    ));
+    let request = {
+        let tx = tx.clone();
+        tokio::spawn(async move { tx.send_receive(200).await })
+    };
+    sleep(Duration::from_millis(10)).await;
+    stop.send(()).unwrap();
+    assert_eq!(request.await.unwrap(), Ok(1200));
+    let report = server.await.unwrap();
+    resume();
+    assert_eq!((report.received, report.responded), (1, 1));
+}
+
+#[tokio::test]
+async fn bounded_serve_with_retry_and_state() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    let (tx, rx) = bmrng::channel::<i32, Result<i32, String>>(1);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = tokio::spawn(rx.serve_with_retry_and_state(
+        calls.clone(),
+        bmrng::RetryPolicy::new(3),
+        |err: &String| err.as_str() == "transient",
+        |calls, input| async move {
+            let attempt = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt < 3 {
+                Err("transient".to_string())
+            } else {
+                Ok(input + attempt as i32)
+            }
+        },
+    ));
+    assert_eq!(tx.send_receive(10).await, Ok(Ok(13)));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    drop(tx);
+    let report = server.await.unwrap();
+    assert_eq!((report.received, report.responded), (1, 1));
+}
+
+#[tokio::test]
+async fn unbounded_serve_concurrent_with_retry_and_state() {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    pause();
+    let (tx, rx) = bmrng::unbounded_channel::<i32, Result<i32, i32>>();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let server = tokio::spawn(rx.serve_concurrent_with_retry_and_state(
+        2,
+        calls.clone(),
+        bmrng::RetryPolicy::new(2).backoff(bmrng::Backoff::Fixed(Duration::from_millis(50))),
+        |_: &i32| true,
+        |calls, input| async move {
+            if calls.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(input)
+            } else {
+                Ok(input * 10)
+            }
+        },
+    ));
+    let (first, second) = tokio::join!(tx.send_receive(1), tx.send_receive(2));
+    assert_eq!((first, second), (Ok(Ok(10)), Ok(Ok(20))));
+    drop(tx);
+    let report = server.await.unwrap();
+    resume();
+    assert_eq!(
+        (report.received, report.responded, report.failed),
+        (2, 2, 0)
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+}
+
+#[tokio::test]
+async fn bounded_serve_concurrent() {
+    let (tx, rx) = bmrng::channel::<u64, u64>(8);
+    let in_flight = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let max_in_flight = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let (counter, max) = (in_flight.clone(), max_in_flight.clone());
+    let server = tokio::spawn(rx.serve_concurrent(2, move |input| {
+        let (counter, max) = (counter.clone(), max.clone());
+        async move {
+            let current = counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            max.fetch_max(current, std::sync::atomic::Ordering::SeqCst);
+            sleep(Duration::from_millis(10)).await;
+            counter.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            if input == 0 {
+                panic!("handler panicked");
+            }
+            input * 2
+        }
+    }));
+    let responses = futures_util::future::join_all((0..6).map(|i| tx.send_receive(i))).await;
+    assert_eq!(
+        responses[0],
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(responses[5], Ok(10));
+    drop(tx);
+    let report = server.await.unwrap();
+    assert_eq!(
+        (report.received, report.responded, report.unanswered),
+        (6, 5, 0)
+    );
+    assert_eq!(report.panicked, 1);
+    assert_eq!(max_in_flight.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn unbounded_serve_concurrent() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    let server = tokio::spawn(rx.serve_concurrent(4, |input| async move { input + 1 }));
+    let responses = futures_util::future::join_all((0..8).map(|i| tx.send_receive(i))).await;
+    assert_eq!(responses, (1..9).map(Ok).collect::<Vec<_>>());
+    drop(tx);
+    assert_eq!(server.await.unwrap().responded, 8);
+}
+
+#[tokio::test]
+async fn bounded_sink() {
+    use futures_util::SinkExt;
+
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let server = tokio::spawn(rx.serve(|input| async move { input * 2 }));
+    let (mut sink, receivers) = tx.into_sink();
+    let mut requests = futures_util::stream::iter((1..=4).map(Ok));
+    sink.send_all(&mut requests).await.unwrap();
+    sink.close().await.unwrap();
+    let responses: Vec<_> = receivers
+        .then(std::future::IntoFuture::into_future)
+        .collect()
+        .await;
+    assert_eq!(responses, vec![Ok(2), Ok(4), Ok(6), Ok(8)]);
+    assert_eq!(server.await.unwrap().responded, 4);
+    assert_eq!(sink.send(5).await, Err(SendError(())));
+}
+
+#[tokio::test]
+async fn bounded_send_all() {
+    let (tx, rx) = bmrng::channel::<u64, u64>(4);
+    tokio::spawn(rx.serve_concurrent(4, |input| async move {
+        sleep(Duration::from_millis(10 - input)).await;
+        input * 2
+    }));
+    let responses: Vec<_> = tx
+        .send_all(futures_util::stream::iter(0..10))
+        .collect()
+        .await;
+    assert_eq!(responses, (0..10).map(|i| Ok(i * 2)).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn bounded_send_receive_unordered() {
+    pause();
+    let (tx, rx) = bmrng::channel::<u64, u64>(4);
+    tokio::spawn(rx.serve_concurrent(4, |input| async move {
+        sleep(Duration::from_millis(40 - input * 10)).await;
+        input * 2
+    }));
+    let mut responses: Vec<_> = tx
+        .send_receive_unordered(futures_util::stream::iter(0..4), 4)
+        .collect()
+        .await;
+    assert_eq!(responses[0], (3, Ok(6)));
+    responses.sort_by_key(|(index, _)| *index);
+    assert_eq!(
+        responses,
+        vec![(0, Ok(0)), (1, Ok(2)), (2, Ok(4)), (3, Ok(6))]
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_poll_request_sender() {
+    use futures_util::future::poll_fn;
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let mut sender = bmrng::PollRequestSender::new(tx);
+    assert_eq!(sender.send_item(1).map(|_| ()), Err(PollSendError(Some(1))));
+    poll_fn(|cx| sender.poll_reserve(cx)).await.unwrap();
+    let mut response_receiver = sender.send_item(2).unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input * 2).is_ok());
+    assert_eq!(response_receiver.recv().await, Ok(4));
+
+    poll_fn(|cx| sender.poll_reserve(cx)).await.unwrap();
+    assert!(sender.abort_send());
+    assert!(!sender.abort_send());
+    assert!(rx.try_recv().is_err());
+
+    sender.close();
+    assert!(sender.is_closed());
+    assert_eq!(
+        poll_fn(|cx| sender.poll_reserve(cx)).await,
+        Err(PollSendError(None))
+    );
+}
+
+#[tokio::test]
+async fn bounded_close_and_drain() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let mut first = tx.send(1).await.unwrap();
+    let mut second = tx.send(2).await.unwrap();
+    let payload = rx.recv().await.unwrap();
+    rx.push_front(payload);
+    assert_eq!(rx.close_and_drain().await, 2);
+    assert_eq!(first.recv().await, Err(ReceiveError::RecvError));
+    assert_eq!(second.recv().await, Err(ReceiveError::RecvError));
+    assert_eq!(tx.send(3).await.map(|_| ()), Err(SendError(3)));
+}
+
+#[tokio::test]
+async fn unbounded_close_and_drain() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut response_receiver = tx.send(1).unwrap();
+    assert_eq!(rx.close_and_drain().await, 1);
+    assert_eq!(response_receiver.recv().await, Err(ReceiveError::RecvError));
+    assert!(tx.is_closed());
+}
+
+#[tokio::test]
+async fn bounded_await_idle() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    tx.await_idle().await;
+    let mut first = tx.send(1).await.unwrap();
+    let second = tx.clone().send(2).await.unwrap();
+    assert_eq!(tx.pending_responses(), 2);
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input).is_ok());
+    assert_eq!(tx.pending_responses(), 1);
+    let idle = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.await_idle().await }
+    });
+    let (_, _responder) = rx.recv().await.unwrap();
+    second.cancel();
+    idle.await.unwrap();
+    assert_eq!(tx.pending_responses(), 0);
+    assert_eq!(first.recv().await, Ok(1));
+}
+
+#[tokio::test]
+async fn unbounded_await_idle() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let _response_receiver = tx.send(1).unwrap();
+    assert_eq!(tx.pending_responses(), 1);
+    drop(rx.recv().await.unwrap());
+    tx.await_idle().await;
+    assert_eq!(tx.pending_responses(), 0);
+}
+
+#[tokio::test]
+async fn bounded_serve_catch_unwind() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let server = tokio::spawn(rx.serve_catch_unwind(|input| {
+        if input < 0 {
+            panic!("negative input");
+        }
+        async move {
+            if input == 0 {
+                panic!("zero input");
+            }
+            input * 2
+        }
+    }));
+    assert_eq!(
+        tx.send_receive(-1).await,
+        Err(RequestError::HandlerPanicked)
+    );
+    assert_eq!(tx.send_receive(0).await, Err(RequestError::HandlerPanicked));
+    assert_eq!(tx.send_receive(2).await, Ok(4));
+    drop(tx);
+    let report = server.await.unwrap();
+    assert_eq!((report.responded, report.panicked), (1, 2));
+}
+
+#[tokio::test]
+async fn unbounded_serve_catch_unwind() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    tokio::spawn(rx.serve_catch_unwind(|input| async move {
+        assert!(input > 0, "non-positive input");
+        input
+    }));
+    let response_receiver = tx.send(0).unwrap();
+    assert_eq!(response_receiver.await, Err(ReceiveError::HandlerPanicked));
+    assert_eq!(tx.send_receive(1).await, Ok(1));
+}
+
+#[tokio::test]
+async fn bounded_box_request_sender() {
+    let (tx, rx) = bmrng::typed_channel::<Lookup>(1);
+    tokio::spawn(rx.serve(|input: Lookup| async move { input.0 * 2 }));
+    let sender: bmrng::BoxRequestSender<Lookup> = Box::new(tx);
+    assert!(!sender.is_closed());
+    assert_eq!(sender.send_receive(Lookup(21)).await.ok(), Some(42));
+    let response_receiver = sender.send(Lookup(1)).await.unwrap();
+    assert_eq!(response_receiver.await, Ok(2));
+}
+
+#[tokio::test]
+async fn unbounded_box_request_sender() {
+    let (tx, rx) = bmrng::unbounded_typed_channel::<Lookup>();
+    let sender: bmrng::BoxRequestSender<Lookup> = Box::new(tx);
+    assert!(!sender.is_closed());
+    drop(rx);
+    assert!(sender.is_closed());
+    assert_eq!(sender.send_receive(Lookup(1)).await.ok(), None);
+    assert!(matches!(
+        sender.send(Lookup(1)).await,
+        Err(SendError(Lookup(1)))
+    ));
+}
+
+#[tokio::test]
+async fn bounded_builder_send_timeout() {
+    pause();
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .send_timeout(Duration::from_millis(100))
+        .name("numbers")
+        .build();
+    assert_eq!(tx.name(), Some("numbers"));
+    assert!(format!("{:?}", tx).contains("numbers"));
+    let _response_receiver = tx.send(1).await.unwrap();
+    assert!(matches!(
+        tx.send_or_reject(2).await,
+        Err(RequestError::SendTimeoutError(2))
+    ));
+    assert!(matches!(tx.send(3).await, Err(SendError(3))));
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_builder_response_timeout() {
+    pause();
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .response_timeout(Duration::from_millis(100))
+        .build_unbounded();
+    assert_eq!(tx.name(), None);
+    assert_eq!(
+        tx.send_receive(1).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_builder_on_late_response() {
+    pause();
+    let late = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let (tx, rx) = bmrng::builder::<u64, u64>()
+        .capacity(1)
+        .response_timeout(Duration::from_millis(100))
+        .on_late_response({
+            let late = late.clone();
+            move |id, response| late.lock().unwrap().push((id.as_u64(), *response))
+        })
+        .build();
+    tokio::spawn(rx.serve(|input| async move {
+        sleep(Duration::from_millis(input)).await;
+        input
+    }));
+    assert_eq!(tx.send_receive(50).await, Ok(50));
+    assert_eq!(
+        tx.send_receive(150).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    sleep(Duration::from_millis(100)).await;
+    assert_eq!(*late.lock().unwrap(), vec![(2, 150)]);
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_builder_on_late_response() {
+    pause();
+    let late = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .response_timeout(Duration::from_millis(100))
+        .on_late_response({
+            let late = late.clone();
+            move |id, response| late.lock().unwrap().push((id.as_u64(), *response))
+        })
+        .build_unbounded();
+    let cancelled = tx.send(1).unwrap();
+    let timed_out = tx.send(2).unwrap();
+    cancelled.cancel();
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input).is_err());
+    advance(Duration::from_millis(150)).await;
+    assert_eq!(timed_out.await, Err(ReceiveError::TimeoutError));
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(matches!(responder.respond(input), Err(RespondError(2, ..))));
+    assert_eq!(*late.lock().unwrap(), vec![(2, 2)]);
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_channel_with_timeouts() {
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeouts::<i32, i32>(
+        1,
+        Duration::from_millis(50),
+        Duration::from_millis(100),
+    );
+    let _response_receiver = tx.send(1).await.unwrap();
+    assert_eq!(
+        tx.send_receive(2).await,
+        Err(RequestError::SendTimeoutError(2))
+    );
+    let _payload = rx.recv().await.unwrap();
+    assert_eq!(
+        tx.send_receive(3).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    drop(rx);
+    assert_eq!(tx.send_receive(4).await, Err(RequestError::SendError(4)));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_with_ttl() {
+    pause();
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let stale = tx
+        .send_with_ttl(1, Duration::from_millis(50))
+        .await
+        .unwrap();
+    let fresh = tx.send(2).await.unwrap();
+    advance(Duration::from_millis(100)).await;
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 2);
+    assert!(responder.respond(input).is_ok());
+    assert_eq!(stale.await, Err(ReceiveError::Expired));
+    assert_eq!(fresh.await, Ok(2));
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_builder_ttl() {
+    pause();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .ttl(Duration::from_millis(50))
+        .build_unbounded();
+    let stale = tx.send(1).unwrap();
+    advance(Duration::from_millis(100)).await;
+    let fresh = tx.send_with_ttl(2, Duration::from_secs(1)).unwrap();
+    let mut buffer = Vec::new();
+    assert_eq!(rx.recv_many(&mut buffer, 4).await, 1);
+    assert_eq!(buffer[0].0, 2);
+    assert_eq!(stale.await, Err(ReceiveError::Expired));
+    drop(buffer);
+    assert_eq!(fresh.await, Err(ReceiveError::RecvError));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_builder_timeout_on_dequeue() {
+    pause();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(2)
+        .response_timeout(Duration::from_millis(100))
+        .timeout_on_dequeue()
+        .build();
+    let waiting = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(1).await }
+    });
+    while tx.is_empty() {
+        tokio::task::yield_now().await;
+    }
+    let mut timed_out = tx.send(2).await.unwrap();
+    advance(Duration::from_millis(150)).await;
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.time_remaining(), Some(Duration::from_millis(100)));
+    advance(Duration::from_millis(50)).await;
+    responder.respond(input * 2).unwrap();
+    assert_eq!(waiting.await.unwrap(), Ok(2));
+
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(timed_out.recv().await, Err(ReceiveError::TimeoutError));
+    assert!(responder.respond(4).is_err());
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_builder_timeout_on_dequeue() {
+    pause();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .response_timeout(Duration::from_millis(100))
+        .timeout_on_dequeue()
+        .build_unbounded();
+    let response = tx.send(1).unwrap();
+    advance(Duration::from_millis(150)).await;
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(response.await, Ok(1));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_receive_retained() {
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeout::<String, usize>(1, Duration::from_millis(100));
+    let request = tokio::spawn({
+        let tx = tx.clone();
+        async move {
+            tx.send_receive_retained("slow".to_string(), Clone::clone)
+                .await
+        }
+    });
+    let (_, responder) = rx.recv().await.unwrap();
+    advance(Duration::from_millis(150)).await;
+    assert_eq!(
+        request.await.unwrap(),
+        Err(RetainedError {
+            error: RequestError::RecvTimeoutError(ErrorTrace::capture()),
+            retained: Some("slow".to_string())
+        })
+    );
+    drop(responder);
+
+    let request = tokio::spawn(async move {
+        tx.send_receive_retained("fast".to_string(), |input| input.len())
+            .await
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input.len()).unwrap();
+    assert_eq!(request.await.unwrap(), Ok(4));
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_send_receive_retained() {
+    pause();
+    let (tx, rx) =
+        bmrng::unbounded_channel_with_timeout::<Vec<i32>, i32>(Duration::from_millis(100));
+    assert_eq!(
+        tx.send_receive_retained(vec![7, 8], |input| input[0]).await,
+        Err(RetainedError {
+            error: RequestError::RecvTimeoutError(ErrorTrace::capture()),
+            retained: Some(7)
+        })
+    );
+    drop(rx);
+    assert_eq!(
+        tx.send_receive_retained(vec![8], |input| input[0]).await,
+        Err(RetainedError {
+            error: RequestError::SendError(vec![8]),
+            retained: None
+        })
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_builder_max_outstanding() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .max_outstanding(1)
+        .build();
+    let first = tx.send(1).await.unwrap();
+    assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+    let second = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive(3).await }
+    });
+
+    let (input, responder) = rx.recv().await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(tx.is_empty());
+    responder.respond(input).unwrap();
+    assert_eq!(first.await, Ok(1));
+
+    let (input, responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 3);
+    responder.respond(input).unwrap();
+    assert_eq!(second.await.unwrap(), Ok(3));
+
+    let dropped = tx.send(4).await.unwrap();
+    drop(dropped);
+    let permit = tx.reserve().await.unwrap();
+    assert!(matches!(tx.try_send(5), Err(TrySendError::Full(5))));
+    drop(permit);
+    assert!(tx.try_send(6).is_ok());
+}
+
+#[tokio::test]
+async fn bounded_with_quota() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(8);
+    let tenant = tx.with_quota(2);
+    assert_eq!(tx.available_quota(), None);
+    let first = tenant.send(1).await.unwrap();
+    let second = tenant.clone().try_send(2).unwrap();
+    assert_eq!(tenant.available_quota(), Some(0));
+    assert!(matches!(tenant.try_send(3), Err(TrySendError::Full(3))));
+    let weak = tenant.downgrade();
+    assert!(matches!(
+        weak.upgrade().unwrap().try_send(4),
+        Err(TrySendError::Full(4))
+    ));
+    let _unlimited = tx.try_send(5).unwrap();
+
+    let third = tokio::spawn({
+        let tenant = tenant.clone();
+        async move { tenant.send_receive(6).await }
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    responder.respond(input).unwrap();
+    assert_eq!(first.await, Ok(1));
+    drop(second);
+    for expected in [2, 5, 6] {
+        let (input, responder) = rx.recv().await.unwrap();
+        assert_eq!(input, expected);
+        let _ = responder.respond(input);
+    }
+    assert_eq!(third.await.unwrap(), Ok(6));
+    assert_eq!(tenant.available_quota(), Some(2));
+}
+
+/// A clock that only moves forward when the test advances it
+struct ManualClock(tokio::sync::watch::Sender<tokio::time::Instant>);
+
+impl ManualClock {
+    fn advance(&self, duration: Duration) {
+        self.0.send_modify(|now| *now += duration);
+    }
+}
+
+impl bmrng::Clock for ManualClock {
+    fn now(&self) -> tokio::time::Instant {
+        *self.0.borrow()
+    }
+
+    fn sleep_until(&self, deadline: tokio::time::Instant) -> bmrng::ClockSleep {
+        let mut now = self.0.subscribe();
+        Box::pin(async move {
+            let _ = now.wait_for(|now| *now >= deadline).await;
+        })
+    }
+}
+
+#[tokio::test]
+async fn bounded_builder_clock() {
+    let clock = std::sync::Arc::new(ManualClock(
+        tokio::sync::watch::channel(tokio::time::Instant::now()).0,
+    ));
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .response_timeout(Duration::from_secs(60))
+        .ttl(Duration::from_secs(10))
+        .clock(clock.clone())
+        .build();
+    let mut response = tx.send(1).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    clock.advance(Duration::from_secs(20));
+    assert_eq!(responder.time_remaining(), Some(Duration::from_secs(40)));
+    let waiting = tokio::spawn(async move { response.recv().await });
+    clock.advance(Duration::from_secs(40));
+    assert_eq!(waiting.await.unwrap(), Err(ReceiveError::TimeoutError));
+    assert!(responder.is_closed());
+
+    let expired = tx.send(2).await.unwrap();
+    clock.advance(Duration::from_secs(10));
+    let response = tx.send(3).await.unwrap();
+    let (input, _responder) = rx.recv().await.unwrap();
+    assert_eq!(input, 3);
+    assert_eq!(expired.await, Err(ReceiveError::Expired));
+    let waiting = tokio::spawn(async move { response.await });
+    clock.advance(Duration::from_secs(60));
+    assert_eq!(waiting.await.unwrap(), Err(ReceiveError::TimeoutError));
+}
+
+#[tokio::test]
+async fn bounded_builder_clock_send_timeout() {
+    let start = tokio::time::Instant::now();
+    let clock = std::sync::Arc::new(ManualClock(tokio::sync::watch::channel(start).0));
+    let (dead_letters, mut janitor) = bmrng::dead_letter::queue::<i32>();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .rate_limit(1, 1)
+        .send_timeout(Duration::from_secs(10))
+        .ttl(Duration::from_secs(5))
+        .dead_letters(dead_letters)
+        .clock(clock.clone())
+        .build();
+    let _first = tx.try_send(1).unwrap();
+    assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+    let waiting = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_or_reject(2).await.map(drop) }
+    });
+    tokio::task::yield_now().await;
+    clock.advance(Duration::from_secs(1));
+    tokio::task::yield_now().await;
+    assert!(!waiting.is_finished());
+    clock.advance(Duration::from_secs(10));
+    assert!(matches!(
+        waiting.await.unwrap(),
+        Err(RequestError::SendTimeoutError(2))
+    ));
+
+    assert_eq!(rx.try_recv().map(|_| ()), Err(TryRecvError::Empty));
+    let letter = janitor.try_recv().unwrap();
+    assert_eq!(letter.sent_at(), start);
+    assert_eq!(letter.dead_at(), start + Duration::from_secs(11));
+}
+
+#[tokio::test]
+async fn bounded_builder_clock_hedging() {
+    let clock = std::sync::Arc::new(ManualClock(
+        tokio::sync::watch::channel(tokio::time::Instant::now()).0,
+    ));
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .clock(clock.clone())
+        .build();
+    let hedged = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send_receive_hedged(1, Duration::from_secs(5)).await }
+    });
+    let (first, _first_responder) = rx.recv().await.unwrap();
+    tokio::task::yield_now().await;
+    assert!(rx.is_empty());
+    clock.advance(Duration::from_secs(5));
+    let (second, responder) = rx.recv().await.unwrap();
+    assert_eq!((first, second), (1, 1));
+    responder.respond(10).unwrap();
+    assert_eq!(hedged.await.unwrap(), Ok(10));
+}
+
+#[tokio::test]
+async fn unbounded_builder_clock_recv_timeout() {
+    let clock = std::sync::Arc::new(ManualClock(
+        tokio::sync::watch::channel(tokio::time::Instant::now()).0,
+    ));
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .clock(clock.clone())
+        .build_unbounded();
+    let waiting = tokio::spawn(async move {
+        let result = rx.recv_timeout(Duration::from_secs(30)).await;
+        (rx, result.map(|(input, _)| input))
+    });
+    tokio::task::yield_now().await;
+    assert!(!waiting.is_finished());
+    clock.advance(Duration::from_secs(30));
+    let (mut rx, result) = waiting.await.unwrap();
+    assert_eq!(result, Err(RecvTimeoutError::Timeout));
+    let _response = tx.send(1).unwrap();
+    let result = rx.recv_timeout(Duration::from_secs(30)).await;
+    assert_eq!(result.map(|(input, _)| input), Ok(1));
+}
+
+#[tokio::test]
+async fn bounded_builder_rate_limit() {
+    pause();
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(8)
+        .rate_limit(10, 2)
+        .send_timeout(Duration::from_millis(150))
+        .build();
+    let start = tokio::time::Instant::now();
+    let _first = tx.try_send(1).unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert!(matches!(tx.try_send(3), Err(TrySendError::Full(3))));
+    let _third = tx.send(4).await.unwrap();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+    assert!(start.elapsed() < Duration::from_millis(110));
+
+    let _fourth = tx.send(5).await.unwrap();
+    let paced = start.elapsed();
+    assert!(paced >= Duration::from_millis(200));
+    assert!(paced < Duration::from_millis(210));
+    let _fifth = tokio::spawn({
+        let tx = tx.clone();
+        async move { tx.send(6).await.map(drop) }
+    });
+    tokio::task::yield_now().await;
+    assert!(matches!(
+        tx.send_timeout(7, Duration::from_millis(50)).await,
+        Err(SendTimeoutError::Timeout(7))
+    ));
+    assert!(matches!(
+        tx.send_or_reject(8).await,
+        Err(RequestError::SendTimeoutError(8))
+    ));
+    assert_eq!(start.elapsed(), paced);
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_builder_rate_limit_burst() {
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(8)
+        .rate_limit(1, 3)
+        .send_timeout(Duration::from_millis(50))
+        .build();
+    let _first = tx.try_send(1).unwrap();
+    let _second = tx.try_send(2).unwrap();
+    let _third = tx.try_send(3).unwrap();
+    assert!(matches!(tx.try_send(4), Err(TrySendError::Full(4))));
+    assert!(matches!(tx.reserve().await, Err(SendError(()))));
+    assert!(matches!(
+        tx.clone().reserve_owned().await,
+        Err(SendError(()))
+    ));
+}
+
+#[tokio::test]
+async fn bounded_builder_rate_limit_full_queue() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(1)
+        .rate_limit(1, 2)
+        .build();
+    let _first = tx.try_send(1).unwrap();
+    assert!(matches!(tx.try_send(2), Err(TrySendError::Full(2))));
+    assert!(matches!(tx.try_send(3), Err(TrySendError::Full(3))));
+    let _ = rx.recv().await.unwrap();
+    let _fourth = tx.try_send(4).unwrap();
+    drop(rx);
+    assert!(matches!(tx.try_send(5), Err(TrySendError::Closed(5))));
+}
+
+#[tokio::test]
+async fn bounded_admission_max_depth() {
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(bmrng::Admission::new().max_depth(1))
+        .build();
+    let _first = tx.send(1).await.unwrap();
+    let _second = tx.send(2).await.unwrap();
+    assert_eq!(tx.send_receive(3).await, Err(RequestError::Rejected(3)));
+    assert!(matches!(tx.try_send(4), Err(TrySendError::Rejected(4))));
+    assert!(matches!(
+        tx.send_timeout(5, Duration::from_millis(10)).await,
+        Err(SendTimeoutError::Rejected(5))
+    ));
+    assert!(matches!(
+        tx.send_with_ttl(6, Duration::from_millis(10)).await,
+        Err(SendError(6))
+    ));
+    assert!(matches!(
+        tx.send_or_reject(7).await,
+        Err(RequestError::Rejected(7))
+    ));
+    assert!(matches!(tx.reserve().await, Err(SendError(()))));
+    let _payload = rx.recv().await.unwrap();
+    assert!(tx.try_send(7).is_ok());
+}
+
+#[tokio::test]
+async fn bounded_admission_max_age() {
+    pause();
+    let (tx, mut rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(bmrng::Admission::new().max_age(Duration::from_millis(100)))
+        .build();
+    let _first = tx.send(1).await.unwrap();
+    advance(Duration::from_millis(50)).await;
+    let _second = tx.send(2).await.unwrap();
+    advance(Duration::from_millis(100)).await;
+    assert!(matches!(
+        tx.send_or_reject(3).await,
+        Err(RequestError::Rejected(3))
+    ));
+    let _payload = rx.recv().await.unwrap();
+    assert!(tx.send(4).await.is_ok());
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_admission_max_age_clock() {
+    let clock = std::sync::Arc::new(ManualClock(
+        tokio::sync::watch::channel(tokio::time::Instant::now()).0,
+    ));
+    let (tx, _rx) = bmrng::builder::<i32, i32>()
+        .capacity(4)
+        .admission(bmrng::Admission::new().max_age(Duration::from_secs(10)))
+        .clock(clock.clone())
+        .build();
+    let _first = tx.send(1).await.unwrap();
+    assert!(tx.send(2).await.is_ok());
+    clock.advance(Duration::from_secs(20));
+    assert!(matches!(
+        tx.send_or_reject(3).await,
+        Err(RequestError::Rejected(3))
+    ));
+}
+
+#[tokio::test]
+async fn bounded_send_receive_with_retry() {
+    pause();
+    let (tx, mut rx) = bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(100));
+    tokio::spawn(async move {
+        let mut attempts = 0;
+        while let Ok((input, responder)) = rx.recv().await {
+            attempts += 1;
+            if attempts == 3 {
+                let _ = responder.respond(input * 2);
+            }
+        }
+    });
+    let policy = bmrng::RetryPolicy::new(3).backoff(bmrng::Backoff::Exponential {
+        initial: Duration::from_millis(10),
+        max: Duration::from_secs(1),
+    });
+    assert_eq!(tx.send_receive_with_retry(21, &policy).await, Ok(42));
+    let policy = bmrng::RetryPolicy::new(2);
+    assert_eq!(
+        tx.send_receive_with_retry(1, &policy).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    resume();
+}
+
+#[tokio::test]
+async fn unbounded_send_receive_with_retry_closed() {
+    let (tx, rx) = bmrng::unbounded_channel::<i32, i32>();
+    drop(rx);
+    let policy = bmrng::RetryPolicy::new(3);
+    assert_eq!(
+        tx.send_receive_with_retry(1, &policy).await,
+        Err(RequestError::SendError(1))
+    );
+}
+
+#[tokio::test]
+async fn bounded_send_receive_hedged() {
+    pause();
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(2);
+    let server = tokio::spawn(async move {
+        let (input, mut slow) = rx.recv().await.unwrap();
+        let (_, fast) = rx.recv().await.unwrap();
+        assert!(fast.respond(input * 2).is_ok());
+        slow.closed().await;
+        slow.cancel_reason()
+    });
+    let response = tx.send_receive_hedged(21, Duration::from_millis(50)).await;
+    assert_eq!(response, Ok(42));
+    assert_eq!(server.await.unwrap(), Some(CancelReason::Cancelled));
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_receive_hedged_early_failure() {
+    pause();
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(2);
+    let server = tokio::spawn(async move {
+        let (_, failed) = rx.recv().await.unwrap();
+        drop(failed);
+        let (input, hedge) = rx.recv().await.unwrap();
+        assert!(hedge.respond(input * 2).is_ok());
+    });
+    let start = tokio::time::Instant::now();
+    let response = tx.send_receive_hedged(21, Duration::from_secs(60)).await;
+    assert_eq!(response, Ok(42));
+    assert!(start.elapsed() < Duration::from_secs(60));
+    server.await.unwrap();
+    resume();
+}
+
+#[tokio::test]
+async fn bounded_send_receive_hedged_fast() {
+    let (tx, rx) = bmrng::channel::<i32, i32>(2);
+    tokio::spawn(rx.serve(|input| async move { input + 1 }));
+    let response = tx.send_receive_hedged(1, Duration::from_secs(60)).await;
+    assert_eq!(response, Ok(2));
+}
+
+#[tokio::test]
+async fn bounded_responder_forward() {
+    let (upstream_tx, mut upstream_rx) = bmrng::channel::<i32, i32>(1);
+    let (downstream_tx, downstream_rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(downstream_rx.serve(|input| async move { input * 3 }));
+    let proxy = tokio::spawn(async move {
+        let (input, responder) = upstream_rx.recv().await.expect("Unexpected err");
+        let receiver = downstream_tx.send(input).await.expect("Unexpected err");
+        responder.forward(receiver).await
+    });
+    assert_eq!(upstream_tx.send_receive(5).await, Ok(15));
+    assert!(proxy.await.expect("Unexpected err"));
+}
+
+#[tokio::test]
+async fn bounded_responder_forward_errors() {
+    let (upstream_tx, mut upstream_rx) = bmrng::channel::<i32, i32>(1);
+    let (downstream_tx, mut downstream_rx) =
+        bmrng::channel_with_timeout::<i32, i32>(1, Duration::from_millis(10));
+    tokio::spawn(async move {
+        let (_, _timed_out) = downstream_rx.recv().await.expect("Unexpected err");
+        let (_, dropped) = downstream_rx.recv().await.expect("Unexpected err");
+        drop(dropped);
+        sleep(Duration::from_millis(100)).await;
+    });
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = upstream_rx.recv().await {
+            let receiver = downstream_tx.send(input).await.expect("Unexpected err");
+            assert!(!responder.forward(receiver).await);
+        }
+    });
+    assert_eq!(
+        upstream_tx.send_receive(1).await,
+        Err(RequestError::RecvTimeoutError(ErrorTrace::capture()))
+    );
+    assert_eq!(
+        upstream_tx.send_receive(2).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+}
+
+#[tokio::test]
+async fn bounded_responder_forward_cancelled() {
+    let (upstream_tx, mut upstream_rx) = bmrng::channel::<i32, i32>(1);
+    let (downstream_tx, mut downstream_rx) = bmrng::channel::<i32, i32>(1);
+    let handler = tokio::spawn(async move {
+        let (_, mut responder) = downstream_rx.recv().await.expect("Unexpected err");
+        responder.closed().await;
+        responder.cancel_reason()
+    });
+    let proxy = tokio::spawn(async move {
+        let (input, responder) = upstream_rx.recv().await.expect("Unexpected err");
+        let receiver = downstream_tx.send(input).await.expect("Unexpected err");
+        responder.forward(receiver).await
+    });
+    let receiver = upstream_tx.send(1).await.expect("Unexpected err");
+    sleep(Duration::from_millis(10)).await;
+    drop(receiver);
+    assert!(!proxy.await.expect("Unexpected err"));
+    assert_eq!(
+        handler.await.expect("Unexpected err"),
+        Some(CancelReason::Cancelled)
+    );
+}
+
+#[tokio::test]
+async fn bounded_responder_respond_with() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        let (input, responder) = rx.recv().await.expect("Unexpected err");
+        assert!(responder.respond_with(async move { input * 2 }).await);
+        let (input, responder) = rx.recv().await.expect("Unexpected err");
+        assert!(responder.respond_with_sync(|| input + 1));
+        let (input, responder) = rx.recv().await.expect("Unexpected err");
+        assert!(!responder.respond_with_sync(|| input));
+    });
+    assert_eq!(tx.send_receive(4).await, Ok(8));
+    assert_eq!(tx.send_receive(4).await, Ok(5));
+    drop(tx.send(4).await.expect("Unexpected err"));
+}
+
+#[tokio::test]
+async fn unbounded_guarded_responder_respond_with() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let response_receiver = tx.send(3).unwrap();
+    let (input, responder) = rx.recv().await.unwrap();
+    let guarded = responder.or_else_on_drop(-1);
+    assert!(guarded.respond_with(async move { input * input }).await);
+    assert_eq!(response_receiver.await, Ok(9));
+
+    let response_receiver = tx.send(3).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    let guarded = responder.or_else_on_drop(-1);
+    let handler = guarded.respond_with(std::future::pending());
+    assert!(tokio::time::timeout(Duration::from_millis(10), handler)
+        .await
+        .is_err());
+    assert_eq!(response_receiver.await, Ok(-1));
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Get(u32),
+    Put(u32),
+}
+
+#[tokio::test]
+async fn bounded_stream_map_request() {
+    use bmrng::PayloadStreamExt;
+
+    let (tx, rx) = bmrng::channel::<Command, u32>(4);
+    let stream = RequestReceiverStream::new(rx).filter_map_request(|command| match command {
+        Command::Get(key) => Some(key),
+        Command::Put(..) => None,
+    });
+    tokio::spawn(async move {
+        let mut stream = Box::pin(stream);
+        while let Some((key, responder)) = stream.next().await {
+            let _ = responder.respond(key * 10);
+        }
+    });
+    assert_eq!(tx.send_receive(Command::Get(1)).await, Ok(10));
+    assert_eq!(
+        tx.send_receive(Command::Put(2)).await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+
+    let (tx, rx) = bmrng::channel::<u32, u32>(4);
+    let mut stream = Box::pin(RequestReceiverStream::new(rx).map_request(|input| input + 1));
+    let response = tx.send(1).await.unwrap();
+    let (input, responder) = stream.next().await.unwrap();
+    let _ = responder.respond(input);
+    assert_eq!(response.await, Ok(2));
+}
+
+#[tokio::test]
+async fn unbounded_stream_split_by() {
+    use bmrng::PayloadStreamExt;
+
+    let (tx, rx) = bmrng::unbounded_channel::<Command, u32>();
+    let (mut gets, mut puts) = UnboundedRequestReceiverStream::new(rx)
+        .split_by(|command| matches!(command, Command::Get(..)));
+    let get_task = tokio::spawn(async move {
+        while let Some((command, responder)) = gets.next().await {
+            assert!(matches!(command, Command::Get(..)));
+            let _ = responder.respond(1);
+        }
+    });
+    let put_task = tokio::spawn(async move {
+        while let Some((command, responder)) = puts.next().await {
+            assert!(matches!(command, Command::Put(..)));
+            let _ = responder.respond(2);
+        }
+    });
+    for key in 0..5 {
+        assert_eq!(tx.send_receive(Command::Put(key)).await, Ok(2));
+        assert_eq!(tx.send_receive(Command::Get(key)).await, Ok(1));
+    }
+    drop(tx);
+    get_task.await.unwrap();
+    put_task.await.unwrap();
+}
+
+#[tokio::test]
+async fn bounded_stream_split_by_dropped_half() {
+    use bmrng::PayloadStreamExt;
+
+    let (tx, rx) = bmrng::channel::<Command, u32>(4);
+    let (mut gets, puts) =
+        RequestReceiverStream::new(rx).split_by(|command| matches!(command, Command::Get(..)));
+    drop(puts);
+    let put = tx.send(Command::Put(1)).await.unwrap();
+    let get = tx.send(Command::Get(2)).await.unwrap();
+    let (command, responder) = gets.next().await.unwrap();
+    assert_eq!(command, Command::Get(2));
+    let _ = responder.respond(2);
+    assert_eq!(get.await, Ok(2));
+    assert_eq!(put.await, Err(ReceiveError::RecvError));
+}
+
+#[tokio::test]
+async fn bounded_merge_round_robin() {
+    let (first, first_rx) = bmrng::channel::<i32, i32>(4);
+    let (second, second_rx) = bmrng::channel::<i32, i32>(4);
+    let mut merged = bmrng::merge(vec![first_rx, second_rx]);
+    let mut receivers = Vec::new();
+    for input in 0..3 {
+        receivers.push(first.send(input).await.unwrap());
+    }
+    receivers.push(second.send(10).await.unwrap());
+    let mut order = Vec::new();
+    for _ in 0..4 {
+        let (input, responder) = merged.next().await.unwrap();
+        order.push(input);
+        let _ = responder.respond(input);
+    }
+    assert_eq!(order, vec![0, 10, 1, 2]);
+    drop((first, second));
+    assert!(merged.next().await.is_none());
+    assert!(merged.is_empty());
+}
+
+#[tokio::test]
+async fn unbounded_merge_biased() {
+    let (first, first_rx) = bmrng::unbounded_channel::<i32, i32>();
+    let (second, second_rx) = bmrng::unbounded_channel::<i32, i32>();
+    let mut merged = bmrng::unbounded::merge(vec![first_rx, second_rx]).biased();
+    let _second = second.send(10).unwrap();
+    let _first = (first.send(0).unwrap(), first.send(1).unwrap());
+    let mut order = Vec::new();
+    for _ in 0..3 {
+        order.push(merged.next().await.unwrap().0);
+    }
+    assert_eq!(order, vec![0, 1, 10]);
+    drop(first);
+    assert_eq!(merged.len(), 2);
+    let _ = second.send(11).unwrap();
+    assert_eq!(merged.next().await.unwrap().0, 11);
+    assert_eq!(merged.len(), 1);
+}
+
+#[tokio::test]
+async fn bounded_shared_response_receiver() {
+    let (tx, mut rx) = bmrng::channel::<i32, String>(1);
+    let shared = tx.send(3).await.unwrap().shared();
+    let tasks: Vec<_> = (0..3).map(|_| tokio::spawn(shared.clone())).collect();
+    assert_eq!(shared.peek(), None);
+    let (input, responder) = rx.recv().await.unwrap();
+    let _ = responder.respond(input.to_string());
+    for task in tasks {
+        assert_eq!(task.await.unwrap(), Ok("3".to_string()));
+    }
+    assert_eq!(shared.peek(), Some(Ok("3".to_string())));
+    assert_eq!(shared.await, Ok("3".to_string()));
+}
+
+#[tokio::test]
+async fn unbounded_shared_response_receiver_timeout() {
+    let (tx, mut rx) =
+        bmrng::unbounded_channel_with_timeout::<i32, i32>(Duration::from_millis(100));
+    let shared = tx.send(1).unwrap().shared();
+    let (_, responder) = rx.recv().await.unwrap();
+    pause();
+    let first = tokio::spawn(shared.clone());
+    advance(Duration::from_millis(101)).await;
+    resume();
+    assert_eq!(first.await.unwrap(), Err(ReceiveError::TimeoutError));
+    assert_eq!(shared.await, Err(ReceiveError::TimeoutError));
+    assert_eq!(responder.cancel_reason(), Some(CancelReason::TimedOut));
+}
+
+#[tokio::test]
+async fn bounded_shared_response_receiver_dropped() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let shared = tx.send(1).await.unwrap().shared();
+    let clone = shared.clone();
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(shared);
+    assert!(!responder.is_closed());
+    drop(clone);
+    assert!(responder.is_closed());
+}
+
+#[tokio::test]
+async fn bounded_request_ids_and_context() {
+    use bmrng::PayloadExt;
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(4);
+    let first = tx.send(1).await.unwrap();
+    let second = tx.clone().send(2).await.unwrap();
+    assert!(first.request_id() < second.request_id());
+    assert_eq!(first.request_id().as_u64(), 1);
+    assert_eq!(first.context(), None);
+
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(payload.id(), Some(first.request_id()));
+    assert_eq!(payload.context(), None);
+    let payload = rx.recv().await.unwrap();
+    assert_eq!(payload.1.request_id(), Some(second.request_id()));
+
+    let mut context = bmrng::RequestContext::new();
+    context.insert("trace_id".to_string(), "abc".to_string());
+    let third = tx.send_with_context(3, context.clone()).await.unwrap();
+    assert_eq!(third.context(), Some(&context));
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.request_id(), Some(third.request_id()));
+    assert_eq!(
+        responder
+            .context()
+            .and_then(|context| context.get("trace_id")),
+        Some(&"abc".to_string())
+    );
+
+    tx.send_forget(4).await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.request_id(), None);
+}
+
+#[tokio::test]
+async fn unbounded_request_ids_and_context() {
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let first = tx.send(1).unwrap();
+    let mut context = bmrng::RequestContext::new();
+    context.insert("user".to_string(), "7".to_string());
+    let second = tx.send_with_context(2, context.clone()).unwrap();
+    assert_eq!(
+        second.request_id().as_u64(),
+        first.request_id().as_u64() + 1
+    );
+    let _ = rx.recv().await.unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.request_id(), Some(second.request_id()));
+    assert_eq!(responder.context(), Some(&context));
+    assert_eq!(second.request_id().to_string(), "request #2");
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "tower")]
+
+use bmrng::error::{ErrorTrace, RequestError};
+use bmrng::tower::ChannelService;
+use futures_util::future::poll_fn;
+use tower_service::Service;
+
+#[tokio::test]
+async fn tower_call() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        while let Ok((input, responder)) = rx.recv().await {
+            let _ = responder.respond(input * 2);
+        }
+    });
+    let mut service = ChannelService::new(tx);
+    for i in 0..3 {
+        poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+        assert_eq!(service.call(i).await, Ok(i * 2));
+    }
+}
+
+#[tokio::test]
+async fn tower_poll_ready_waits_for_capacity() {
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    let mut service = ChannelService::from(tx);
+    let mut other = service.clone();
+    poll_fn(|cx| service.poll_ready(cx)).await.unwrap();
+    let pending = service.call(1);
+    let ready = tokio::spawn(async move {
+        poll_fn(|cx| other.poll_ready(cx)).await.unwrap();
+        other
+    });
+    let (input, responder) = rx.recv().await.unwrap();
+    assert!(responder.respond(input).is_ok());
+    assert_eq!(pending.await, Ok(1));
+    let mut other = ready.await.unwrap();
+    let pending = other.call(2);
+    drop(rx);
+    assert_eq!(
+        pending.await,
+        Err(RequestError::RecvError(ErrorTrace::capture()))
+    );
+    assert_eq!(
+        poll_fn(|cx| other.poll_ready(cx)).await,
+        Err(RequestError::SendError(()))
+    );
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "tracing")]
+
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+use tracing_core::span::Current;
+
+/// An event, with the name of the span it was recorded in
+type Recorded = (Option<&'static str>, String);
+
+/// Records the name of the span of every event
+#[derive(Clone, Default)]
+struct Recorder {
+    spans: Arc<Mutex<Vec<&'static Metadata<'static>>>>,
+    stack: Arc<Mutex<Vec<u64>>>,
+    events: Arc<Mutex<Vec<Recorded>>>,
+}
+
+struct Message(String);
+
+impl tracing::field::Visit for Message {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(span.metadata());
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let span = match event.parent() {
+            Some(parent) => Some(parent.into_u64()),
+            None if event.is_contextual() => self.stack.lock().unwrap().last().copied(),
+            None => None,
+        };
+        let name = span.map(|id| self.spans.lock().unwrap()[id as usize - 1].name());
+        let mut message = Message(String::new());
+        event.record(&mut message);
+        self.events.lock().unwrap().push((name, message.0));
+    }
+
+    fn enter(&self, span: &Id) {
+        self.stack.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _span: &Id) {
+        self.stack.lock().unwrap().pop();
+    }
+
+    fn current_span(&self) -> Current {
+        match self.stack.lock().unwrap().last() {
+            Some(id) => Current::new(
+                Id::from_u64(*id),
+                self.spans.lock().unwrap()[*id as usize - 1],
+            ),
+            None => Current::none(),
+        }
+    }
+}
+
+#[tokio::test]
+async fn tracing_handler_runs_in_request_span() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let (tx, rx) = bmrng::channel::<i32, i32>(1);
+    let server = rx.serve(|input| async move {
+        tracing::info!("handling");
+        input * 2
+    });
+    let client = async move {
+        let span = tracing::info_span!("client_request");
+        let _entered = span.enter();
+        let response = tx.send_receive(4).await;
+        drop(tx);
+        response
+    };
+    let (response, _) = tokio::join!(client, server);
+    assert_eq!(response, Ok(8));
+
+    let events = recorder.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            (Some("client_request"), "handling".to_string()),
+            (Some("client_request"), "response sent".to_string()),
+            (Some("client_request"), "response received".to_string()),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn tracing_responder_span() {
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let span = tracing::info_span!("outer");
+    let receiver = span.in_scope(|| tx.send(1).unwrap());
+    let (_, responder) = rx.recv().await.unwrap();
+    assert_eq!(responder.span().id(), span.id());
+    assert_eq!(receiver.span().id(), span.id());
+
+    tx.send_forget(2).unwrap();
+    let (_, responder) = rx.recv().await.unwrap();
+    assert!(responder.span().is_none());
+}
+
+#[tokio::test]
+async fn tracing_dynamic_request_span() {
+    struct Ping;
+
+    impl bmrng::Request for Ping {
+        type Response = ();
+    }
+
+    let recorder = Recorder::default();
+    let _guard = tracing::subscriber::set_default(recorder.clone());
+
+    let (tx, mut rx) = bmrng::dynamic::channel(1);
+    let server = async move {
+        let payload = rx.recv().await.unwrap();
+        let (Ping, responder) = payload.downcast::<Ping>().ok().unwrap();
+        responder.respond(()).unwrap();
+    };
+    let (response, _) = tokio::join!(tx.send_receive(Ping), server);
+    assert!(response.is_ok());
+
+    let events = recorder.events.lock().unwrap().clone();
+    assert_eq!(
+        events,
+        vec![
+            (Some("dynamic request"), "response sent".to_string()),
+            (Some("dynamic request"), "response received".to_string()),
+            (
+                Some("dynamic request"),
+                "dynamic request finished".to_string()
+            ),
+        ]
+    );
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "tracing-error")]
+
+use bmrng::error::{ErrorTrace, ReceiveError, RequestError, RespondError};
+use tracing_error::{ErrorLayer, SpanTraceStatus};
+use tracing_subscriber::layer::SubscriberExt;
+
+fn subscriber() -> impl tracing::Subscriber + Send + Sync {
+    tracing_subscriber::registry().with(ErrorLayer::default())
+}
+
+#[tokio::test]
+async fn traced_recv_error_in_request_span() {
+    let _guard = tracing::subscriber::set_default(subscriber());
+
+    let (tx, mut rx) = bmrng::channel::<i32, i32>(1);
+    tokio::spawn(async move {
+        let (_, responder) = rx.recv().await.unwrap();
+        drop(responder);
+    });
+    let span = tracing::info_span!("load_user");
+    let result = tracing::Instrument::instrument(tx.send_receive_traced(1), span).await;
+    let err = result.unwrap_err();
+    assert_eq!(err.error(), &RequestError::RecvError(ErrorTrace::capture()));
+    assert_eq!(err.span_trace().status(), SpanTraceStatus::CAPTURED);
+    assert!(err.span_trace().to_string().contains("load_user"));
+    assert_eq!(err.to_string(), "request channel closed");
+    assert_eq!(
+        err.into_inner(),
+        RequestError::RecvError(ErrorTrace::capture())
+    );
+}
+
+#[tokio::test]
+async fn traced_respond_error_and_send_error() {
+    let _guard = tracing::subscriber::set_default(subscriber());
+
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    let response = tracing::info_span!("notify").in_scope(|| tx.send(1).unwrap());
+    let (_, responder) = rx.recv().await.unwrap();
+    drop(response);
+    let err = responder.respond_traced(2).unwrap_err();
+    assert_eq!(err.error(), &RespondError(2, ErrorTrace::capture()));
+    assert!(err.span_trace().to_string().contains("notify"));
+
+    let mut response = tx.send(3).unwrap();
+    drop(rx);
+    let err = response.recv_traced().await.unwrap_err();
+    assert_eq!(err.error(), &ReceiveError::RecvError);
+    assert!(matches!(
+        tx.send_receive_traced(4)
+            .await
+            .map_err(|err| err.into_inner()),
+        Err(RequestError::SendError(4))
+    ));
+}
+
+#[tokio::test]
+async fn errors_capture_span_trace() {
+    let _guard = tracing::subscriber::set_default(subscriber());
+
+    let (tx, mut rx) =
+        bmrng::channel_with_timeout::<i32, i32>(1, std::time::Duration::from_millis(1));
+    let responder = tokio::spawn(async move {
+        let (_, responder) = rx.recv().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        let span = tracing::info_span!("late_reply");
+        let err = span.in_scope(|| responder.respond(2)).unwrap_err();
+        assert_eq!(err.into_inner(), 2);
+        let err = tracing::Instrument::instrument(rx.recv(), tracing::info_span!("drain"))
+            .await
+            .unwrap_err();
+        assert!(err.span_trace().unwrap().to_string().contains("drain"));
+    });
+    let span = tracing::info_span!("load_user");
+    let err = tracing::Instrument::instrument(tx.send_receive(1), span)
+        .await
+        .unwrap_err();
+    assert!(matches!(err, RequestError::RecvTimeoutError(..)));
+    assert_eq!(
+        err.span_trace().unwrap().status(),
+        SpanTraceStatus::CAPTURED
+    );
+    assert!(err.span_trace().unwrap().to_string().contains("load_user"));
+    assert!(RequestError::SendError(1).span_trace().is_none());
+    drop(tx);
+    responder.await.unwrap();
+}
+
+#[test]
+fn respond_error_captures_span_trace() {
+    let _guard = tracing::subscriber::set_default(subscriber());
+
+    let (tx, mut rx) = bmrng::unbounded_channel::<i32, i32>();
+    drop(tx.send(1).unwrap());
+    let (_, responder) = rx.try_recv().unwrap();
+    let err = tracing::info_span!("reply")
+        .in_scope(|| responder.respond(2))
+        .unwrap_err();
+    assert!(err.span_trace().to_string().contains("reply"));
+    assert_eq!(err.to_string(), "sender closed the response channel");
+}